@@ -194,7 +194,7 @@ const PLAYER_REGISTRY_INSTRUCTIONS = {
             'player_profile',
             'player'
         ],
-        args: ['boss_id', 'boss_level', 'reward_amount']
+        args: ['boss_id', 'boss_level']
     },
     claimDailyReward: {
         name: 'claim_daily_reward',
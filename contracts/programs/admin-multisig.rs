@@ -0,0 +1,495 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+
+declare_id!("AdminMuLtisig1111111111111111111111111111111");
+
+pub const MAX_OWNERS: usize = 10;
+
+#[program]
+pub mod admin_multisig {
+    use super::*;
+
+    /// Create an M-of-N multisig. Its signer PDA can be set as the admin of
+    /// the token and registry programs via their propose/accept authority flow.
+    pub fn create_multisig(
+        ctx: Context<CreateMultisig>,
+        owners: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        validate_owners(&owners, threshold)?;
+
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.create_key = ctx.accounts.create_key.key();
+        multisig.owners = owners;
+        multisig.threshold = threshold;
+        multisig.proposal_count = 0;
+        multisig.owner_set_seqno = 0;
+        multisig.bump = ctx.bumps.multisig;
+        multisig.signer_bump = ctx.bumps.multisig_signer;
+
+        msg!("Multisig created: {}-of-{}", threshold, multisig.owners.len());
+        msg!("Multisig signer: {}", ctx.accounts.multisig_signer.key());
+        Ok(())
+    }
+
+    /// Propose an instruction to be executed by the multisig signer
+    pub fn create_proposal(
+        ctx: Context<CreateProposal>,
+        target_program: Pubkey,
+        accounts: Vec<ProposalAccount>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let owner_index = multisig
+            .owner_index(&ctx.accounts.proposer.key())
+            .ok_or(MultisigError::NotAnOwner)?;
+
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.multisig = multisig.key();
+        proposal.index = multisig.proposal_count;
+        proposal.proposer = ctx.accounts.proposer.key();
+        proposal.target_program = target_program;
+        proposal.accounts = accounts;
+        proposal.data = data;
+        proposal.approvals = vec![false; multisig.owners.len()];
+        proposal.approvals[owner_index] = true;
+        proposal.owner_set_seqno = multisig.owner_set_seqno;
+        proposal.executed = false;
+        proposal.created_at = Clock::get()?.unix_timestamp;
+        proposal.bump = ctx.bumps.proposal;
+
+        multisig.proposal_count = multisig.proposal_count.saturating_add(1);
+
+        emit!(ProposalCreated {
+            multisig: proposal.multisig,
+            proposal: proposal.key(),
+            index: proposal.index,
+            proposer: proposal.proposer,
+            target_program,
+        });
+
+        msg!("Proposal {} created", proposal.index);
+        Ok(())
+    }
+
+    /// Approve a pending proposal
+    pub fn approve_proposal(
+        ctx: Context<ApproveProposal>,
+    ) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let proposal = &mut ctx.accounts.proposal;
+
+        require!(!proposal.executed, MultisigError::AlreadyExecuted);
+        require!(
+            proposal.owner_set_seqno == multisig.owner_set_seqno,
+            MultisigError::StaleProposal
+        );
+
+        let owner_index = multisig
+            .owner_index(&ctx.accounts.owner.key())
+            .ok_or(MultisigError::NotAnOwner)?;
+        proposal.approvals[owner_index] = true;
+
+        emit!(ProposalApproved {
+            multisig: multisig.key(),
+            proposal: proposal.key(),
+            owner: ctx.accounts.owner.key(),
+            approvals: proposal.approval_count() as u8,
+        });
+
+        msg!("Proposal {} approved ({} of {})", proposal.index, proposal.approval_count(), multisig.threshold);
+        Ok(())
+    }
+
+    /// Execute a proposal once it has collected enough approvals
+    pub fn execute_proposal<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteProposal<'info>>,
+    ) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.check_executable(multisig)?;
+
+        // Mark executed and write it back before the CPI, so a target that re-enters
+        // execute_proposal sees the proposal as spent and it can't be replayed
+        proposal.executed = true;
+        proposal.exit(&crate::ID)?;
+
+        let multisig_signer = ctx.accounts.multisig_signer.key();
+        let instruction = Instruction {
+            program_id: proposal.target_program,
+            accounts: proposal
+                .accounts
+                .iter()
+                .map(|account| AccountMeta {
+                    pubkey: account.pubkey,
+                    is_signer: account.is_signer || account.pubkey == multisig_signer,
+                    is_writable: account.is_writable,
+                })
+                .collect(),
+            data: proposal.data.clone(),
+        };
+
+        let multisig_key = multisig.key();
+        let signer_seeds: &[&[u8]] = &[
+            b"multisig_signer",
+            multisig_key.as_ref(),
+            &[multisig.signer_bump],
+        ];
+        invoke_signed(&instruction, ctx.remaining_accounts, &[signer_seeds])?;
+
+        emit!(ProposalExecuted {
+            multisig: multisig_key,
+            proposal: proposal.key(),
+            index: proposal.index,
+        });
+
+        msg!("Proposal {} executed", proposal.index);
+        Ok(())
+    }
+
+    /// Replace the owner set. Only callable by the multisig itself through a proposal.
+    pub fn set_owners_and_threshold(
+        ctx: Context<MultisigAuth>,
+        owners: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        validate_owners(&owners, threshold)?;
+
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.owners = owners;
+        multisig.threshold = threshold;
+        // Pending proposals were approved by the old owner set
+        multisig.owner_set_seqno = multisig.owner_set_seqno.saturating_add(1);
+
+        msg!("Multisig owners updated: {}-of-{}", threshold, multisig.owners.len());
+        Ok(())
+    }
+}
+
+// Helper functions
+fn validate_owners(owners: &[Pubkey], threshold: u8) -> Result<()> {
+    require!(
+        !owners.is_empty() && owners.len() <= MAX_OWNERS,
+        MultisigError::InvalidOwners
+    );
+    require!(
+        threshold > 0 && threshold as usize <= owners.len(),
+        MultisigError::InvalidThreshold
+    );
+
+    for (i, owner) in owners.iter().enumerate() {
+        require!(
+            !owners[..i].contains(owner),
+            MultisigError::DuplicateOwner
+        );
+    }
+    Ok(())
+}
+
+// Account structures
+#[derive(Accounts)]
+pub struct CreateMultisig<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Multisig::INIT_SPACE,
+        seeds = [b"multisig", create_key.key().as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    /// CHECK: PDA that signs executed proposals
+    #[account(seeds = [b"multisig_signer", multisig.key().as_ref()], bump)]
+    pub multisig_signer: UncheckedAccount<'info>,
+
+    pub create_key: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(target_program: Pubkey, accounts: Vec<ProposalAccount>, data: Vec<u8>)]
+pub struct CreateProposal<'info> {
+    #[account(
+        mut,
+        seeds = [b"multisig", multisig.create_key.as_ref()],
+        bump = multisig.bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = Proposal::space(multisig.owners.len(), accounts.len(), data.len()),
+        seeds = [b"proposal", multisig.key().as_ref(), &multisig.proposal_count.to_le_bytes()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveProposal<'info> {
+    #[account(
+        seeds = [b"multisig", multisig.create_key.as_ref()],
+        bump = multisig.bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", multisig.key().as_ref(), &proposal.index.to_le_bytes()],
+        bump = proposal.bump,
+        has_one = multisig
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    #[account(
+        seeds = [b"multisig", multisig.create_key.as_ref()],
+        bump = multisig.bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    /// CHECK: PDA that signs the proposed instruction
+    #[account(
+        seeds = [b"multisig_signer", multisig.key().as_ref()],
+        bump = multisig.signer_bump
+    )]
+    pub multisig_signer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", multisig.key().as_ref(), &proposal.index.to_le_bytes()],
+        bump = proposal.bump,
+        has_one = multisig
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+#[derive(Accounts)]
+pub struct MultisigAuth<'info> {
+    #[account(
+        mut,
+        seeds = [b"multisig", multisig.create_key.as_ref()],
+        bump = multisig.bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        seeds = [b"multisig_signer", multisig.key().as_ref()],
+        bump = multisig.signer_bump
+    )]
+    pub multisig_signer: Signer<'info>,
+}
+
+// Data structures
+#[account]
+#[derive(InitSpace)]
+pub struct Multisig {
+    pub create_key: Pubkey,
+    #[max_len(MAX_OWNERS)]
+    pub owners: Vec<Pubkey>,
+    pub threshold: u8,
+    pub proposal_count: u64,
+    pub owner_set_seqno: u32,
+    pub bump: u8,
+    pub signer_bump: u8,
+}
+
+impl Multisig {
+    pub fn owner_index(&self, owner: &Pubkey) -> Option<usize> {
+        self.owners.iter().position(|o| o == owner)
+    }
+}
+
+#[account]
+pub struct Proposal {
+    pub multisig: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub target_program: Pubkey,
+    pub accounts: Vec<ProposalAccount>,
+    pub data: Vec<u8>,
+    pub approvals: Vec<bool>,
+    pub owner_set_seqno: u32,
+    pub executed: bool,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl Proposal {
+    pub fn space(owners: usize, accounts: usize, data: usize) -> usize {
+        8 + 32 + 8 + 32 + 32
+            + (4 + accounts * ProposalAccount::INIT_SPACE)
+            + (4 + data)
+            + (4 + owners)
+            + 4 + 1 + 8 + 1
+    }
+
+    pub fn approval_count(&self) -> usize {
+        self.approvals.iter().filter(|approved| **approved).count()
+    }
+
+    // Not yet executed, approved by the current owner set, and at the threshold
+    pub fn check_executable(&self, multisig: &Multisig) -> Result<()> {
+        require!(!self.executed, MultisigError::AlreadyExecuted);
+        require!(
+            self.owner_set_seqno == multisig.owner_set_seqno,
+            MultisigError::StaleProposal
+        );
+        require!(
+            self.approval_count() >= multisig.threshold as usize,
+            MultisigError::NotEnoughApprovals
+        );
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct ProposalAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+// Events
+#[event]
+pub struct ProposalCreated {
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub target_program: Pubkey,
+}
+
+#[event]
+pub struct ProposalApproved {
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub owner: Pubkey,
+    pub approvals: u8,
+}
+
+#[event]
+pub struct ProposalExecuted {
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub index: u64,
+}
+
+// Error codes
+#[error_code]
+pub enum MultisigError {
+    #[msg("Owners must be unique and between 1 and 10")]
+    InvalidOwners,
+    #[msg("Threshold must be between 1 and the number of owners")]
+    InvalidThreshold,
+    #[msg("Duplicate owner")]
+    DuplicateOwner,
+    #[msg("Signer is not a multisig owner")]
+    NotAnOwner,
+    #[msg("Proposal already executed")]
+    AlreadyExecuted,
+    #[msg("Proposal was created for a previous owner set")]
+    StaleProposal,
+    #[msg("Not enough approvals")]
+    NotEnoughApprovals,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn multisig(owners: usize, threshold: u8) -> Multisig {
+        Multisig {
+            create_key: Pubkey::new_unique(),
+            owners: (0..owners).map(|_| Pubkey::new_unique()).collect(),
+            threshold,
+            proposal_count: 1,
+            owner_set_seqno: 0,
+            bump: 255,
+            signer_bump: 255,
+        }
+    }
+
+    fn proposal(multisig: &Multisig, approved_by: &[usize]) -> Proposal {
+        let mut approvals = vec![false; multisig.owners.len()];
+        for index in approved_by {
+            approvals[*index] = true;
+        }
+        Proposal {
+            multisig: Pubkey::new_unique(),
+            index: 0,
+            proposer: multisig.owners[0],
+            target_program: Pubkey::new_unique(),
+            accounts: vec![],
+            data: vec![],
+            approvals,
+            owner_set_seqno: multisig.owner_set_seqno,
+            executed: false,
+            created_at: 0,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn executable_at_threshold() {
+        let multisig = multisig(3, 2);
+        assert!(proposal(&multisig, &[0, 2]).check_executable(&multisig).is_ok());
+        assert!(proposal(&multisig, &[0, 1, 2]).check_executable(&multisig).is_ok());
+    }
+
+    #[test]
+    fn below_threshold_rejected() {
+        let multisig = multisig(3, 2);
+        assert_eq!(
+            proposal(&multisig, &[1]).check_executable(&multisig).unwrap_err(),
+            MultisigError::NotEnoughApprovals.into()
+        );
+    }
+
+    #[test]
+    fn executed_proposal_cannot_be_replayed() {
+        let multisig = multisig(3, 2);
+        let mut proposal = proposal(&multisig, &[0, 1]);
+        assert!(proposal.check_executable(&multisig).is_ok());
+
+        proposal.executed = true;
+        assert_eq!(
+            proposal.check_executable(&multisig).unwrap_err(),
+            MultisigError::AlreadyExecuted.into()
+        );
+    }
+
+    #[test]
+    fn owner_change_invalidates_pending_proposals() {
+        let mut multisig = multisig(3, 2);
+        let proposal = proposal(&multisig, &[0, 1]);
+        multisig.owner_set_seqno += 1;
+        assert_eq!(
+            proposal.check_executable(&multisig).unwrap_err(),
+            MultisigError::StaleProposal.into()
+        );
+    }
+
+    #[test]
+    fn owner_validation() {
+        let owners: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        assert!(validate_owners(&owners, 3).is_ok());
+        assert!(validate_owners(&owners, 0).is_err());
+        assert!(validate_owners(&owners, 4).is_err());
+        assert!(validate_owners(&[owners[0], owners[0]], 1).is_err());
+        assert!(validate_owners(&[], 1).is_err());
+    }
+}
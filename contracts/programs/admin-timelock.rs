@@ -0,0 +1,504 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+
+declare_id!("AdminTimeLock1111111111111111111111111111111");
+
+// Bounds on the queue delay, so it can't be set to zero or to lock the admin out
+pub const MIN_DELAY_SECONDS: i64 = 86_400;
+pub const MAX_DELAY_SECONDS: i64 = 30 * 86_400;
+
+// A ready action must be executed within this window or it has to be queued again
+pub const GRACE_PERIOD_SECONDS: i64 = 14 * 86_400;
+
+#[program]
+pub mod admin_timelock {
+    use super::*;
+
+    /// Create a timelock. Its signer PDA is meant to hold the Admin authority of the
+    /// token and registry programs (via propose/accept authority), so config updates,
+    /// role grants and limit increases are only applied after a public delay. Those
+    /// programs only accept their config authority for Admin-gated instructions and
+    /// refuse to grant the Admin role, so the delay can't be routed around.
+    /// Emergency roles such as Pauser should stay with an operational key.
+    pub fn create_timelock(
+        ctx: Context<CreateTimelock>,
+        admin: Pubkey,
+        delay_seconds: i64,
+    ) -> Result<()> {
+        validate_delay(delay_seconds)?;
+
+        let timelock = &mut ctx.accounts.timelock;
+        timelock.create_key = ctx.accounts.create_key.key();
+        timelock.admin = admin;
+        timelock.delay_seconds = delay_seconds;
+        timelock.action_count = 0;
+        timelock.bump = ctx.bumps.timelock;
+        timelock.signer_bump = ctx.bumps.timelock_signer;
+
+        msg!("Timelock created with a {}s delay", delay_seconds);
+        msg!("Timelock signer: {}", ctx.accounts.timelock_signer.key());
+        Ok(())
+    }
+
+    /// Queue an instruction to be executed by the timelock signer at or after `eta`
+    pub fn queue_action(
+        ctx: Context<QueueAction>,
+        target_program: Pubkey,
+        accounts: Vec<ActionAccount>,
+        data: Vec<u8>,
+        eta: i64,
+    ) -> Result<()> {
+        let timelock = &mut ctx.accounts.timelock;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            eta >= now.saturating_add(timelock.delay_seconds),
+            TimelockError::EtaTooSoon
+        );
+
+        let action = &mut ctx.accounts.action;
+        action.timelock = timelock.key();
+        action.index = timelock.action_count;
+        action.proposer = ctx.accounts.admin.key();
+        action.target_program = target_program;
+        action.accounts = accounts;
+        action.data = data;
+        action.eta = eta;
+        action.status = ActionStatus::Queued;
+        action.queued_at = now;
+        action.bump = ctx.bumps.action;
+
+        timelock.action_count = timelock.action_count.saturating_add(1);
+
+        emit!(ActionQueued {
+            timelock: action.timelock,
+            action: action.key(),
+            index: action.index,
+            target_program,
+            eta,
+        });
+
+        msg!("Action {} queued, executable at {}", action.index, eta);
+        Ok(())
+    }
+
+    /// Cancel a queued action before it is executed
+    pub fn cancel_action(
+        ctx: Context<CancelAction>,
+    ) -> Result<()> {
+        let action = &mut ctx.accounts.action;
+        require!(
+            action.status == ActionStatus::Queued,
+            TimelockError::ActionNotQueued
+        );
+        action.status = ActionStatus::Cancelled;
+
+        emit!(ActionCancelled {
+            timelock: action.timelock,
+            action: action.key(),
+            index: action.index,
+        });
+
+        msg!("Action {} cancelled", action.index);
+        Ok(())
+    }
+
+    /// Execute a queued action once its ETA has passed. Permissionless: the admin
+    /// decided at queue time, and the delay is what gives everyone else notice.
+    pub fn execute_action<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteAction<'info>>,
+    ) -> Result<()> {
+        let timelock = &ctx.accounts.timelock;
+        let action = &mut ctx.accounts.action;
+        action.check_executable(Clock::get()?.unix_timestamp)?;
+
+        // Mark executed and write it back before the CPI, so a target that re-enters
+        // execute_action sees the action as spent and it can't be replayed
+        action.status = ActionStatus::Executed;
+        action.exit(&crate::ID)?;
+
+        let timelock_signer = ctx.accounts.timelock_signer.key();
+        let instruction = Instruction {
+            program_id: action.target_program,
+            accounts: action
+                .accounts
+                .iter()
+                .map(|account| AccountMeta {
+                    pubkey: account.pubkey,
+                    is_signer: account.is_signer || account.pubkey == timelock_signer,
+                    is_writable: account.is_writable,
+                })
+                .collect(),
+            data: action.data.clone(),
+        };
+
+        let timelock_key = timelock.key();
+        let signer_seeds: &[&[u8]] = &[
+            b"timelock_signer",
+            timelock_key.as_ref(),
+            &[timelock.signer_bump],
+        ];
+        invoke_signed(&instruction, ctx.remaining_accounts, &[signer_seeds])?;
+
+        emit!(ActionExecuted {
+            timelock: timelock_key,
+            action: action.key(),
+            index: action.index,
+            executed_by: ctx.accounts.executor.key(),
+        });
+
+        msg!("Action {} executed", action.index);
+        Ok(())
+    }
+
+    /// Change the delay. Only callable by the timelock itself through a queued action.
+    pub fn set_delay(
+        ctx: Context<TimelockAuth>,
+        delay_seconds: i64,
+    ) -> Result<()> {
+        validate_delay(delay_seconds)?;
+
+        let timelock = &mut ctx.accounts.timelock;
+        let previous_delay = timelock.delay_seconds;
+        timelock.delay_seconds = delay_seconds;
+
+        emit!(TimelockUpdated {
+            timelock: timelock.key(),
+            admin: timelock.admin,
+            delay_seconds,
+        });
+
+        msg!("Timelock delay updated: {}s -> {}s", previous_delay, delay_seconds);
+        Ok(())
+    }
+
+    /// Change the admin. Only callable by the timelock itself through a queued action.
+    pub fn set_admin(
+        ctx: Context<TimelockAuth>,
+        new_admin: Pubkey,
+    ) -> Result<()> {
+        let timelock = &mut ctx.accounts.timelock;
+        timelock.admin = new_admin;
+
+        emit!(TimelockUpdated {
+            timelock: timelock.key(),
+            admin: new_admin,
+            delay_seconds: timelock.delay_seconds,
+        });
+
+        msg!("Timelock admin updated: {}", new_admin);
+        Ok(())
+    }
+}
+
+// Helper functions
+fn validate_delay(delay_seconds: i64) -> Result<()> {
+    require!(
+        (MIN_DELAY_SECONDS..=MAX_DELAY_SECONDS).contains(&delay_seconds),
+        TimelockError::InvalidDelay
+    );
+    Ok(())
+}
+
+// Account structures
+#[derive(Accounts)]
+pub struct CreateTimelock<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Timelock::INIT_SPACE,
+        seeds = [b"timelock", create_key.key().as_ref()],
+        bump
+    )]
+    pub timelock: Account<'info, Timelock>,
+
+    /// CHECK: PDA that signs executed actions
+    #[account(seeds = [b"timelock_signer", timelock.key().as_ref()], bump)]
+    pub timelock_signer: UncheckedAccount<'info>,
+
+    pub create_key: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(target_program: Pubkey, accounts: Vec<ActionAccount>, data: Vec<u8>)]
+pub struct QueueAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"timelock", timelock.create_key.as_ref()],
+        bump = timelock.bump,
+        has_one = admin @ TimelockError::Unauthorized
+    )]
+    pub timelock: Account<'info, Timelock>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = QueuedAction::space(accounts.len(), data.len()),
+        seeds = [b"queued_action", timelock.key().as_ref(), &timelock.action_count.to_le_bytes()],
+        bump
+    )]
+    pub action: Account<'info, QueuedAction>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAction<'info> {
+    #[account(
+        seeds = [b"timelock", timelock.create_key.as_ref()],
+        bump = timelock.bump,
+        has_one = admin @ TimelockError::Unauthorized
+    )]
+    pub timelock: Account<'info, Timelock>,
+
+    #[account(
+        mut,
+        seeds = [b"queued_action", timelock.key().as_ref(), &action.index.to_le_bytes()],
+        bump = action.bump,
+        has_one = timelock
+    )]
+    pub action: Account<'info, QueuedAction>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteAction<'info> {
+    #[account(
+        seeds = [b"timelock", timelock.create_key.as_ref()],
+        bump = timelock.bump
+    )]
+    pub timelock: Account<'info, Timelock>,
+
+    /// CHECK: PDA that signs the queued instruction
+    #[account(
+        seeds = [b"timelock_signer", timelock.key().as_ref()],
+        bump = timelock.signer_bump
+    )]
+    pub timelock_signer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"queued_action", timelock.key().as_ref(), &action.index.to_le_bytes()],
+        bump = action.bump,
+        has_one = timelock
+    )]
+    pub action: Account<'info, QueuedAction>,
+
+    pub executor: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TimelockAuth<'info> {
+    #[account(
+        mut,
+        seeds = [b"timelock", timelock.create_key.as_ref()],
+        bump = timelock.bump
+    )]
+    pub timelock: Account<'info, Timelock>,
+
+    #[account(
+        seeds = [b"timelock_signer", timelock.key().as_ref()],
+        bump = timelock.signer_bump
+    )]
+    pub timelock_signer: Signer<'info>,
+}
+
+// Data structures
+#[account]
+#[derive(InitSpace)]
+pub struct Timelock {
+    pub create_key: Pubkey,
+    pub admin: Pubkey,
+    pub delay_seconds: i64,
+    pub action_count: u64,
+    pub bump: u8,
+    pub signer_bump: u8,
+}
+
+#[account]
+pub struct QueuedAction {
+    pub timelock: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub target_program: Pubkey,
+    pub accounts: Vec<ActionAccount>,
+    pub data: Vec<u8>,
+    pub eta: i64,
+    pub status: ActionStatus,
+    pub queued_at: i64,
+    pub bump: u8,
+}
+
+impl QueuedAction {
+    pub fn space(accounts: usize, data: usize) -> usize {
+        8 + 32 + 8 + 32 + 32
+            + (4 + accounts * ActionAccount::INIT_SPACE)
+            + (4 + data)
+            + 8 + 1 + 8 + 1
+    }
+
+    // Still queued, past its ETA and inside the grace period
+    pub fn check_executable(&self, now: i64) -> Result<()> {
+        require!(
+            self.status == ActionStatus::Queued,
+            TimelockError::ActionNotQueued
+        );
+        require!(now >= self.eta, TimelockError::ActionNotReady);
+        require!(
+            now <= self.eta.saturating_add(GRACE_PERIOD_SECONDS),
+            TimelockError::ActionExpired
+        );
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct ActionAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+// Enums
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ActionStatus {
+    Queued,
+    Executed,
+    Cancelled,
+}
+
+// Events
+#[event]
+pub struct ActionQueued {
+    pub timelock: Pubkey,
+    pub action: Pubkey,
+    pub index: u64,
+    pub target_program: Pubkey,
+    pub eta: i64,
+}
+
+#[event]
+pub struct ActionCancelled {
+    pub timelock: Pubkey,
+    pub action: Pubkey,
+    pub index: u64,
+}
+
+#[event]
+pub struct ActionExecuted {
+    pub timelock: Pubkey,
+    pub action: Pubkey,
+    pub index: u64,
+    pub executed_by: Pubkey,
+}
+
+#[event]
+pub struct TimelockUpdated {
+    pub timelock: Pubkey,
+    pub admin: Pubkey,
+    pub delay_seconds: i64,
+}
+
+// Error codes
+#[error_code]
+pub enum TimelockError {
+    #[msg("Delay must be between 1 and 30 days")]
+    InvalidDelay,
+    #[msg("ETA is earlier than the timelock delay allows")]
+    EtaTooSoon,
+    #[msg("Action is not queued")]
+    ActionNotQueued,
+    #[msg("Action ETA has not passed yet")]
+    ActionNotReady,
+    #[msg("Action grace period has passed")]
+    ActionExpired,
+    #[msg("Unauthorized")]
+    Unauthorized,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ETA: i64 = 1_700_000_000;
+
+    fn action(accounts: usize, data: usize) -> QueuedAction {
+        QueuedAction {
+            timelock: Pubkey::new_unique(),
+            index: 0,
+            proposer: Pubkey::new_unique(),
+            target_program: Pubkey::new_unique(),
+            accounts: (0..accounts)
+                .map(|_| ActionAccount {
+                    pubkey: Pubkey::new_unique(),
+                    is_signer: false,
+                    is_writable: true,
+                })
+                .collect(),
+            data: vec![0; data],
+            eta: ETA,
+            status: ActionStatus::Queued,
+            queued_at: ETA - MIN_DELAY_SECONDS,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn delay_bounds() {
+        assert!(validate_delay(MIN_DELAY_SECONDS).is_ok());
+        assert!(validate_delay(MAX_DELAY_SECONDS).is_ok());
+        assert_eq!(
+            validate_delay(MIN_DELAY_SECONDS - 1).unwrap_err(),
+            TimelockError::InvalidDelay.into()
+        );
+        assert_eq!(
+            validate_delay(MAX_DELAY_SECONDS + 1).unwrap_err(),
+            TimelockError::InvalidDelay.into()
+        );
+        assert_eq!(validate_delay(0).unwrap_err(), TimelockError::InvalidDelay.into());
+    }
+
+    #[test]
+    fn executable_from_eta_to_end_of_grace_period() {
+        let action = action(1, 8);
+        assert_eq!(
+            action.check_executable(ETA - 1).unwrap_err(),
+            TimelockError::ActionNotReady.into()
+        );
+        assert!(action.check_executable(ETA).is_ok());
+        assert!(action.check_executable(ETA + GRACE_PERIOD_SECONDS).is_ok());
+        assert_eq!(
+            action.check_executable(ETA + GRACE_PERIOD_SECONDS + 1).unwrap_err(),
+            TimelockError::ActionExpired.into()
+        );
+    }
+
+    #[test]
+    fn executed_or_cancelled_actions_cant_run() {
+        let mut action = action(1, 8);
+        for status in [ActionStatus::Executed, ActionStatus::Cancelled] {
+            action.status = status;
+            assert_eq!(
+                action.check_executable(ETA).unwrap_err(),
+                TimelockError::ActionNotQueued.into()
+            );
+        }
+    }
+
+    #[test]
+    fn space_fits_serialized_action() {
+        for (accounts, data) in [(0, 0), (1, 8), (5, 300)] {
+            let action = action(accounts, data);
+            let mut serialized = Vec::new();
+            action.serialize(&mut serialized).unwrap();
+            assert_eq!(QueuedAction::space(accounts, data), 8 + serialized.len());
+        }
+    }
+}
@@ -1,8 +1,36 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::{self, AssociatedToken};
+use anchor_spl::token_2022::{
+    self,
+    spl_token_2022::{extension::ExtensionType, state::Mint as MintState},
+};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
 
 declare_id!("AdmiralToken111111111111111111111111111111111");
 
+// Pause flags, batch limits, roles, mint limiters and burn callers shared with the
+// other token program
+#[path = "token-common.rs"]
+mod token_common;
+pub use token_common::*;
+
+use AdmiralTokenError as TokenError;
+
+// Transfer fees on $ADMIRAL are capped at 5%
+pub const MAX_TRANSFER_FEE_BASIS_POINTS: u16 = 500;
+
+// Fixed-point scale for staking reward-per-share accounting
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+// pirate_token program, burns the $PIRATE side of a conversion
+pub const PIRATE_TOKEN_PROGRAM_ID: Pubkey = pubkey!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+// Borsh index of pirate_token's BurnReason::Conversion
+pub const PIRATE_BURN_REASON_CONVERSION: u8 = 4;
+
 #[program]
 pub mod admiral_token {
     use super::*;
@@ -14,43 +42,166 @@ pub mod admiral_token {
         symbol: String,
         decimals: u8,
         total_supply: u64,
+        extensions: MintExtensionArgs,
     ) -> Result<()> {
-        let token_mint = &mut ctx.accounts.token_mint;
+        let token_mint = &ctx.accounts.token_mint;
         let authority = &ctx.accounts.authority;
         let system_program = &ctx.accounts.system_program;
         let token_program = &ctx.accounts.token_program;
-        let rent = &ctx.accounts.rent;
+        let mint_authority = &ctx.accounts.mint_authority;
+        let token_config = &mut ctx.accounts.token_config;
+
+        let token_mint_key = token_mint.key();
+        let mint_authority_seeds: &[&[u8]] = &[
+            b"mint_authority",
+            token_mint_key.as_ref(),
+            &[ctx.bumps.mint_authority],
+        ];
+
+        // Extensions are only available on Token-2022 mints
+        let is_token_2022 = token_program.key() == token_2022::ID;
+        require!(
+            is_token_2022 || extensions.is_empty(),
+            AdmiralTokenError::ExtensionsRequireToken2022
+        );
+
+        // Create the mint account with room for the requested extensions
+        let mint_space = ExtensionType::try_calculate_account_len::<MintState>(
+            &extensions.extension_types(),
+        )?;
+        let metadata_space = match &extensions.metadata {
+            Some(metadata) => token_metadata_space(&name, &symbol, &metadata.uri),
+            None => 0,
+        };
+        let lamports = Rent::get()?.minimum_balance(mint_space + metadata_space);
+
+        system_program::create_account(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: authority.to_account_info(),
+                    to: token_mint.to_account_info(),
+                },
+            ),
+            lamports,
+            mint_space as u64,
+            &token_program.key(),
+        )?;
+
+        // Extensions must be initialized before the mint itself
+        if let Some(transfer_fee) = &extensions.transfer_fee {
+            require!(
+                transfer_fee.basis_points <= MAX_TRANSFER_FEE_BASIS_POINTS,
+                AdmiralTokenError::InvalidTransferFee
+            );
 
-        // Initialize the token mint
-        token::initialize_mint(
+            token_interface::transfer_fee_initialize(
+                CpiContext::new(
+                    token_program.to_account_info(),
+                    token_interface::TransferFeeInitialize {
+                        token_program_id: token_program.to_account_info(),
+                        mint: token_mint.to_account_info(),
+                    },
+                ),
+                Some(&mint_authority.key()),
+                Some(&mint_authority.key()),
+                transfer_fee.basis_points,
+                transfer_fee.maximum_fee,
+            )?;
+        }
+
+        if extensions.metadata.is_some() {
+            token_interface::metadata_pointer_initialize(
+                CpiContext::new(
+                    token_program.to_account_info(),
+                    token_interface::MetadataPointerInitialize {
+                        token_program_id: token_program.to_account_info(),
+                        mint: token_mint.to_account_info(),
+                    },
+                ),
+                Some(mint_authority.key()),
+                Some(token_mint.key()),
+            )?;
+        }
+
+        // Initialize the token mint, controlled by the program so the admin can be rotated
+        token_interface::initialize_mint2(
             CpiContext::new(
                 token_program.to_account_info(),
-                token::InitializeMint {
+                token_interface::InitializeMint2 {
                     mint: token_mint.to_account_info(),
-                    rent: rent.to_account_info(),
                 },
             ),
             decimals,
-            authority,
-            Some(authority),
+            &mint_authority.key(),
+            Some(&mint_authority.key()),
         )?;
 
+        // Metadata lives on the mint itself, behind the metadata pointer
+        if let Some(metadata) = &extensions.metadata {
+            token_interface::token_metadata_initialize(
+                CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    token_interface::TokenMetadataInitialize {
+                        token_program_id: token_program.to_account_info(),
+                        mint: token_mint.to_account_info(),
+                        metadata: token_mint.to_account_info(),
+                        mint_authority: mint_authority.to_account_info(),
+                        update_authority: mint_authority.to_account_info(),
+                    },
+                    &[mint_authority_seeds],
+                ),
+                name.clone(),
+                symbol.clone(),
+                metadata.uri.clone(),
+            )?;
+        }
+
         // Create token account for the authority
-        let cpi_accounts = token::MintTo {
+        associated_token::create(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: authority.to_account_info(),
+                associated_token: ctx.accounts.authority_token_account.to_account_info(),
+                authority: authority.to_account_info(),
+                mint: token_mint.to_account_info(),
+                system_program: system_program.to_account_info(),
+                token_program: token_program.to_account_info(),
+            },
+        ))?;
+
+        let cpi_accounts = token_interface::MintTo {
             mint: token_mint.to_account_info(),
             to: ctx.accounts.authority_token_account.to_account_info(),
-            authority: authority.to_account_info(),
+            authority: mint_authority.to_account_info(),
         };
 
         let cpi_program = token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        token::mint_to(cpi_ctx, total_supply)?;
+        let signer_seeds = &[mint_authority_seeds];
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        token_interface::mint_to(cpi_ctx, total_supply)?;
+
+        // Initialize the token config
+        token_config.mint = token_mint.key();
+        token_config.authority = authority.key();
+        token_config.pending_authority = None;
+        token_config.total_burned = 0;
+        token_config.burned_shop = 0;
+        token_config.burned_recharge = 0;
+        token_config.burned_upgrade = 0;
+        token_config.burned_fee = 0;
+        token_config.burned_via_cpi = 0;
+        token_config.paused_flags = 0;
+        token_config.mint_limit_per_window = 0;
+        token_config.mint_limit_window_seconds = 86400;
+        token_config.bump = ctx.bumps.token_config;
 
         msg!("$ADMIRAL token initialized successfully!");
         msg!("Name: {}", name);
         msg!("Symbol: {}", symbol);
         msg!("Decimals: {}", decimals);
         msg!("Total Supply: {}", total_supply);
+        msg!("Token program: {}", token_program.key());
 
         Ok(())
     }
@@ -60,15 +211,21 @@ pub mod admiral_token {
         ctx: Context<TransferTokens>,
         amount: u64,
     ) -> Result<()> {
-        let cpi_accounts = Transfer {
+        require!(
+            !ctx.accounts.token_config.is_paused(PAUSE_TRANSFERS),
+            AdmiralTokenError::Paused
+        );
+
+        let cpi_accounts = TransferChecked {
             from: ctx.accounts.from.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
             to: ctx.accounts.to.to_account_info(),
             authority: ctx.accounts.authority.to_account_info(),
         };
 
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        token::transfer(cpi_ctx, amount)?;
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
 
         msg!("Transferred {} $ADMIRAL tokens", amount);
         Ok(())
@@ -79,62 +236,2327 @@ pub mod admiral_token {
         ctx: Context<MintTokens>,
         amount: u64,
     ) -> Result<()> {
-        let cpi_accounts = token::MintTo {
+        require_role(
+            &ctx.accounts.token_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            Role::Minter,
+        )?;
+
+        require!(
+            !ctx.accounts.token_config.is_paused(PAUSE_MINTING),
+            AdmiralTokenError::Paused
+        );
+
+        // Enforce the per-recipient rolling window limit
+        let clock = Clock::get()?;
+        let token_config = &ctx.accounts.token_config;
+        let mint_limiter = &mut ctx.accounts.mint_limiter;
+        if mint_limiter.recipient == Pubkey::default() {
+            mint_limiter.mint = ctx.accounts.mint.key();
+            mint_limiter.recipient = ctx.accounts.to.owner;
+            mint_limiter.window_start = clock.unix_timestamp;
+            mint_limiter.bump = ctx.bumps.mint_limiter;
+        }
+        mint_limiter.record_mint(
+            amount,
+            clock.unix_timestamp,
+            token_config.mint_limit_per_window,
+            token_config.mint_limit_window_seconds,
+        )?;
+
+        let mint_key = ctx.accounts.mint.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"mint_authority",
+            mint_key.as_ref(),
+            &[ctx.bumps.mint_authority],
+        ]];
+
+        let cpi_accounts = token_interface::MintTo {
             mint: ctx.accounts.mint.to_account_info(),
             to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        token_interface::mint_to(cpi_ctx, amount)?;
+
+        msg!("Minted {} $ADMIRAL tokens", amount);
+        Ok(())
+    }
+
+    // Mint to many recipients in one transaction (tournament and leaderboard payouts).
+    // remaining_accounts holds a [recipient token account, mint limiter PDA] pair per
+    // amount; any failure reverts the whole batch
+    pub fn batch_mint<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchMint<'info>>,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        process_batch_mint(ctx, amounts)
+    }
+
+    // Transfer from one account to many recipients in one transaction.
+    // remaining_accounts holds one recipient token account per amount; any failure
+    // reverts the whole batch
+    pub fn batch_transfer<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchTransfer<'info>>,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        process_batch_transfer(ctx, amounts)
+    }
+
+    // Burn $ADMIRAL tokens (sink for shop, recharge, upgrades and fees)
+    pub fn burn_tokens(
+        ctx: Context<BurnTokens>,
+        amount: u64,
+        reason: BurnReason,
+    ) -> Result<()> {
+        require!(amount > 0, AdmiralTokenError::InvalidAmount);
+
+        let cpi_accounts = token_interface::Burn {
+            mint: ctx.accounts.mint.to_account_info(),
+            from: ctx.accounts.from.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token_interface::burn(cpi_ctx, amount)?;
+
+        let caller_program =
+            resolve_burn_caller(&ctx.accounts.burn_caller, &ctx.accounts.caller_authority)?;
+
+        let token_config = &mut ctx.accounts.token_config;
+        token_config.record_burn(amount, reason, caller_program.is_some())?;
+        if let Some(burn_caller) = &mut ctx.accounts.burn_caller {
+            burn_caller.record_burn(amount)?;
+        }
+
+        emit!(TokensBurned {
+            mint: ctx.accounts.mint.key(),
+            owner: ctx.accounts.owner.key(),
+            amount,
+            reason,
+            caller_program,
+            total_burned: token_config.total_burned,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Burned {} $ADMIRAL tokens ({})", amount, reason.to_string());
+        Ok(())
+    }
+
+    // Pause or resume minting and transfers (emergency switch)
+    pub fn set_paused(
+        ctx: Context<SetPaused>,
+        paused_flags: u8,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.token_config,
+            &ctx.accounts.pauser,
+            &ctx.accounts.pauser_roles,
+            Role::Pauser,
+        )?;
+
+        let token_config = &mut ctx.accounts.token_config;
+        token_config.paused_flags = paused_flags;
+
+        emit!(PauseStateChanged {
+            mint: token_config.mint,
+            pauser: ctx.accounts.pauser.key(),
+            paused_flags,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("$ADMIRAL pause flags set to: {:#04b}", paused_flags);
+        Ok(())
+    }
+
+    // Grant a role for this token (Admin only)
+    pub fn grant_role(
+        ctx: Context<GrantRole>,
+        member: Pubkey,
+        role: Role,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.token_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            Role::Admin,
+        )?;
+        require!(role != Role::Admin, AdmiralTokenError::AdminRoleReserved);
+
+        let roles = &mut ctx.accounts.roles;
+        if roles.member == Pubkey::default() {
+            roles.config = ctx.accounts.token_config.key();
+            roles.member = member;
+            roles.bump = ctx.bumps.roles;
+        }
+        roles.grant(role);
+
+        emit!(RoleGranted {
+            config: roles.config,
+            member,
+            role,
+            granted_by: ctx.accounts.authority.key(),
+        });
+
+        msg!("$ADMIRAL role {} granted to: {}", role.to_string(), member);
+        Ok(())
+    }
+
+    // Revoke a role for this token (Admin only)
+    pub fn revoke_role(
+        ctx: Context<RevokeRole>,
+        role: Role,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.token_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            Role::Admin,
+        )?;
+
+        let roles = &mut ctx.accounts.roles;
+        roles.revoke(role);
+
+        emit!(RoleRevoked {
+            config: roles.config,
+            member: roles.member,
+            role,
+            revoked_by: ctx.accounts.authority.key(),
+        });
+
+        msg!("$ADMIRAL role {} revoked from: {}", role.to_string(), roles.member);
+        Ok(())
+    }
+
+    // Configure the per-recipient mint limit (0 disables it)
+    pub fn set_mint_limit(
+        ctx: Context<SetMintLimit>,
+        limit_per_window: u64,
+        window_seconds: i64,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.token_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            Role::Admin,
+        )?;
+
+        require!(window_seconds > 0, AdmiralTokenError::InvalidMintLimit);
+
+        let token_config = &mut ctx.accounts.token_config;
+        token_config.mint_limit_per_window = limit_per_window;
+        token_config.mint_limit_window_seconds = window_seconds;
+
+        emit!(MintLimitUpdated {
+            mint: token_config.mint,
+            limit_per_window,
+            window_seconds,
+        });
+
+        msg!("$ADMIRAL mint limit set to {} per {} seconds", limit_per_window, window_seconds);
+        Ok(())
+    }
+
+    // Grant a one-off allowance on top of a recipient's mint limit
+    pub fn grant_mint_override(
+        ctx: Context<GrantMintOverride>,
+        recipient: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.token_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            Role::Admin,
+        )?;
+
+        let mint_limiter = &mut ctx.accounts.mint_limiter;
+        if mint_limiter.recipient == Pubkey::default() {
+            mint_limiter.mint = ctx.accounts.token_config.mint;
+            mint_limiter.recipient = recipient;
+            mint_limiter.window_start = Clock::get()?.unix_timestamp;
+            mint_limiter.bump = ctx.bumps.mint_limiter;
+        }
+        mint_limiter.override_allowance = mint_limiter.override_allowance.saturating_add(amount);
+
+        emit!(MintOverrideGranted {
+            mint: mint_limiter.mint,
+            recipient,
+            amount,
+            override_allowance: mint_limiter.override_allowance,
+        });
+
+        msg!("$ADMIRAL mint override granted: {} for {}", amount, recipient);
+        Ok(())
+    }
+
+    // Propose a new admin (step one of the authority transfer)
+    pub fn propose_authority(
+        ctx: Context<ProposeAuthority>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        let token_config = &mut ctx.accounts.token_config;
+        token_config.pending_authority = Some(new_authority);
+
+        emit!(AuthorityTransferProposed {
+            mint: token_config.mint,
+            current_authority: token_config.authority,
+            pending_authority: new_authority,
+        });
+
+        msg!("$ADMIRAL authority transfer proposed to: {}", new_authority);
+        Ok(())
+    }
+
+    // Accept the admin role (step two of the authority transfer)
+    pub fn accept_authority(
+        ctx: Context<AcceptAuthority>,
+    ) -> Result<()> {
+        let token_config = &mut ctx.accounts.token_config;
+        let new_authority = ctx.accounts.new_authority.key();
+
+        require!(
+            token_config.pending_authority == Some(new_authority),
+            AdmiralTokenError::NotPendingAuthority
+        );
+
+        let previous_authority = token_config.authority;
+        token_config.authority = new_authority;
+        token_config.pending_authority = None;
+
+        emit!(AuthorityTransferred {
+            mint: token_config.mint,
+            previous_authority,
+            new_authority,
+        });
+
+        msg!("$ADMIRAL authority transferred to: {}", new_authority);
+        Ok(())
+    }
+
+    // Create a vesting grant escrowing $ADMIRAL for a team, partner or tournament allocation
+    pub fn create_vesting(
+        ctx: Context<CreateVesting>,
+        vesting_id: u64,
+        amount: u64,
+        start_ts: i64,
+        cliff_ts: i64,
+        end_ts: i64,
+        schedule: VestingSchedule,
+        revocable: bool,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.token_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            Role::Treasurer,
+        )?;
+
+        require!(amount > 0, AdmiralTokenError::InvalidAmount);
+        require!(
+            start_ts <= cliff_ts && cliff_ts <= end_ts && start_ts < end_ts,
+            AdmiralTokenError::InvalidVestingSchedule
+        );
+        if let VestingSchedule::Stepped { period_seconds } = schedule {
+            require!(
+                period_seconds > 0 && period_seconds <= end_ts - start_ts,
+                AdmiralTokenError::InvalidVestingSchedule
+            );
+        }
+
+        // Escrow the allocation in the vesting vault
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.treasury_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
             authority: ctx.accounts.authority.to_account_info(),
         };
 
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        token::mint_to(cpi_ctx, amount)?;
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
 
-        msg!("Minted {} $ADMIRAL tokens", amount);
+        // Transfer fees may be withheld, so vest what actually arrived
+        ctx.accounts.vault.reload()?;
+        let escrowed_amount = ctx.accounts.vault.amount;
+
+        let vesting_account = &mut ctx.accounts.vesting_account;
+        vesting_account.mint = ctx.accounts.mint.key();
+        vesting_account.beneficiary = ctx.accounts.beneficiary.key();
+        vesting_account.treasury_token_account = ctx.accounts.treasury_token_account.key();
+        vesting_account.vesting_id = vesting_id;
+        vesting_account.total_amount = escrowed_amount;
+        vesting_account.released_amount = 0;
+        vesting_account.start_ts = start_ts;
+        vesting_account.cliff_ts = cliff_ts;
+        vesting_account.end_ts = end_ts;
+        vesting_account.schedule = schedule;
+        vesting_account.revocable = revocable;
+        vesting_account.revoked = false;
+        vesting_account.bump = ctx.bumps.vesting_account;
+
+        emit!(VestingCreated {
+            vesting_account: vesting_account.key(),
+            beneficiary: vesting_account.beneficiary,
+            amount: escrowed_amount,
+            start_ts,
+            cliff_ts,
+            end_ts,
+            revocable,
+        });
+
+        msg!("Vesting created: {} $ADMIRAL for {}", escrowed_amount, vesting_account.beneficiary);
         Ok(())
     }
-}
 
-#[derive(Accounts)]
-pub struct InitializeToken<'info> {
-    #[account(
-        init,
-        payer = authority,
-        mint::decimals = 9,
-        mint::authority = authority.key(),
-    )]
-    pub token_mint: Account<'info, token::Mint>,
-    
-    #[account(
-        init,
-        payer = authority,
-        token::mint = token_mint,
-        token::authority = authority,
-    )]
-    pub authority_token_account: Account<'info, TokenAccount>,
-    
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    pub rent: Sysvar<'info, Rent>,
-}
+    // Release vested $ADMIRAL to the beneficiary (permissionless)
+    pub fn release(
+        ctx: Context<ReleaseVesting>,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let vesting_account = &ctx.accounts.vesting_account;
 
-#[derive(Accounts)]
-pub struct TransferTokens<'info> {
-    #[account(mut)]
-    pub from: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub to: Account<'info, TokenAccount>,
-    pub authority: Signer<'info>,
-    pub token_program: Program<'info, Token>,
-}
+        let releasable = vesting_account
+            .vested_amount(clock.unix_timestamp)
+            .saturating_sub(vesting_account.released_amount);
+        require!(releasable > 0, AdmiralTokenError::NothingToRelease);
 
-#[derive(Accounts)]
-pub struct MintTokens<'info> {
-    #[account(mut)]
-    pub mint: Account<'info, token::Mint>,
-    #[account(mut)]
-    pub to: Account<'info, TokenAccount>,
-    pub authority: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+        transfer_from_vesting_vault(
+            vesting_account,
+            &ctx.accounts.vault,
+            &ctx.accounts.mint,
+            ctx.accounts.beneficiary_token_account.to_account_info(),
+            &ctx.accounts.token_program,
+            releasable,
+        )?;
+
+        let vesting_account = &mut ctx.accounts.vesting_account;
+        vesting_account.released_amount = vesting_account.released_amount.saturating_add(releasable);
+
+        emit!(VestingReleased {
+            vesting_account: vesting_account.key(),
+            beneficiary: vesting_account.beneficiary,
+            amount: releasable,
+            released_amount: vesting_account.released_amount,
+        });
+
+        msg!("Released {} vested $ADMIRAL tokens", releasable);
+        Ok(())
+    }
+
+    // Revoke a revocable grant, returning the unvested remainder to the treasury
+    pub fn revoke(
+        ctx: Context<RevokeVesting>,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.token_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            Role::Treasurer,
+        )?;
+
+        let clock = Clock::get()?;
+        let vesting_account = &ctx.accounts.vesting_account;
+
+        require!(vesting_account.revocable, AdmiralTokenError::VestingNotRevocable);
+        require!(!vesting_account.revoked, AdmiralTokenError::VestingAlreadyRevoked);
+
+        // Vested tokens stay claimable by the beneficiary
+        let vested = vesting_account.vested_amount(clock.unix_timestamp);
+        let unvested = vesting_account.total_amount.saturating_sub(vested);
+
+        if unvested > 0 {
+            transfer_from_vesting_vault(
+                vesting_account,
+                &ctx.accounts.vault,
+                &ctx.accounts.mint,
+                ctx.accounts.treasury_token_account.to_account_info(),
+                &ctx.accounts.token_program,
+                unvested,
+            )?;
+        }
+
+        let vesting_account = &mut ctx.accounts.vesting_account;
+        vesting_account.total_amount = vested;
+        vesting_account.revoked = true;
+
+        emit!(VestingRevoked {
+            vesting_account: vesting_account.key(),
+            beneficiary: vesting_account.beneficiary,
+            returned_amount: unvested,
+            vested_amount: vested,
+        });
+
+        msg!("Vesting revoked, {} $ADMIRAL returned to treasury", unvested);
+        Ok(())
+    }
+
+    // Create the $ADMIRAL staking pool paying $PIRATE yield
+    pub fn create_stake_pool(
+        ctx: Context<CreateStakePool>,
+        reward_rate_per_second: u64,
+        cooldown_seconds: i64,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.token_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            Role::Admin,
+        )?;
+
+        require!(cooldown_seconds >= 0, AdmiralTokenError::InvalidStakeConfig);
+
+        let stake_pool = &mut ctx.accounts.stake_pool;
+        stake_pool.stake_mint = ctx.accounts.mint.key();
+        stake_pool.reward_mint = ctx.accounts.reward_mint.key();
+        stake_pool.reward_rate_per_second = reward_rate_per_second;
+        stake_pool.acc_reward_per_share = 0;
+        stake_pool.last_update_ts = Clock::get()?.unix_timestamp;
+        stake_pool.total_staked = 0;
+        stake_pool.cooldown_seconds = cooldown_seconds;
+        stake_pool.bump = ctx.bumps.stake_pool;
+
+        msg!("Staking pool created: {} $PIRATE per second", reward_rate_per_second);
+        Ok(())
+    }
+
+    // Update the $PIRATE emission rate and unstake cooldown
+    pub fn update_stake_pool(
+        ctx: Context<UpdateStakePool>,
+        reward_rate_per_second: u64,
+        cooldown_seconds: i64,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.token_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            Role::Admin,
+        )?;
+
+        require!(cooldown_seconds >= 0, AdmiralTokenError::InvalidStakeConfig);
+
+        // Settle rewards at the old rate first
+        let stake_pool = &mut ctx.accounts.stake_pool;
+        stake_pool.update_rewards(Clock::get()?.unix_timestamp)?;
+        stake_pool.reward_rate_per_second = reward_rate_per_second;
+        stake_pool.cooldown_seconds = cooldown_seconds;
+
+        msg!("Staking pool updated: {} $PIRATE per second", reward_rate_per_second);
+        Ok(())
+    }
+
+    // Stake $ADMIRAL into the pool
+    pub fn stake(
+        ctx: Context<Stake>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, AdmiralTokenError::InvalidAmount);
+        require!(
+            !ctx.accounts.token_config.is_paused(PAUSE_TRANSFERS),
+            AdmiralTokenError::Paused
+        );
+
+        let clock = Clock::get()?;
+        let stake_pool = &mut ctx.accounts.stake_pool;
+        let stake_account = &mut ctx.accounts.stake_account;
+
+        stake_pool.update_rewards(clock.unix_timestamp)?;
+        if stake_account.owner == Pubkey::default() {
+            stake_account.pool = stake_pool.key();
+            stake_account.owner = ctx.accounts.owner.key();
+            stake_account.bump = ctx.bumps.stake_account;
+        }
+        stake_account.settle_rewards(stake_pool)?;
+
+        let vault_before = ctx.accounts.stake_vault.amount;
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.owner_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.stake_vault.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+        // Transfer fees may be withheld, so stake what actually arrived
+        ctx.accounts.stake_vault.reload()?;
+        let staked = ctx.accounts.stake_vault.amount.saturating_sub(vault_before);
+
+        let stake_pool = &mut ctx.accounts.stake_pool;
+        let stake_account = &mut ctx.accounts.stake_account;
+        stake_account.amount = stake_account.amount.checked_add(staked).ok_or(AdmiralTokenError::MathOverflow)?;
+        stake_account.reward_debt = stake_account.accrued_rewards(stake_pool);
+        stake_pool.total_staked = stake_pool.total_staked.checked_add(staked).ok_or(AdmiralTokenError::MathOverflow)?;
+
+        emit!(Staked {
+            owner: stake_account.owner,
+            amount: staked,
+            total_staked: stake_account.amount,
+        });
+
+        msg!("Staked {} $ADMIRAL", staked);
+        Ok(())
+    }
+
+    // Start the cooldown for unstaking; cooling tokens stop earning rewards
+    pub fn request_unstake(
+        ctx: Context<RequestUnstake>,
+        amount: u64,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let stake_pool = &mut ctx.accounts.stake_pool;
+        let stake_account = &mut ctx.accounts.stake_account;
+
+        require!(
+            amount > 0 && amount <= stake_account.amount,
+            AdmiralTokenError::InvalidAmount
+        );
+
+        stake_pool.update_rewards(clock.unix_timestamp)?;
+        stake_account.settle_rewards(stake_pool)?;
+
+        stake_account.amount = stake_account.amount.saturating_sub(amount);
+        stake_account.cooling_amount = stake_account.cooling_amount.saturating_add(amount);
+        stake_account.unstake_available_at = clock.unix_timestamp.saturating_add(stake_pool.cooldown_seconds);
+        stake_account.reward_debt = stake_account.accrued_rewards(stake_pool);
+        stake_pool.total_staked = stake_pool.total_staked.saturating_sub(amount);
+
+        emit!(UnstakeRequested {
+            owner: stake_account.owner,
+            amount,
+            available_at: stake_account.unstake_available_at,
+        });
+
+        msg!("Unstake requested: {} $ADMIRAL, available at {}", amount, stake_account.unstake_available_at);
+        Ok(())
+    }
+
+    // Withdraw $ADMIRAL once the unstake cooldown has passed
+    pub fn withdraw_unstaked(
+        ctx: Context<WithdrawUnstaked>,
+    ) -> Result<()> {
+        require!(
+            !ctx.accounts.token_config.is_paused(PAUSE_TRANSFERS),
+            AdmiralTokenError::Paused
+        );
+
+        let clock = Clock::get()?;
+        let stake_account = &ctx.accounts.stake_account;
+
+        require!(stake_account.cooling_amount > 0, AdmiralTokenError::NothingToWithdraw);
+        require!(
+            clock.unix_timestamp >= stake_account.unstake_available_at,
+            AdmiralTokenError::CooldownNotElapsed
+        );
+
+        let amount = stake_account.cooling_amount;
+        transfer_from_stake_pool(
+            &ctx.accounts.stake_pool,
+            ctx.accounts.stake_vault.to_account_info(),
+            &ctx.accounts.mint,
+            ctx.accounts.owner_token_account.to_account_info(),
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+
+        let stake_account = &mut ctx.accounts.stake_account;
+        stake_account.cooling_amount = 0;
+
+        emit!(Unstaked {
+            owner: stake_account.owner,
+            amount,
+        });
+
+        msg!("Withdrew {} unstaked $ADMIRAL", amount);
+        Ok(())
+    }
+
+    // Claim accrued $PIRATE staking rewards
+    pub fn claim_staking_rewards(
+        ctx: Context<ClaimStakingRewards>,
+    ) -> Result<()> {
+        require!(
+            !ctx.accounts.token_config.is_paused(PAUSE_CLAIMS),
+            AdmiralTokenError::Paused
+        );
+
+        let clock = Clock::get()?;
+        let stake_pool = &mut ctx.accounts.stake_pool;
+        let stake_account = &mut ctx.accounts.stake_account;
+
+        stake_pool.update_rewards(clock.unix_timestamp)?;
+        stake_account.settle_rewards(stake_pool)?;
+        stake_account.reward_debt = stake_account.accrued_rewards(stake_pool);
+
+        // Pay what the reward vault can cover; the rest stays pending
+        let payout = stake_account.pending_rewards.min(ctx.accounts.reward_vault.amount);
+        require!(payout > 0, AdmiralTokenError::NothingToWithdraw);
+
+        transfer_from_stake_pool(
+            &ctx.accounts.stake_pool,
+            ctx.accounts.reward_vault.to_account_info(),
+            &ctx.accounts.reward_mint,
+            ctx.accounts.owner_reward_account.to_account_info(),
+            &ctx.accounts.reward_token_program,
+            payout,
+        )?;
+
+        let stake_account = &mut ctx.accounts.stake_account;
+        stake_account.pending_rewards = stake_account.pending_rewards.saturating_sub(payout);
+
+        emit!(StakingRewardsClaimed {
+            owner: stake_account.owner,
+            amount: payout,
+        });
+
+        msg!("Claimed {} $PIRATE staking rewards", payout);
+        Ok(())
+    }
+
+    // Create the $PIRATE to $ADMIRAL conversion config
+    pub fn initialize_conversion_config(
+        ctx: Context<InitializeConversionConfig>,
+        params: ConversionParams,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.token_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            Role::Admin,
+        )?;
+
+        params.validate()?;
+
+        let clock = Clock::get()?;
+        let conversion_config = &mut ctx.accounts.conversion_config;
+        conversion_config.admiral_mint = ctx.accounts.mint.key();
+        conversion_config.pirate_mint = ctx.accounts.pirate_mint.key();
+        conversion_config.apply(&params);
+        conversion_config.current_epoch = clock.unix_timestamp / params.epoch_duration_seconds;
+        conversion_config.epoch_converted = 0;
+        conversion_config.total_pirate_burned = 0;
+        conversion_config.total_admiral_minted = 0;
+        conversion_config.bump = ctx.bumps.conversion_config;
+
+        emit!(ConversionConfigUpdated {
+            rate_numerator: params.rate_numerator,
+            rate_denominator: params.rate_denominator,
+            wallet_daily_cap: params.wallet_daily_cap,
+            epoch_budget: params.epoch_budget,
+            epoch_duration_seconds: params.epoch_duration_seconds,
+        });
+
+        msg!("Conversion config initialized: {}/{} $ADMIRAL per $PIRATE", params.rate_numerator, params.rate_denominator);
+        Ok(())
+    }
+
+    // Update the conversion rate and limits
+    pub fn update_conversion_config(
+        ctx: Context<UpdateConversionConfig>,
+        params: ConversionParams,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.token_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            Role::Admin,
+        )?;
+
+        params.validate()?;
+
+        let conversion_config = &mut ctx.accounts.conversion_config;
+        conversion_config.apply(&params);
+
+        emit!(ConversionConfigUpdated {
+            rate_numerator: params.rate_numerator,
+            rate_denominator: params.rate_denominator,
+            wallet_daily_cap: params.wallet_daily_cap,
+            epoch_budget: params.epoch_budget,
+            epoch_duration_seconds: params.epoch_duration_seconds,
+        });
+
+        msg!("Conversion config updated: {}/{} $ADMIRAL per $PIRATE", params.rate_numerator, params.rate_denominator);
+        Ok(())
+    }
+
+    // Burn $PIRATE and mint $ADMIRAL at the configured rate
+    pub fn convert(
+        ctx: Context<Convert>,
+        pirate_amount: u64,
+    ) -> Result<()> {
+        require!(pirate_amount > 0, AdmiralTokenError::InvalidAmount);
+        require!(
+            !ctx.accounts.token_config.is_paused(PAUSE_MINTING),
+            AdmiralTokenError::Paused
+        );
+
+        let clock = Clock::get()?;
+        let conversion_config = &mut ctx.accounts.conversion_config;
+        let conversion_limiter = &mut ctx.accounts.conversion_limiter;
+
+        let admiral_amount = conversion_config.quote(pirate_amount)?;
+        require!(admiral_amount > 0, AdmiralTokenError::ConversionTooSmall);
+
+        // Global budget, reset every epoch
+        let epoch = clock.unix_timestamp / conversion_config.epoch_duration_seconds;
+        if epoch != conversion_config.current_epoch {
+            conversion_config.current_epoch = epoch;
+            conversion_config.epoch_converted = 0;
+        }
+        let epoch_converted = conversion_config
+            .epoch_converted
+            .checked_add(admiral_amount)
+            .ok_or(AdmiralTokenError::MathOverflow)?;
+        require!(
+            epoch_converted <= conversion_config.epoch_budget,
+            AdmiralTokenError::EpochBudgetExceeded
+        );
+
+        // Per-wallet cap, reset every day
+        let day = clock.unix_timestamp / 86400;
+        if conversion_limiter.owner == Pubkey::default() {
+            conversion_limiter.owner = ctx.accounts.owner.key();
+            conversion_limiter.bump = ctx.bumps.conversion_limiter;
+        }
+        if day != conversion_limiter.day {
+            conversion_limiter.day = day;
+            conversion_limiter.converted_today = 0;
+        }
+        let converted_today = conversion_limiter
+            .converted_today
+            .checked_add(admiral_amount)
+            .ok_or(AdmiralTokenError::MathOverflow)?;
+        require!(
+            converted_today <= conversion_config.wallet_daily_cap,
+            AdmiralTokenError::WalletDailyCapExceeded
+        );
+
+        conversion_config.epoch_converted = epoch_converted;
+        conversion_config.total_pirate_burned = conversion_config.total_pirate_burned.saturating_add(pirate_amount);
+        conversion_config.total_admiral_minted = conversion_config.total_admiral_minted.saturating_add(admiral_amount);
+        conversion_limiter.converted_today = converted_today;
+
+        // Burn the $PIRATE side through pirate_token so it is counted as a conversion sink
+        burn_pirate_for_conversion(&ctx.accounts, pirate_amount, ctx.bumps.burn_authority)?;
+
+        // Mint the $ADMIRAL side
+        let mint_key = ctx.accounts.mint.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"mint_authority",
+            mint_key.as_ref(),
+            &[ctx.bumps.mint_authority],
+        ]];
+
+        let cpi_accounts = token_interface::MintTo {
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.owner_admiral_account.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        token_interface::mint_to(cpi_ctx, admiral_amount)?;
+
+        emit!(TokensConverted {
+            owner: ctx.accounts.owner.key(),
+            pirate_burned: pirate_amount,
+            admiral_minted: admiral_amount,
+            epoch,
+            epoch_converted,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("Converted {} $PIRATE into {} $ADMIRAL", pirate_amount, admiral_amount);
+        Ok(())
+    }
+
+    // View: current supply and burn totals. Simulate to read the result.
+    pub fn get_supply_info(
+        ctx: Context<GetSupplyInfo>,
+    ) -> Result<SupplyInfo> {
+        let token_config = &ctx.accounts.token_config;
+        let mint = &ctx.accounts.mint;
+
+        Ok(SupplyInfo {
+            mint: mint.key(),
+            decimals: mint.decimals,
+            supply: mint.supply,
+            total_burned: token_config.total_burned,
+            burned_shop: token_config.burned_shop,
+            burned_recharge: token_config.burned_recharge,
+            burned_upgrade: token_config.burned_upgrade,
+            burned_fee: token_config.burned_fee,
+            burned_via_cpi: token_config.burned_via_cpi,
+            paused_flags: token_config.paused_flags,
+            mint_limit_per_window: token_config.mint_limit_per_window,
+            mint_limit_window_seconds: token_config.mint_limit_window_seconds,
+        })
+    }
+
+    // Register a program allowed to burn through CPI with its own counters (Admin only)
+    pub fn register_burn_caller(
+        ctx: Context<RegisterBurnCaller>,
+        program: Pubkey,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.token_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            Role::Admin,
+        )?;
+
+        let (authority, _) = Pubkey::find_program_address(&[b"burn_authority"], &program);
+
+        let burn_caller = &mut ctx.accounts.burn_caller;
+        burn_caller.mint = ctx.accounts.token_config.mint;
+        burn_caller.program = program;
+        burn_caller.authority = authority;
+        burn_caller.total_burned = 0;
+        burn_caller.burn_count = 0;
+        burn_caller.bump = ctx.bumps.burn_caller;
+
+        emit!(BurnCallerRegistered {
+            mint: burn_caller.mint,
+            program,
+            authority,
+        });
+
+        msg!("$ADMIRAL burn caller registered: {}", program);
+        Ok(())
+    }
+}
+
+// Helper functions
+// Transfers out of a vesting vault, signed by the vesting PDA
+fn transfer_from_vesting_vault<'info>(
+    vesting_account: &Account<'info, VestingAccount>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<()> {
+    let vesting_id = vesting_account.vesting_id.to_le_bytes();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"vesting",
+        vesting_account.mint.as_ref(),
+        vesting_account.beneficiary.as_ref(),
+        &vesting_id,
+        &[vesting_account.bump],
+    ]];
+
+    let cpi_accounts = TransferChecked {
+        from: vault.to_account_info(),
+        mint: mint.to_account_info(),
+        to,
+        authority: vesting_account.to_account_info(),
+    };
+
+    let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)
+}
+
+// Transfers out of a staking vault, signed by the stake pool PDA
+fn transfer_from_stake_pool<'info>(
+    stake_pool: &Account<'info, StakePool>,
+    vault: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"stake_pool",
+        stake_pool.stake_mint.as_ref(),
+        &[stake_pool.bump],
+    ]];
+
+    let cpi_accounts = TransferChecked {
+        from: vault,
+        mint: mint.to_account_info(),
+        to,
+        authority: stake_pool.to_account_info(),
+    };
+
+    let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)
+}
+
+// CPI into pirate_token::burn_tokens with the Conversion reason, signed by this
+// program's burn authority so pirate_token attributes the burn to admiral_token
+fn burn_pirate_for_conversion(accounts: &Convert, amount: u64, burn_authority_bump: u8) -> Result<()> {
+    let mut data = hash(b"global:burn_tokens").to_bytes()[..8].to_vec();
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.push(PIRATE_BURN_REASON_CONVERSION);
+
+    let instruction = Instruction {
+        program_id: PIRATE_TOKEN_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(accounts.pirate_token_config.key(), false),
+            AccountMeta::new(accounts.pirate_mint.key(), false),
+            AccountMeta::new(accounts.owner_pirate_account.key(), false),
+            AccountMeta::new_readonly(accounts.owner.key(), true),
+            AccountMeta::new(accounts.pirate_burn_caller.key(), false),
+            AccountMeta::new_readonly(accounts.burn_authority.key(), true),
+            AccountMeta::new_readonly(accounts.pirate_token_program.key(), false),
+            AccountMeta::new_readonly(accounts.conversion_config.key(), false),
+        ],
+        data,
+    };
+
+    invoke_signed(
+        &instruction,
+        &[
+            accounts.pirate_token_config.to_account_info(),
+            accounts.pirate_mint.to_account_info(),
+            accounts.owner_pirate_account.to_account_info(),
+            accounts.owner.to_account_info(),
+            accounts.pirate_burn_caller.to_account_info(),
+            accounts.burn_authority.to_account_info(),
+            accounts.pirate_token_program.to_account_info(),
+            accounts.conversion_config.to_account_info(),
+            accounts.pirate_program.to_account_info(),
+        ],
+        &[&[b"burn_authority", &[burn_authority_bump]]],
+    )?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeToken<'info> {
+    /// CHECK: Created and initialized in the handler so extensions can be added first
+    #[account(mut)]
+    pub token_mint: Signer<'info>,
+
+    /// CHECK: Associated token account, created in the handler once the mint exists
+    #[account(mut)]
+    pub authority_token_account: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + TokenConfig::INIT_SPACE,
+        seeds = [b"token_config", token_mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    /// CHECK: PDA that holds mint, freeze and extension authority over the mint
+    #[account(seeds = [b"mint_authority", token_mint.key().as_ref()], bump)]
+    pub mint_authority: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct TransferTokens<'info> {
+    #[account(
+        seeds = [b"token_config", mint.key().as_ref()],
+        bump = token_config.bump,
+        has_one = mint
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub from: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub to: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct MintTokens<'info> {
+    #[account(
+        seeds = [b"token_config", mint.key().as_ref()],
+        bump = token_config.bump,
+        has_one = mint
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    #[account(mut, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub to: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + MintLimiter::INIT_SPACE,
+        seeds = [b"mint_limiter", mint.key().as_ref(), to.owner.as_ref()],
+        bump
+    )]
+    pub mint_limiter: Account<'info, MintLimiter>,
+    /// CHECK: PDA mint authority, signs the mint CPI
+    #[account(seeds = [b"mint_authority", mint.key().as_ref()], bump)]
+    pub mint_authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"roles", token_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct BatchMint<'info> {
+    #[account(
+        seeds = [b"token_config", mint.key().as_ref()],
+        bump = token_config.bump,
+        has_one = mint
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    #[account(mut, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: PDA mint authority, signs the mint CPIs
+    #[account(seeds = [b"mint_authority", mint.key().as_ref()], bump)]
+    pub mint_authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"roles", token_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct BatchTransfer<'info> {
+    #[account(
+        seeds = [b"token_config", mint.key().as_ref()],
+        bump = token_config.bump,
+        has_one = mint
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub from: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct BurnTokens<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_config", mint.key().as_ref()],
+        bump = token_config.bump,
+        has_one = mint
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    #[account(mut, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = owner,
+        token::token_program = token_program
+    )]
+    pub from: InterfaceAccount<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    // CPI burns only: the calling program's counters and its signing burn authority PDA
+    #[account(
+        mut,
+        seeds = [b"burn_caller", mint.key().as_ref(), burn_caller.program.as_ref()],
+        bump = burn_caller.bump
+    )]
+    pub burn_caller: Option<Account<'info, BurnCaller>>,
+    pub caller_authority: Option<Signer<'info>>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_config", token_config.mint.as_ref()],
+        bump = token_config.bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    #[account(
+        seeds = [b"roles", token_config.key().as_ref(), pauser.key().as_ref()],
+        bump = pauser_roles.bump
+    )]
+    pub pauser_roles: Option<Account<'info, Roles>>,
+    pub pauser: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(member: Pubkey)]
+pub struct GrantRole<'info> {
+    #[account(
+        seeds = [b"token_config", token_config.mint.as_ref()],
+        bump = token_config.bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + Roles::INIT_SPACE,
+        seeds = [b"roles", token_config.key().as_ref(), member.as_ref()],
+        bump
+    )]
+    pub roles: Account<'info, Roles>,
+    #[account(
+        seeds = [b"roles", token_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeRole<'info> {
+    #[account(
+        seeds = [b"token_config", token_config.mint.as_ref()],
+        bump = token_config.bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    #[account(
+        mut,
+        seeds = [b"roles", token_config.key().as_ref(), roles.member.as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Account<'info, Roles>,
+    #[account(
+        seeds = [b"roles", token_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetMintLimit<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_config", token_config.mint.as_ref()],
+        bump = token_config.bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    #[account(
+        seeds = [b"roles", token_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(recipient: Pubkey)]
+pub struct GrantMintOverride<'info> {
+    #[account(
+        seeds = [b"token_config", token_config.mint.as_ref()],
+        bump = token_config.bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + MintLimiter::INIT_SPACE,
+        seeds = [b"mint_limiter", token_config.mint.as_ref(), recipient.as_ref()],
+        bump
+    )]
+    pub mint_limiter: Account<'info, MintLimiter>,
+    #[account(
+        seeds = [b"roles", token_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_config", token_config.mint.as_ref()],
+        bump = token_config.bump,
+        has_one = authority @ AdmiralTokenError::Unauthorized
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_config", token_config.mint.as_ref()],
+        bump = token_config.bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(vesting_id: u64)]
+pub struct CreateVesting<'info> {
+    #[account(
+        seeds = [b"token_config", mint.key().as_ref()],
+        bump = token_config.bump,
+        has_one = mint
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + VestingAccount::INIT_SPACE,
+        seeds = [
+            b"vesting",
+            mint.key().as_ref(),
+            beneficiary.key().as_ref(),
+            &vesting_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"vesting_vault", vesting_account.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vesting_account,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = authority,
+        token::token_program = token_program
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: Only recorded as the grant beneficiary
+    pub beneficiary: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"roles", token_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseVesting<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            vesting_account.mint.as_ref(),
+            vesting_account.beneficiary.as_ref(),
+            &vesting_account.vesting_id.to_le_bytes()
+        ],
+        bump = vesting_account.bump,
+        has_one = mint
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting_vault", vesting_account.key().as_ref()],
+        bump
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = vesting_account.beneficiary,
+        token::token_program = token_program
+    )]
+    pub beneficiary_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeVesting<'info> {
+    #[account(
+        seeds = [b"token_config", mint.key().as_ref()],
+        bump = token_config.bump,
+        has_one = mint
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            vesting_account.mint.as_ref(),
+            vesting_account.beneficiary.as_ref(),
+            &vesting_account.vesting_id.to_le_bytes()
+        ],
+        bump = vesting_account.bump,
+        has_one = mint,
+        has_one = treasury_token_account
+    )]
+    pub vesting_account: Account<'info, VestingAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting_vault", vesting_account.key().as_ref()],
+        bump
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"roles", token_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CreateStakePool<'info> {
+    #[account(
+        seeds = [b"token_config", mint.key().as_ref()],
+        bump = token_config.bump,
+        has_one = mint
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + StakePool::INIT_SPACE,
+        seeds = [b"stake_pool", mint.key().as_ref()],
+        bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"stake_vault", stake_pool.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = stake_pool,
+        token::token_program = token_program
+    )]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"reward_vault", stake_pool.key().as_ref()],
+        bump,
+        token::mint = reward_mint,
+        token::authority = stake_pool,
+        token::token_program = reward_token_program
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mint::token_program = reward_token_program)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"roles", token_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub reward_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateStakePool<'info> {
+    #[account(
+        seeds = [b"token_config", stake_pool.stake_mint.as_ref()],
+        bump = token_config.bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"stake_pool", stake_pool.stake_mint.as_ref()],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    #[account(
+        seeds = [b"roles", token_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(
+        seeds = [b"token_config", mint.key().as_ref()],
+        bump = token_config.bump,
+        has_one = mint
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"stake_pool", mint.key().as_ref()],
+        bump = stake_pool.bump,
+        constraint = stake_pool.stake_mint == mint.key()
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + StakeAccount::INIT_SPACE,
+        seeds = [b"stake", stake_pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"stake_vault", stake_pool.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = owner,
+        token::token_program = token_program
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RequestUnstake<'info> {
+    #[account(
+        mut,
+        seeds = [b"stake_pool", stake_pool.stake_mint.as_ref()],
+        bump = stake_pool.bump
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", stake_pool.key().as_ref(), owner.key().as_ref()],
+        bump = stake_account.bump,
+        has_one = owner
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawUnstaked<'info> {
+    #[account(
+        seeds = [b"token_config", mint.key().as_ref()],
+        bump = token_config.bump,
+        has_one = mint
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        seeds = [b"stake_pool", mint.key().as_ref()],
+        bump = stake_pool.bump,
+        constraint = stake_pool.stake_mint == mint.key()
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", stake_pool.key().as_ref(), owner.key().as_ref()],
+        bump = stake_account.bump,
+        has_one = owner
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"stake_vault", stake_pool.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = owner,
+        token::token_program = token_program
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub owner: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimStakingRewards<'info> {
+    #[account(
+        seeds = [b"token_config", stake_pool.stake_mint.as_ref()],
+        bump = token_config.bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"stake_pool", stake_pool.stake_mint.as_ref()],
+        bump = stake_pool.bump,
+        has_one = reward_mint
+    )]
+    pub stake_pool: Account<'info, StakePool>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", stake_pool.key().as_ref(), owner.key().as_ref()],
+        bump = stake_account.bump,
+        has_one = owner
+    )]
+    pub stake_account: Account<'info, StakeAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault", stake_pool.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = owner,
+        token::token_program = reward_token_program
+    )]
+    pub owner_reward_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    pub owner: Signer<'info>,
+    pub reward_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeConversionConfig<'info> {
+    #[account(
+        seeds = [b"token_config", mint.key().as_ref()],
+        bump = token_config.bump,
+        has_one = mint
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ConversionConfig::INIT_SPACE,
+        seeds = [b"conversion_config"],
+        bump
+    )]
+    pub conversion_config: Account<'info, ConversionConfig>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub pirate_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"roles", token_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConversionConfig<'info> {
+    #[account(
+        seeds = [b"token_config", conversion_config.admiral_mint.as_ref()],
+        bump = token_config.bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"conversion_config"],
+        bump = conversion_config.bump
+    )]
+    pub conversion_config: Account<'info, ConversionConfig>,
+
+    #[account(
+        seeds = [b"roles", token_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Convert<'info> {
+    #[account(
+        seeds = [b"token_config", mint.key().as_ref()],
+        bump = token_config.bump,
+        has_one = mint
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"conversion_config"],
+        bump = conversion_config.bump,
+        constraint = conversion_config.admiral_mint == mint.key(),
+        constraint = conversion_config.pirate_mint == pirate_mint.key()
+    )]
+    pub conversion_config: Account<'info, ConversionConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + ConversionLimiter::INIT_SPACE,
+        seeds = [b"conversion_limiter", owner.key().as_ref()],
+        bump
+    )]
+    pub conversion_limiter: Account<'info, ConversionLimiter>,
+
+    #[account(mut, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: PDA mint authority, signs the mint CPI
+    #[account(seeds = [b"mint_authority", mint.key().as_ref()], bump)]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = owner,
+        token::token_program = token_program
+    )]
+    pub owner_admiral_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, mint::token_program = pirate_token_program)]
+    pub pirate_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = pirate_mint,
+        token::authority = owner,
+        token::token_program = pirate_token_program
+    )]
+    pub owner_pirate_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: pirate_token's TokenConfig, validated by pirate_token during the burn
+    #[account(mut)]
+    pub pirate_token_config: UncheckedAccount<'info>,
+
+    /// CHECK: pirate_token's BurnCaller for this program, validated by pirate_token during the burn
+    #[account(mut)]
+    pub pirate_burn_caller: UncheckedAccount<'info>,
+
+    /// CHECK: PDA signing the pirate burn on behalf of this program
+    #[account(seeds = [b"burn_authority"], bump)]
+    pub burn_authority: UncheckedAccount<'info>,
+
+    /// CHECK: The pirate_token program
+    #[account(address = PIRATE_TOKEN_PROGRAM_ID)]
+    pub pirate_program: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub pirate_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct GetSupplyInfo<'info> {
+    #[account(
+        seeds = [b"token_config", mint.key().as_ref()],
+        bump = token_config.bump,
+        has_one = mint
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
+#[derive(Accounts)]
+#[instruction(program: Pubkey)]
+pub struct RegisterBurnCaller<'info> {
+    #[account(
+        seeds = [b"token_config", token_config.mint.as_ref()],
+        bump = token_config.bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + BurnCaller::INIT_SPACE,
+        seeds = [b"burn_caller", token_config.mint.as_ref(), program.as_ref()],
+        bump
+    )]
+    pub burn_caller: Account<'info, BurnCaller>,
+    #[account(
+        seeds = [b"roles", token_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Data structures
+#[account]
+#[derive(InitSpace)]
+pub struct TokenConfig {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Option<Pubkey>,
+    pub total_burned: u64,
+    pub burned_shop: u64,
+    pub burned_recharge: u64,
+    pub burned_upgrade: u64,
+    pub burned_fee: u64,
+    pub burned_via_cpi: u64,
+    pub paused_flags: u8,
+    pub mint_limit_per_window: u64,
+    pub mint_limit_window_seconds: i64,
+    pub bump: u8,
+}
+
+impl TokenConfig {
+    pub fn is_paused(&self, category: u8) -> bool {
+        self.paused_flags & category != 0
+    }
+
+    pub fn record_burn(&mut self, amount: u64, reason: BurnReason, via_cpi: bool) -> Result<()> {
+        let counter = match reason {
+            BurnReason::Shop => &mut self.burned_shop,
+            BurnReason::Recharge => &mut self.burned_recharge,
+            BurnReason::Upgrade => &mut self.burned_upgrade,
+            BurnReason::Fee => &mut self.burned_fee,
+        };
+        *counter = counter.checked_add(amount).ok_or(AdmiralTokenError::MathOverflow)?;
+
+        self.total_burned = self.total_burned.checked_add(amount).ok_or(AdmiralTokenError::MathOverflow)?;
+        if via_cpi {
+            self.burned_via_cpi = self.burned_via_cpi.checked_add(amount).ok_or(AdmiralTokenError::MathOverflow)?;
+        }
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct VestingAccount {
+    pub mint: Pubkey,
+    pub beneficiary: Pubkey,
+    pub treasury_token_account: Pubkey,
+    pub vesting_id: u64,
+    pub total_amount: u64,
+    pub released_amount: u64,
+    pub start_ts: i64,
+    pub cliff_ts: i64,
+    pub end_ts: i64,
+    pub schedule: VestingSchedule,
+    pub revocable: bool,
+    pub revoked: bool,
+    pub bump: u8,
+}
+
+impl VestingAccount {
+    pub fn vested_amount(&self, now: i64) -> u64 {
+        if now < self.cliff_ts {
+            return 0;
+        }
+        if now >= self.end_ts || self.revoked {
+            return self.total_amount;
+        }
+
+        let elapsed = match self.schedule {
+            VestingSchedule::Linear => now - self.start_ts,
+            VestingSchedule::Stepped { period_seconds } => {
+                (now - self.start_ts) / period_seconds * period_seconds
+            },
+        };
+        let duration = self.end_ts - self.start_ts;
+
+        ((self.total_amount as u128)
+            .saturating_mul(elapsed as u128)
+            / duration as u128) as u64
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct StakePool {
+    pub stake_mint: Pubkey,
+    pub reward_mint: Pubkey,
+    pub reward_rate_per_second: u64,
+    pub acc_reward_per_share: u128,
+    pub last_update_ts: i64,
+    pub total_staked: u64,
+    pub cooldown_seconds: i64,
+    pub bump: u8,
+}
+
+impl StakePool {
+    pub fn update_rewards(&mut self, now: i64) -> Result<()> {
+        if now <= self.last_update_ts {
+            return Ok(());
+        }
+
+        if self.total_staked > 0 {
+            let elapsed = (now - self.last_update_ts) as u128;
+            let emitted = elapsed
+                .checked_mul(self.reward_rate_per_second as u128)
+                .and_then(|rewards| rewards.checked_mul(REWARD_PRECISION))
+                .ok_or(AdmiralTokenError::MathOverflow)?;
+            self.acc_reward_per_share = self
+                .acc_reward_per_share
+                .checked_add(emitted / self.total_staked as u128)
+                .ok_or(AdmiralTokenError::MathOverflow)?;
+        }
+
+        self.last_update_ts = now;
+        Ok(())
+    }
+}
+
+// player_registry reads `pool`, `owner` and `amount` from the front of this account,
+// so those fields must keep their position
+#[account]
+#[derive(InitSpace)]
+pub struct StakeAccount {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub cooling_amount: u64,
+    pub unstake_available_at: i64,
+    pub reward_debt: u128,
+    pub pending_rewards: u64,
+    pub bump: u8,
+}
+
+impl StakeAccount {
+    pub fn accrued_rewards(&self, stake_pool: &StakePool) -> u128 {
+        (self.amount as u128).saturating_mul(stake_pool.acc_reward_per_share) / REWARD_PRECISION
+    }
+
+    pub fn settle_rewards(&mut self, stake_pool: &StakePool) -> Result<()> {
+        let earned = self.accrued_rewards(stake_pool).saturating_sub(self.reward_debt);
+        self.pending_rewards = self
+            .pending_rewards
+            .checked_add(u64::try_from(earned).map_err(|_| AdmiralTokenError::MathOverflow)?)
+            .ok_or(AdmiralTokenError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ConversionConfig {
+    pub admiral_mint: Pubkey,
+    pub pirate_mint: Pubkey,
+    pub rate_numerator: u64,
+    pub rate_denominator: u64,
+    pub wallet_daily_cap: u64,
+    pub epoch_budget: u64,
+    pub epoch_duration_seconds: i64,
+    pub current_epoch: i64,
+    pub epoch_converted: u64,
+    pub total_pirate_burned: u64,
+    pub total_admiral_minted: u64,
+    pub bump: u8,
+}
+
+impl ConversionConfig {
+    pub fn apply(&mut self, params: &ConversionParams) {
+        self.rate_numerator = params.rate_numerator;
+        self.rate_denominator = params.rate_denominator;
+        self.wallet_daily_cap = params.wallet_daily_cap;
+        self.epoch_budget = params.epoch_budget;
+        self.epoch_duration_seconds = params.epoch_duration_seconds;
+    }
+
+    // $ADMIRAL base units minted for the given $PIRATE base units
+    pub fn quote(&self, pirate_amount: u64) -> Result<u64> {
+        let admiral_amount = (pirate_amount as u128)
+            .checked_mul(self.rate_numerator as u128)
+            .ok_or(AdmiralTokenError::MathOverflow)?
+            / self.rate_denominator as u128;
+        u64::try_from(admiral_amount).map_err(|_| AdmiralTokenError::MathOverflow.into())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ConversionLimiter {
+    pub owner: Pubkey,
+    pub day: i64,
+    pub converted_today: u64,
+    pub bump: u8,
+}
+
+// Return data of get_supply_info
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SupplyInfo {
+    pub mint: Pubkey,
+    pub decimals: u8,
+    pub supply: u64,
+    pub total_burned: u64,
+    pub burned_shop: u64,
+    pub burned_recharge: u64,
+    pub burned_upgrade: u64,
+    pub burned_fee: u64,
+    pub burned_via_cpi: u64,
+    pub paused_flags: u8,
+    pub mint_limit_per_window: u64,
+    pub mint_limit_window_seconds: i64,
+}
+
+// Instruction arguments
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct MintExtensionArgs {
+    pub transfer_fee: Option<TransferFeeArgs>,
+    pub metadata: Option<TokenMetadataArgs>,
+}
+
+impl MintExtensionArgs {
+    pub fn is_empty(&self) -> bool {
+        self.transfer_fee.is_none() && self.metadata.is_none()
+    }
+
+    pub fn extension_types(&self) -> Vec<ExtensionType> {
+        let mut extension_types = Vec::new();
+        if self.transfer_fee.is_some() {
+            extension_types.push(ExtensionType::TransferFeeConfig);
+        }
+        if self.metadata.is_some() {
+            extension_types.push(ExtensionType::MetadataPointer);
+        }
+        extension_types
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TransferFeeArgs {
+    pub basis_points: u16,
+    pub maximum_fee: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TokenMetadataArgs {
+    pub uri: String,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ConversionParams {
+    pub rate_numerator: u64,
+    pub rate_denominator: u64,
+    pub wallet_daily_cap: u64,
+    pub epoch_budget: u64,
+    pub epoch_duration_seconds: i64,
+}
+
+impl ConversionParams {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.rate_numerator > 0 && self.rate_denominator > 0 && self.epoch_duration_seconds > 0,
+            AdmiralTokenError::InvalidConversionConfig
+        );
+        Ok(())
+    }
+}
+
+// Enums
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum BurnReason {
+    Shop,
+    Recharge,
+    Upgrade,
+    Fee,
+}
+
+impl BurnReason {
+    pub fn to_string(&self) -> String {
+        match self {
+            BurnReason::Shop => "shop".to_string(),
+            BurnReason::Recharge => "recharge".to_string(),
+            BurnReason::Upgrade => "upgrade".to_string(),
+            BurnReason::Fee => "fee".to_string(),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum VestingSchedule {
+    Linear,
+    Stepped { period_seconds: i64 },
+}
+
+// Events
+#[event]
+pub struct MintLimitUpdated {
+    pub mint: Pubkey,
+    pub limit_per_window: u64,
+    pub window_seconds: i64,
+}
+
+#[event]
+pub struct MintOverrideGranted {
+    pub mint: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub override_allowance: u64,
+}
+
+#[event]
+pub struct AuthorityTransferProposed {
+    pub mint: Pubkey,
+    pub current_authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub mint: Pubkey,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct PauseStateChanged {
+    pub mint: Pubkey,
+    pub pauser: Pubkey,
+    pub paused_flags: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BatchMinted {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub recipients: u16,
+    pub total_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BatchTransferred {
+    pub mint: Pubkey,
+    pub from: Pubkey,
+    pub recipients: u16,
+    pub total_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokensBurned {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub reason: BurnReason,
+    pub caller_program: Option<Pubkey>,
+    pub total_burned: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestingCreated {
+    pub vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub start_ts: i64,
+    pub cliff_ts: i64,
+    pub end_ts: i64,
+    pub revocable: bool,
+}
+
+#[event]
+pub struct VestingReleased {
+    pub vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub released_amount: u64,
+}
+
+#[event]
+pub struct VestingRevoked {
+    pub vesting_account: Pubkey,
+    pub beneficiary: Pubkey,
+    pub returned_amount: u64,
+    pub vested_amount: u64,
+}
+
+#[event]
+pub struct Staked {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub total_staked: u64,
+}
+
+#[event]
+pub struct UnstakeRequested {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub available_at: i64,
+}
+
+#[event]
+pub struct Unstaked {
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct StakingRewardsClaimed {
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ConversionConfigUpdated {
+    pub rate_numerator: u64,
+    pub rate_denominator: u64,
+    pub wallet_daily_cap: u64,
+    pub epoch_budget: u64,
+    pub epoch_duration_seconds: i64,
+}
+
+#[event]
+pub struct TokensConverted {
+    pub owner: Pubkey,
+    pub pirate_burned: u64,
+    pub admiral_minted: u64,
+    pub epoch: i64,
+    pub epoch_converted: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoleGranted {
+    pub config: Pubkey,
+    pub member: Pubkey,
+    pub role: Role,
+    pub granted_by: Pubkey,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub config: Pubkey,
+    pub member: Pubkey,
+    pub role: Role,
+    pub revoked_by: Pubkey,
+}
+
+#[event]
+pub struct BurnCallerRegistered {
+    pub mint: Pubkey,
+    pub program: Pubkey,
+    pub authority: Pubkey,
+}
+
+// Error codes
+#[error_code]
+pub enum AdmiralTokenError {
+    #[msg("Amount must be greater than zero")]
+    InvalidAmount,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("Mint extensions require the Token-2022 program")]
+    ExtensionsRequireToken2022,
+    #[msg("Transfer fee exceeds the maximum allowed")]
+    InvalidTransferFee,
+    #[msg("This operation is paused")]
+    Paused,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Signer is not the pending authority")]
+    NotPendingAuthority,
+    #[msg("Invalid vesting schedule")]
+    InvalidVestingSchedule,
+    #[msg("Nothing to release yet")]
+    NothingToRelease,
+    #[msg("Vesting grant is not revocable")]
+    VestingNotRevocable,
+    #[msg("Vesting grant already revoked")]
+    VestingAlreadyRevoked,
+    #[msg("Invalid staking pool configuration")]
+    InvalidStakeConfig,
+    #[msg("Nothing to withdraw")]
+    NothingToWithdraw,
+    #[msg("Unstake cooldown has not elapsed")]
+    CooldownNotElapsed,
+    #[msg("Invalid conversion configuration")]
+    InvalidConversionConfig,
+    #[msg("Conversion amount too small")]
+    ConversionTooSmall,
+    #[msg("Conversion budget for this epoch exceeded")]
+    EpochBudgetExceeded,
+    #[msg("Daily conversion cap for this wallet exceeded")]
+    WalletDailyCapExceeded,
+    #[msg("Invalid mint limit")]
+    InvalidMintLimit,
+    #[msg("Mint limit for this recipient exceeded")]
+    MintLimitExceeded,
+    #[msg("Signer is missing the required role")]
+    MissingRole,
+    #[msg("Batch amounts and recipient accounts don't match or exceed the batch limit")]
+    InvalidBatch,
+    #[msg("Batch recipient is not a token account for this mint")]
+    InvalidBatchRecipient,
+    #[msg("Mint limiter account does not match the recipient")]
+    InvalidMintLimiter,
+    #[msg("Insufficient balance for the batch")]
+    InsufficientBalance,
+    #[msg("Burn caller is not registered or its burn authority didn't sign")]
+    InvalidBurnCaller,
+    #[msg("The Admin role belongs to the config authority and can't be granted")]
+    AdminRoleReserved,
 }
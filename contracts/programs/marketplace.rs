@@ -0,0 +1,1313 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+    state::Mint as MintState,
+};
+use anchor_spl::token_interface::{
+    self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+declare_id!("KaboomMarket11111111111111111111111111111111");
+
+// Royalty to the game treasury is capped at 10%
+pub const MAX_ROYALTY_BPS: u16 = 1_000;
+
+// Pause categories for the emergency switch. Cancelling stays open so sellers can
+// always take their items back.
+pub const PAUSE_LISTINGS: u8 = 1 << 0;
+pub const PAUSE_SALES: u8 = 1 << 1;
+
+// player_registry owns the skin records and inventories that can be listed
+pub const PLAYER_REGISTRY_PROGRAM_ID: Pubkey = pubkey!("PLYRrgstry111111111111111111111111111111111");
+// The token programs whose TokenConfig vouches for the payment mints
+pub const PIRATE_TOKEN_PROGRAM_ID: Pubkey = pubkey!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+pub const ADMIRAL_TOKEN_PROGRAM_ID: Pubkey = pubkey!("AdmiralToken111111111111111111111111111111111");
+
+#[program]
+pub mod marketplace {
+    use super::*;
+
+    /// Initialize the marketplace config (upgrade authority only)
+    pub fn initialize_market(
+        ctx: Context<InitializeMarket>,
+        treasury: Pubkey,
+        royalty_bps: u16,
+    ) -> Result<()> {
+        require!(royalty_bps <= MAX_ROYALTY_BPS, MarketplaceError::InvalidRoyalty);
+
+        let market_config = &mut ctx.accounts.market_config;
+        market_config.authority = ctx.accounts.authority.key();
+        market_config.pending_authority = None;
+        market_config.pauser = ctx.accounts.authority.key();
+        market_config.paused_flags = 0;
+        market_config.treasury = treasury;
+        market_config.royalty_bps = royalty_bps;
+        market_config.pirate_mint = ctx.accounts.pirate_mint.key();
+        market_config.admiral_mint = ctx.accounts.admiral_mint.key();
+        market_config.total_listings = 0;
+        market_config.total_sales = 0;
+        market_config.pirate_volume = 0;
+        market_config.admiral_volume = 0;
+        market_config.pirate_royalties = 0;
+        market_config.admiral_royalties = 0;
+        market_config.bump = ctx.bumps.market_config;
+
+        msg!("Marketplace initialized with {} bps royalty", royalty_bps);
+        Ok(())
+    }
+
+    /// Update the treasury and royalty (authority only)
+    pub fn update_market_config(
+        ctx: Context<UpdateMarketConfig>,
+        treasury: Pubkey,
+        royalty_bps: u16,
+    ) -> Result<()> {
+        require!(royalty_bps <= MAX_ROYALTY_BPS, MarketplaceError::InvalidRoyalty);
+
+        let market_config = &mut ctx.accounts.market_config;
+        market_config.treasury = treasury;
+        market_config.royalty_bps = royalty_bps;
+
+        emit!(MarketConfigUpdated {
+            treasury,
+            royalty_bps,
+        });
+
+        msg!("Marketplace royalty set to {} bps", royalty_bps);
+        Ok(())
+    }
+
+    /// Propose a new market authority (step one of the authority transfer)
+    pub fn propose_authority(
+        ctx: Context<ProposeAuthority>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        let market_config = &mut ctx.accounts.market_config;
+        market_config.pending_authority = Some(new_authority);
+
+        emit!(AuthorityTransferProposed {
+            current_authority: market_config.authority,
+            pending_authority: new_authority,
+        });
+
+        msg!("Market authority transfer proposed to: {}", new_authority);
+        Ok(())
+    }
+
+    /// Accept the market authority (step two of the authority transfer)
+    pub fn accept_authority(
+        ctx: Context<AcceptAuthority>,
+    ) -> Result<()> {
+        let market_config = &mut ctx.accounts.market_config;
+        let new_authority = ctx.accounts.new_authority.key();
+
+        require!(
+            market_config.pending_authority == Some(new_authority),
+            MarketplaceError::NotPendingAuthority
+        );
+
+        let previous_authority = market_config.authority;
+        market_config.authority = new_authority;
+        market_config.pending_authority = None;
+
+        emit!(AuthorityTransferred {
+            previous_authority,
+            new_authority,
+        });
+
+        msg!("Market authority transferred to: {}", new_authority);
+        Ok(())
+    }
+
+    /// Set the key allowed to pause the market (authority only). Kept apart from the
+    /// authority so pausing isn't delayed when the authority is a timelock.
+    pub fn set_pauser(
+        ctx: Context<UpdateMarketConfig>,
+        pauser: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.market_config.pauser = pauser;
+
+        msg!("Market pauser set to: {}", pauser);
+        Ok(())
+    }
+
+    /// Set the pause flags (pauser or authority)
+    pub fn set_paused(
+        ctx: Context<SetPaused>,
+        paused_flags: u8,
+    ) -> Result<()> {
+        let market_config = &mut ctx.accounts.market_config;
+        let signer = ctx.accounts.pauser.key();
+        require!(
+            signer == market_config.pauser || signer == market_config.authority,
+            MarketplaceError::Unauthorized
+        );
+        market_config.paused_flags = paused_flags;
+
+        emit!(PauseStateChanged {
+            pauser: signer,
+            paused_flags,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Market pause flags set to: {:#04b}", paused_flags);
+        Ok(())
+    }
+
+    /// List a skin NFT for sale; it is held in escrow until sold or cancelled
+    pub fn list_item(
+        ctx: Context<ListItem>,
+        amount: u64,
+        price: u64,
+        currency: Currency,
+    ) -> Result<()> {
+        require!(amount > 0 && price > 0, MarketplaceError::InvalidAmount);
+        require!(
+            !ctx.accounts.market_config.is_paused(PAUSE_LISTINGS),
+            MarketplaceError::Paused
+        );
+        // A fee-bearing mint would leave the escrow short of listing.amount
+        require!(
+            !has_transfer_fee(&ctx.accounts.item_mint)?,
+            MarketplaceError::InvalidItemMint
+        );
+
+        let listing = &mut ctx.accounts.listing;
+        listing.seller = ctx.accounts.seller.key();
+        listing.item_mint = ctx.accounts.item_mint.key();
+        listing.amount = amount;
+        listing.price = price;
+        listing.currency = currency;
+        listing.created_at = Clock::get()?.unix_timestamp;
+        listing.bump = ctx.bumps.listing;
+        listing.escrow_bump = ctx.bumps.escrow;
+
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.item_token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.seller_item_account.to_account_info(),
+                mint: ctx.accounts.item_mint.to_account_info(),
+                to: ctx.accounts.escrow.to_account_info(),
+                authority: ctx.accounts.seller.to_account_info(),
+            },
+        );
+        token_interface::transfer_checked(transfer_ctx, amount, ctx.accounts.item_mint.decimals)?;
+
+        let market_config = &mut ctx.accounts.market_config;
+        market_config.total_listings = market_config.total_listings.saturating_add(1);
+
+        emit!(ItemListed {
+            listing: listing.key(),
+            seller: listing.seller,
+            item_mint: listing.item_mint,
+            amount,
+            price,
+            currency,
+            total_listings: market_config.total_listings,
+        });
+
+        msg!("Listed {} of {} for {} {}", amount, listing.item_mint, price, currency.to_string());
+        Ok(())
+    }
+
+    /// Change the asking price of a listing
+    pub fn update_price(
+        ctx: Context<UpdatePrice>,
+        new_price: u64,
+    ) -> Result<()> {
+        require!(new_price > 0, MarketplaceError::InvalidAmount);
+
+        let listing = &mut ctx.accounts.listing;
+        let old_price = listing.price;
+        listing.price = new_price;
+
+        emit!(ListingPriceUpdated {
+            listing: listing.key(),
+            old_price,
+            new_price,
+        });
+
+        msg!("Listing price updated: {} -> {}", old_price, new_price);
+        Ok(())
+    }
+
+    /// Cancel a listing and return the escrowed items to the seller
+    pub fn cancel_listing(
+        ctx: Context<CancelListing>,
+    ) -> Result<()> {
+        let listing = &ctx.accounts.listing;
+        release_escrow(
+            listing,
+            &ctx.accounts.escrow,
+            &ctx.accounts.item_mint,
+            &ctx.accounts.seller_item_account.to_account_info(),
+            &ctx.accounts.seller.to_account_info(),
+            &ctx.accounts.item_token_program,
+        )?;
+
+        emit!(ListingCancelled {
+            listing: listing.key(),
+            seller: listing.seller,
+            item_mint: listing.item_mint,
+        });
+
+        msg!("Listing cancelled: {}", listing.key());
+        Ok(())
+    }
+
+    /// Buy a whole listing: pay the seller, send the royalty to the treasury and
+    /// release the escrowed items to the buyer
+    pub fn buy_item(
+        ctx: Context<BuyItem>,
+        max_price: u64,
+    ) -> Result<()> {
+        let listing = &ctx.accounts.listing;
+        let market_config = &ctx.accounts.market_config;
+        require!(!market_config.is_paused(PAUSE_SALES), MarketplaceError::Paused);
+
+        // Protects the buyer from a price update landing first
+        require!(listing.price <= max_price, MarketplaceError::PriceChanged);
+        require_keys_eq!(
+            ctx.accounts.payment_mint.key(),
+            market_config.mint_for(listing.currency),
+            MarketplaceError::InvalidPaymentMint
+        );
+
+        let royalty = pay_for_listing(
+            market_config,
+            listing.price,
+            &ctx.accounts.payment_mint,
+            &ctx.accounts.buyer_payment_account,
+            &ctx.accounts.seller_payment_account,
+            &ctx.accounts.treasury_payment_account,
+            &ctx.accounts.buyer,
+            &ctx.accounts.payment_token_program,
+        )?;
+
+        release_escrow(
+            listing,
+            &ctx.accounts.escrow,
+            &ctx.accounts.item_mint,
+            &ctx.accounts.buyer_item_account.to_account_info(),
+            &ctx.accounts.seller.to_account_info(),
+            &ctx.accounts.item_token_program,
+        )?;
+
+        let market_config = &mut ctx.accounts.market_config;
+        market_config.record_sale(listing.currency, listing.price, royalty);
+
+        emit!(ItemSold {
+            listing: listing.key(),
+            seller: listing.seller,
+            buyer: ctx.accounts.buyer.key(),
+            item_mint: listing.item_mint,
+            amount: listing.amount,
+            price: listing.price,
+            royalty,
+            currency: listing.currency,
+            total_sales: market_config.total_sales,
+            total_volume: market_config.volume_for(listing.currency),
+            total_royalties: market_config.royalties_for(listing.currency),
+        });
+
+        msg!("Sold {} of {} for {} {}", listing.amount, listing.item_mint, listing.price, listing.currency.to_string());
+        Ok(())
+    }
+
+    /// List shop items from the seller's inventory; the quantity is taken out of
+    /// the inventory until the listing is sold or cancelled
+    pub fn list_inventory_item(
+        ctx: Context<ListInventoryItem>,
+        item_id: u16,
+        quantity: u32,
+        price: u64,
+        currency: Currency,
+    ) -> Result<()> {
+        require!(quantity > 0 && price > 0, MarketplaceError::InvalidAmount);
+        require!(
+            !ctx.accounts.market_config.is_paused(PAUSE_LISTINGS),
+            MarketplaceError::Paused
+        );
+
+        let item_listing = &mut ctx.accounts.item_listing;
+        item_listing.seller = ctx.accounts.seller.key();
+        item_listing.item_id = item_id;
+        item_listing.quantity = quantity;
+        item_listing.price = price;
+        item_listing.currency = currency;
+        item_listing.created_at = Clock::get()?.unix_timestamp;
+        item_listing.bump = ctx.bumps.item_listing;
+
+        let instruction = Instruction {
+            program_id: PLAYER_REGISTRY_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(ctx.accounts.shop_item.key(), false),
+                AccountMeta::new(ctx.accounts.seller_inventory.key(), false),
+                AccountMeta::new_readonly(ctx.accounts.seller.key(), true),
+                AccountMeta::new_readonly(ctx.accounts.market_authority.key(), true),
+            ],
+            data: registry_item_data(b"global:escrow_market_item", quantity),
+        };
+        invoke_signed(
+            &instruction,
+            &[
+                ctx.accounts.shop_item.to_account_info(),
+                ctx.accounts.seller_inventory.to_account_info(),
+                ctx.accounts.seller.to_account_info(),
+                ctx.accounts.market_authority.to_account_info(),
+                ctx.accounts.registry_program.to_account_info(),
+            ],
+            &[&[b"market_authority", &[ctx.bumps.market_authority]]],
+        )?;
+
+        let market_config = &mut ctx.accounts.market_config;
+        market_config.total_listings = market_config.total_listings.saturating_add(1);
+
+        emit!(InventoryItemListed {
+            listing: item_listing.key(),
+            seller: item_listing.seller,
+            item_id,
+            quantity,
+            price,
+            currency,
+            total_listings: market_config.total_listings,
+        });
+
+        msg!("Listed {} x item {} for {} {}", quantity, item_id, price, currency.to_string());
+        Ok(())
+    }
+
+    /// Cancel an inventory item listing and return the items to the seller
+    pub fn cancel_item_listing(
+        ctx: Context<CancelItemListing>,
+    ) -> Result<()> {
+        let item_listing = &ctx.accounts.item_listing;
+        release_inventory_item(
+            item_listing.quantity,
+            &ctx.accounts.shop_item,
+            &ctx.accounts.seller_inventory,
+            &ctx.accounts.seller.to_account_info(),
+            &ctx.accounts.market_authority,
+            ctx.bumps.market_authority,
+            &ctx.accounts.registry_program,
+            &ctx.accounts.system_program,
+        )?;
+
+        emit!(InventoryItemListingCancelled {
+            listing: item_listing.key(),
+            seller: item_listing.seller,
+            item_id: item_listing.item_id,
+        });
+
+        msg!("Item listing cancelled: {}", item_listing.key());
+        Ok(())
+    }
+
+    /// Buy a whole inventory item listing: pay the seller and treasury like buy_item,
+    /// then deliver the items to the buyer's inventory
+    pub fn buy_inventory_item(
+        ctx: Context<BuyInventoryItem>,
+        max_price: u64,
+    ) -> Result<()> {
+        let item_listing = &ctx.accounts.item_listing;
+        let market_config = &ctx.accounts.market_config;
+        require!(!market_config.is_paused(PAUSE_SALES), MarketplaceError::Paused);
+
+        require!(item_listing.price <= max_price, MarketplaceError::PriceChanged);
+        require_keys_eq!(
+            ctx.accounts.payment_mint.key(),
+            market_config.mint_for(item_listing.currency),
+            MarketplaceError::InvalidPaymentMint
+        );
+
+        let royalty = pay_for_listing(
+            market_config,
+            item_listing.price,
+            &ctx.accounts.payment_mint,
+            &ctx.accounts.buyer_payment_account,
+            &ctx.accounts.seller_payment_account,
+            &ctx.accounts.treasury_payment_account,
+            &ctx.accounts.buyer,
+            &ctx.accounts.payment_token_program,
+        )?;
+
+        release_inventory_item(
+            item_listing.quantity,
+            &ctx.accounts.shop_item,
+            &ctx.accounts.buyer_inventory,
+            &ctx.accounts.buyer.to_account_info(),
+            &ctx.accounts.market_authority,
+            ctx.bumps.market_authority,
+            &ctx.accounts.registry_program,
+            &ctx.accounts.system_program,
+        )?;
+
+        let market_config = &mut ctx.accounts.market_config;
+        market_config.record_sale(item_listing.currency, item_listing.price, royalty);
+
+        emit!(InventoryItemSold {
+            listing: item_listing.key(),
+            seller: item_listing.seller,
+            buyer: ctx.accounts.buyer.key(),
+            item_id: item_listing.item_id,
+            quantity: item_listing.quantity,
+            price: item_listing.price,
+            royalty,
+            currency: item_listing.currency,
+            total_sales: market_config.total_sales,
+            total_volume: market_config.volume_for(item_listing.currency),
+            total_royalties: market_config.royalties_for(item_listing.currency),
+        });
+
+        msg!("Sold {} x item {} for {} {}", item_listing.quantity, item_listing.item_id, item_listing.price, item_listing.currency.to_string());
+        Ok(())
+    }
+}
+
+// Helper functions
+// Moves the escrowed items out and closes the escrow, signed by the listing PDA.
+// Rent from the escrow goes back to the seller, who paid it.
+fn release_escrow<'info>(
+    listing: &Account<'info, Listing>,
+    escrow: &InterfaceAccount<'info, TokenAccount>,
+    item_mint: &InterfaceAccount<'info, Mint>,
+    to: &AccountInfo<'info>,
+    seller: &AccountInfo<'info>,
+    item_token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    let item_mint_key = listing.item_mint;
+    let seller_key = listing.seller;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"listing",
+        item_mint_key.as_ref(),
+        seller_key.as_ref(),
+        &[listing.bump],
+    ]];
+
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            item_token_program.to_account_info(),
+            TransferChecked {
+                from: escrow.to_account_info(),
+                mint: item_mint.to_account_info(),
+                to: to.clone(),
+                authority: listing.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        listing.amount,
+        item_mint.decimals,
+    )?;
+
+    token_interface::close_account(CpiContext::new_with_signer(
+        item_token_program.to_account_info(),
+        CloseAccount {
+            account: escrow.to_account_info(),
+            destination: seller.clone(),
+            authority: listing.to_account_info(),
+        },
+        signer_seeds,
+    ))
+}
+
+// Pays the seller and the treasury royalty for a listing; returns the royalty
+#[allow(clippy::too_many_arguments)]
+fn pay_for_listing<'info>(
+    market_config: &MarketConfig,
+    price: u64,
+    payment_mint: &InterfaceAccount<'info, Mint>,
+    buyer_payment_account: &InterfaceAccount<'info, TokenAccount>,
+    seller_payment_account: &InterfaceAccount<'info, TokenAccount>,
+    treasury_payment_account: &InterfaceAccount<'info, TokenAccount>,
+    buyer: &Signer<'info>,
+    payment_token_program: &Interface<'info, TokenInterface>,
+) -> Result<u64> {
+    let royalty = market_config.royalty_on(price);
+    let seller_proceeds = price - royalty;
+
+    token_interface::transfer_checked(
+        CpiContext::new(
+            payment_token_program.to_account_info(),
+            TransferChecked {
+                from: buyer_payment_account.to_account_info(),
+                mint: payment_mint.to_account_info(),
+                to: seller_payment_account.to_account_info(),
+                authority: buyer.to_account_info(),
+            },
+        ),
+        seller_proceeds,
+        payment_mint.decimals,
+    )?;
+
+    if royalty > 0 {
+        token_interface::transfer_checked(
+            CpiContext::new(
+                payment_token_program.to_account_info(),
+                TransferChecked {
+                    from: buyer_payment_account.to_account_info(),
+                    mint: payment_mint.to_account_info(),
+                    to: treasury_payment_account.to_account_info(),
+                    authority: buyer.to_account_info(),
+                },
+            ),
+            royalty,
+            payment_mint.decimals,
+        )?;
+    }
+
+    Ok(royalty)
+}
+
+fn has_transfer_fee(mint: &InterfaceAccount<Mint>) -> Result<bool> {
+    let mint_info = mint.to_account_info();
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<MintState>::unpack(&mint_data)?;
+    Ok(mint_state
+        .get_extension_types()?
+        .contains(&ExtensionType::TransferFeeConfig))
+}
+
+// Instruction data for player_registry's escrow_market_item / release_market_item
+fn registry_item_data(sighash_preimage: &[u8], quantity: u32) -> Vec<u8> {
+    let mut data = hash(sighash_preimage).to_bytes()[..8].to_vec();
+    data.extend_from_slice(&quantity.to_le_bytes());
+    data
+}
+
+// CPI into player_registry::release_market_item, signed by the market authority.
+// The recipient also pays for their inventory if it doesn't exist yet.
+#[allow(clippy::too_many_arguments)]
+fn release_inventory_item<'info>(
+    quantity: u32,
+    shop_item: &UncheckedAccount<'info>,
+    inventory: &UncheckedAccount<'info>,
+    recipient: &AccountInfo<'info>,
+    market_authority: &UncheckedAccount<'info>,
+    market_authority_bump: u8,
+    registry_program: &UncheckedAccount<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let instruction = Instruction {
+        program_id: PLAYER_REGISTRY_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(shop_item.key(), false),
+            AccountMeta::new(inventory.key(), false),
+            AccountMeta::new_readonly(recipient.key(), false),
+            AccountMeta::new_readonly(market_authority.key(), true),
+            AccountMeta::new(recipient.key(), true),
+            AccountMeta::new_readonly(system_program.key(), false),
+        ],
+        data: registry_item_data(b"global:release_market_item", quantity),
+    };
+    invoke_signed(
+        &instruction,
+        &[
+            shop_item.to_account_info(),
+            inventory.to_account_info(),
+            recipient.clone(),
+            market_authority.to_account_info(),
+            system_program.to_account_info(),
+            registry_program.to_account_info(),
+        ],
+        &[&[b"market_authority", &[market_authority_bump]]],
+    )?;
+    Ok(())
+}
+
+// Account structures
+#[derive(Accounts)]
+pub struct InitializeMarket<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + MarketConfig::INIT_SPACE,
+        seeds = [b"market_config"],
+        bump
+    )]
+    pub market_config: Account<'info, MarketConfig>,
+
+    pub pirate_mint: InterfaceAccount<'info, Mint>,
+    pub admiral_mint: InterfaceAccount<'info, Mint>,
+
+    // Each payment mint must have a TokenConfig in its token program
+    /// CHECK: pirate_token's TokenConfig PDA for pirate_mint
+    #[account(
+        seeds = [b"token_config", pirate_mint.key().as_ref()],
+        bump,
+        seeds::program = PIRATE_TOKEN_PROGRAM_ID,
+        owner = PIRATE_TOKEN_PROGRAM_ID @ MarketplaceError::InvalidPaymentMint
+    )]
+    pub pirate_token_config: UncheckedAccount<'info>,
+    /// CHECK: admiral_token's TokenConfig PDA for admiral_mint
+    #[account(
+        seeds = [b"token_config", admiral_mint.key().as_ref()],
+        bump,
+        seeds::program = ADMIRAL_TOKEN_PROGRAM_ID,
+        owner = ADMIRAL_TOKEN_PROGRAM_ID @ MarketplaceError::InvalidPaymentMint
+    )]
+    pub admiral_token_config: UncheckedAccount<'info>,
+
+    // The market config can only be created once, so only the upgrade authority may create it
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::Marketplace>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+            @ MarketplaceError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMarketConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"market_config"],
+        bump = market_config.bump,
+        has_one = authority @ MarketplaceError::Unauthorized
+    )]
+    pub market_config: Account<'info, MarketConfig>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"market_config"],
+        bump = market_config.bump,
+        has_one = authority @ MarketplaceError::Unauthorized
+    )]
+    pub market_config: Account<'info, MarketConfig>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(mut, seeds = [b"market_config"], bump = market_config.bump)]
+    pub market_config: Account<'info, MarketConfig>,
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(mut, seeds = [b"market_config"], bump = market_config.bump)]
+    pub market_config: Account<'info, MarketConfig>,
+    pub pauser: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ListItem<'info> {
+    #[account(mut, seeds = [b"market_config"], bump = market_config.bump)]
+    pub market_config: Account<'info, MarketConfig>,
+
+    #[account(
+        init,
+        payer = seller,
+        space = 8 + Listing::INIT_SPACE,
+        seeds = [b"listing", item_mint.key().as_ref(), seller.key().as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, Listing>,
+
+    #[account(
+        init,
+        payer = seller,
+        seeds = [b"escrow", listing.key().as_ref()],
+        bump,
+        token::mint = item_mint,
+        token::authority = listing,
+        token::token_program = item_token_program
+    )]
+    pub escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mint::token_program = item_token_program)]
+    pub item_mint: InterfaceAccount<'info, Mint>,
+
+    // Only skins minted by player_registry can be listed
+    /// CHECK: player_registry's Skin record for item_mint
+    #[account(
+        seeds = [b"skin", item_mint.key().as_ref()],
+        bump,
+        seeds::program = PLAYER_REGISTRY_PROGRAM_ID,
+        owner = PLAYER_REGISTRY_PROGRAM_ID @ MarketplaceError::InvalidItemMint
+    )]
+    pub skin: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = item_mint,
+        token::authority = seller,
+        token::token_program = item_token_program
+    )]
+    pub seller_item_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    pub item_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePrice<'info> {
+    #[account(
+        mut,
+        seeds = [b"listing", listing.item_mint.as_ref(), seller.key().as_ref()],
+        bump = listing.bump,
+        has_one = seller @ MarketplaceError::Unauthorized
+    )]
+    pub listing: Account<'info, Listing>,
+    pub seller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelListing<'info> {
+    #[account(
+        mut,
+        close = seller,
+        seeds = [b"listing", item_mint.key().as_ref(), seller.key().as_ref()],
+        bump = listing.bump,
+        has_one = seller @ MarketplaceError::Unauthorized
+    )]
+    pub listing: Account<'info, Listing>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", listing.key().as_ref()],
+        bump = listing.escrow_bump
+    )]
+    pub escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mint::token_program = item_token_program)]
+    pub item_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = item_mint,
+        token::authority = seller,
+        token::token_program = item_token_program
+    )]
+    pub seller_item_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    pub item_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct BuyItem<'info> {
+    #[account(mut, seeds = [b"market_config"], bump = market_config.bump)]
+    pub market_config: Account<'info, MarketConfig>,
+
+    #[account(
+        mut,
+        close = seller,
+        seeds = [b"listing", item_mint.key().as_ref(), seller.key().as_ref()],
+        bump = listing.bump,
+        has_one = seller
+    )]
+    pub listing: Account<'info, Listing>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", listing.key().as_ref()],
+        bump = listing.escrow_bump
+    )]
+    pub escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mint::token_program = item_token_program)]
+    pub item_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = item_mint,
+        associated_token::authority = buyer,
+        associated_token::token_program = item_token_program
+    )]
+    pub buyer_item_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mint::token_program = payment_token_program)]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = buyer,
+        token::token_program = payment_token_program
+    )]
+    pub buyer_payment_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = seller,
+        token::token_program = payment_token_program
+    )]
+    pub seller_payment_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = market_config.treasury,
+        token::token_program = payment_token_program
+    )]
+    pub treasury_payment_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Listing seller, receives the listing and escrow rent
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    pub item_token_program: Interface<'info, TokenInterface>,
+    pub payment_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(item_id: u16)]
+pub struct ListInventoryItem<'info> {
+    #[account(mut, seeds = [b"market_config"], bump = market_config.bump)]
+    pub market_config: Account<'info, MarketConfig>,
+
+    #[account(
+        init,
+        payer = seller,
+        space = 8 + ItemListing::INIT_SPACE,
+        seeds = [b"item_listing", item_id.to_le_bytes().as_ref(), seller.key().as_ref()],
+        bump
+    )]
+    pub item_listing: Account<'info, ItemListing>,
+
+    /// CHECK: player_registry's ShopItem for item_id, validated by player_registry
+    #[account(
+        seeds = [b"shop_item", item_id.to_le_bytes().as_ref()],
+        bump,
+        seeds::program = PLAYER_REGISTRY_PROGRAM_ID
+    )]
+    pub shop_item: UncheckedAccount<'info>,
+    /// CHECK: The seller's player_registry Inventory, validated by player_registry
+    #[account(mut)]
+    pub seller_inventory: UncheckedAccount<'info>,
+
+    /// CHECK: PDA that signs inventory moves into and out of listings
+    #[account(seeds = [b"market_authority"], bump)]
+    pub market_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    /// CHECK: The player_registry program
+    #[account(address = PLAYER_REGISTRY_PROGRAM_ID)]
+    pub registry_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelItemListing<'info> {
+    #[account(
+        mut,
+        close = seller,
+        seeds = [b"item_listing", item_listing.item_id.to_le_bytes().as_ref(), seller.key().as_ref()],
+        bump = item_listing.bump,
+        has_one = seller @ MarketplaceError::Unauthorized
+    )]
+    pub item_listing: Account<'info, ItemListing>,
+
+    /// CHECK: player_registry's ShopItem for the listed item
+    #[account(
+        seeds = [b"shop_item", item_listing.item_id.to_le_bytes().as_ref()],
+        bump,
+        seeds::program = PLAYER_REGISTRY_PROGRAM_ID
+    )]
+    pub shop_item: UncheckedAccount<'info>,
+    /// CHECK: The seller's player_registry Inventory, validated by player_registry
+    #[account(mut)]
+    pub seller_inventory: UncheckedAccount<'info>,
+
+    /// CHECK: PDA that signs inventory moves into and out of listings
+    #[account(seeds = [b"market_authority"], bump)]
+    pub market_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    /// CHECK: The player_registry program
+    #[account(address = PLAYER_REGISTRY_PROGRAM_ID)]
+    pub registry_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BuyInventoryItem<'info> {
+    #[account(mut, seeds = [b"market_config"], bump = market_config.bump)]
+    pub market_config: Account<'info, MarketConfig>,
+
+    #[account(
+        mut,
+        close = seller,
+        seeds = [b"item_listing", item_listing.item_id.to_le_bytes().as_ref(), seller.key().as_ref()],
+        bump = item_listing.bump,
+        has_one = seller
+    )]
+    pub item_listing: Account<'info, ItemListing>,
+
+    /// CHECK: player_registry's ShopItem for the listed item
+    #[account(
+        seeds = [b"shop_item", item_listing.item_id.to_le_bytes().as_ref()],
+        bump,
+        seeds::program = PLAYER_REGISTRY_PROGRAM_ID
+    )]
+    pub shop_item: UncheckedAccount<'info>,
+    /// CHECK: The buyer's player_registry Inventory, created by player_registry if needed
+    #[account(mut)]
+    pub buyer_inventory: UncheckedAccount<'info>,
+
+    #[account(mint::token_program = payment_token_program)]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = buyer,
+        token::token_program = payment_token_program
+    )]
+    pub buyer_payment_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = seller,
+        token::token_program = payment_token_program
+    )]
+    pub seller_payment_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = payment_mint,
+        token::authority = market_config.treasury,
+        token::token_program = payment_token_program
+    )]
+    pub treasury_payment_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Listing seller, receives the listing rent
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+
+    /// CHECK: PDA that signs inventory moves into and out of listings
+    #[account(seeds = [b"market_authority"], bump)]
+    pub market_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    /// CHECK: The player_registry program
+    #[account(address = PLAYER_REGISTRY_PROGRAM_ID)]
+    pub registry_program: UncheckedAccount<'info>,
+    pub payment_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+// Data structures
+#[account]
+#[derive(InitSpace)]
+pub struct MarketConfig {
+    pub authority: Pubkey,
+    pub pending_authority: Option<Pubkey>,
+    pub pauser: Pubkey,
+    pub paused_flags: u8,
+    pub treasury: Pubkey,
+    pub royalty_bps: u16,
+    pub pirate_mint: Pubkey,
+    pub admiral_mint: Pubkey,
+    pub total_listings: u64,
+    pub total_sales: u64,
+    pub pirate_volume: u64,
+    pub admiral_volume: u64,
+    pub pirate_royalties: u64,
+    pub admiral_royalties: u64,
+    pub bump: u8,
+}
+
+impl MarketConfig {
+    pub fn is_paused(&self, category: u8) -> bool {
+        self.paused_flags & category != 0
+    }
+
+    // Treasury share of a sale price, rounded down in the seller's favour
+    pub fn royalty_on(&self, price: u64) -> u64 {
+        (price as u128 * self.royalty_bps as u128 / 10_000) as u64
+    }
+
+    pub fn mint_for(&self, currency: Currency) -> Pubkey {
+        match currency {
+            Currency::Pirate => self.pirate_mint,
+            Currency::Admiral => self.admiral_mint,
+        }
+    }
+
+    pub fn volume_for(&self, currency: Currency) -> u64 {
+        match currency {
+            Currency::Pirate => self.pirate_volume,
+            Currency::Admiral => self.admiral_volume,
+        }
+    }
+
+    pub fn royalties_for(&self, currency: Currency) -> u64 {
+        match currency {
+            Currency::Pirate => self.pirate_royalties,
+            Currency::Admiral => self.admiral_royalties,
+        }
+    }
+
+    pub fn record_sale(&mut self, currency: Currency, price: u64, royalty: u64) {
+        self.total_sales = self.total_sales.saturating_add(1);
+        match currency {
+            Currency::Pirate => {
+                self.pirate_volume = self.pirate_volume.saturating_add(price);
+                self.pirate_royalties = self.pirate_royalties.saturating_add(royalty);
+            }
+            Currency::Admiral => {
+                self.admiral_volume = self.admiral_volume.saturating_add(price);
+                self.admiral_royalties = self.admiral_royalties.saturating_add(royalty);
+            }
+        }
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Listing {
+    pub seller: Pubkey,
+    pub item_mint: Pubkey,
+    pub amount: u64,
+    pub price: u64,
+    pub currency: Currency,
+    pub created_at: i64,
+    pub bump: u8,
+    pub escrow_bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ItemListing {
+    pub seller: Pubkey,
+    pub item_id: u16,
+    pub quantity: u32,
+    pub price: u64,
+    pub currency: Currency,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+// Enums
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum Currency {
+    Pirate,
+    Admiral,
+}
+
+impl Currency {
+    pub fn to_string(&self) -> String {
+        match self {
+            Currency::Pirate => "PIRATE".to_string(),
+            Currency::Admiral => "ADMIRAL".to_string(),
+        }
+    }
+}
+
+// Events
+#[event]
+pub struct MarketConfigUpdated {
+    pub treasury: Pubkey,
+    pub royalty_bps: u16,
+}
+
+#[event]
+pub struct AuthorityTransferProposed {
+    pub current_authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct PauseStateChanged {
+    pub pauser: Pubkey,
+    pub paused_flags: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ItemListed {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub item_mint: Pubkey,
+    pub amount: u64,
+    pub price: u64,
+    pub currency: Currency,
+    pub total_listings: u64,
+}
+
+#[event]
+pub struct ListingPriceUpdated {
+    pub listing: Pubkey,
+    pub old_price: u64,
+    pub new_price: u64,
+}
+
+#[event]
+pub struct ListingCancelled {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub item_mint: Pubkey,
+}
+
+#[event]
+pub struct ItemSold {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub item_mint: Pubkey,
+    pub amount: u64,
+    pub price: u64,
+    pub royalty: u64,
+    pub currency: Currency,
+    pub total_sales: u64,
+    pub total_volume: u64,
+    pub total_royalties: u64,
+}
+
+#[event]
+pub struct InventoryItemListed {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub item_id: u16,
+    pub quantity: u32,
+    pub price: u64,
+    pub currency: Currency,
+    pub total_listings: u64,
+}
+
+#[event]
+pub struct InventoryItemListingCancelled {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub item_id: u16,
+}
+
+#[event]
+pub struct InventoryItemSold {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub item_id: u16,
+    pub quantity: u32,
+    pub price: u64,
+    pub royalty: u64,
+    pub currency: Currency,
+    pub total_sales: u64,
+    pub total_volume: u64,
+    pub total_royalties: u64,
+}
+
+// Error codes
+#[error_code]
+pub enum MarketplaceError {
+    #[msg("Royalty cannot exceed 10%")]
+    InvalidRoyalty,
+    #[msg("Amount and price must be greater than zero")]
+    InvalidAmount,
+    #[msg("Listing price is higher than the buyer's maximum")]
+    PriceChanged,
+    #[msg("Payment mint does not match the listing currency")]
+    InvalidPaymentMint,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Only player_registry skins without transfer fees can be listed")]
+    InvalidItemMint,
+    #[msg("Signer is not the pending authority")]
+    NotPendingAuthority,
+    #[msg("Marketplace is paused")]
+    Paused,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(royalty_bps: u16) -> MarketConfig {
+        MarketConfig {
+            authority: Pubkey::new_unique(),
+            pending_authority: None,
+            pauser: Pubkey::new_unique(),
+            paused_flags: 0,
+            treasury: Pubkey::new_unique(),
+            royalty_bps,
+            pirate_mint: Pubkey::new_unique(),
+            admiral_mint: Pubkey::new_unique(),
+            total_listings: 0,
+            total_sales: 0,
+            pirate_volume: 0,
+            admiral_volume: 0,
+            pirate_royalties: 0,
+            admiral_royalties: 0,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn pause_flags_are_independent() {
+        let mut market_config = config(250);
+        assert!(!market_config.is_paused(PAUSE_LISTINGS));
+        assert!(!market_config.is_paused(PAUSE_SALES));
+
+        market_config.paused_flags = PAUSE_SALES;
+        assert!(!market_config.is_paused(PAUSE_LISTINGS));
+        assert!(market_config.is_paused(PAUSE_SALES));
+
+        market_config.paused_flags = PAUSE_LISTINGS | PAUSE_SALES;
+        assert!(market_config.is_paused(PAUSE_LISTINGS));
+    }
+
+    #[test]
+    fn royalty_rounds_down() {
+        let market_config = config(250);
+        assert_eq!(market_config.royalty_on(10_000), 250);
+        assert_eq!(market_config.royalty_on(39), 0);
+        assert_eq!(market_config.royalty_on(41), 1);
+        assert_eq!(market_config.royalty_on(u64::MAX), (u64::MAX as u128 * 250 / 10_000) as u64);
+        assert_eq!(config(0).royalty_on(10_000), 0);
+    }
+
+    #[test]
+    fn sales_are_recorded_per_currency() {
+        let mut market_config = config(500);
+        market_config.record_sale(Currency::Pirate, 1_000, 50);
+        market_config.record_sale(Currency::Admiral, 200, 10);
+        market_config.record_sale(Currency::Pirate, 500, 25);
+
+        assert_eq!(market_config.total_sales, 3);
+        assert_eq!(market_config.volume_for(Currency::Pirate), 1_500);
+        assert_eq!(market_config.royalties_for(Currency::Pirate), 75);
+        assert_eq!(market_config.volume_for(Currency::Admiral), 200);
+        assert_eq!(market_config.royalties_for(Currency::Admiral), 10);
+    }
+
+    #[test]
+    fn payment_mint_follows_currency() {
+        let market_config = config(0);
+        assert_eq!(market_config.mint_for(Currency::Pirate), market_config.pirate_mint);
+        assert_eq!(market_config.mint_for(Currency::Admiral), market_config.admiral_mint);
+    }
+}
@@ -1,8 +1,30 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::keccak;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::{self, AssociatedToken};
+use anchor_spl::token_2022::{
+    self,
+    spl_token_2022::{extension::ExtensionType, state::Mint as MintState},
+};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
 
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 
+// Pause flags, batch limits, roles, mint limiters and burn callers shared with the
+// other token program
+#[path = "token-common.rs"]
+mod token_common;
+pub use token_common::*;
+
+use PirateTokenError as TokenError;
+
+// Largest airdrop a single distributor can track (one claimed bit per recipient)
+pub const MAX_AIRDROP_RECIPIENTS: u64 = 64_000;
+
+// admiral_token program, the only caller allowed to burn for $ADMIRAL conversions
+pub const ADMIRAL_TOKEN_PROGRAM_ID: Pubkey = pubkey!("AdmiralToken111111111111111111111111111111111");
+
 #[program]
 pub mod pirate_token {
     use super::*;
@@ -14,43 +36,146 @@ pub mod pirate_token {
         symbol: String,
         decimals: u8,
         total_supply: u64,
+        extensions: MintExtensionArgs,
     ) -> Result<()> {
-        let token_mint = &mut ctx.accounts.token_mint;
+        let token_mint = &ctx.accounts.token_mint;
         let authority = &ctx.accounts.authority;
         let system_program = &ctx.accounts.system_program;
         let token_program = &ctx.accounts.token_program;
-        let rent = &ctx.accounts.rent;
+        let mint_authority = &ctx.accounts.mint_authority;
+        let token_config = &mut ctx.accounts.token_config;
+
+        let token_mint_key = token_mint.key();
+        let mint_authority_seeds: &[&[u8]] = &[
+            b"mint_authority",
+            token_mint_key.as_ref(),
+            &[ctx.bumps.mint_authority],
+        ];
+
+        // Extensions are only available on Token-2022 mints
+        let is_token_2022 = token_program.key() == token_2022::ID;
+        require!(
+            is_token_2022 || extensions.is_empty(),
+            PirateTokenError::ExtensionsRequireToken2022
+        );
+
+        // Create the mint account with room for the requested extensions
+        let mint_space = ExtensionType::try_calculate_account_len::<MintState>(
+            &extensions.extension_types(),
+        )?;
+        let metadata_space = match &extensions.metadata {
+            Some(metadata) => token_metadata_space(&name, &symbol, &metadata.uri),
+            None => 0,
+        };
+        let lamports = Rent::get()?.minimum_balance(mint_space + metadata_space);
+
+        system_program::create_account(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: authority.to_account_info(),
+                    to: token_mint.to_account_info(),
+                },
+            ),
+            lamports,
+            mint_space as u64,
+            &token_program.key(),
+        )?;
+
+        // Extensions must be initialized before the mint itself
+        if extensions.metadata.is_some() {
+            token_interface::metadata_pointer_initialize(
+                CpiContext::new(
+                    token_program.to_account_info(),
+                    token_interface::MetadataPointerInitialize {
+                        token_program_id: token_program.to_account_info(),
+                        mint: token_mint.to_account_info(),
+                    },
+                ),
+                Some(mint_authority.key()),
+                Some(token_mint.key()),
+            )?;
+        }
 
-        // Initialize the token mint
-        token::initialize_mint(
+        // Initialize the token mint, controlled by the program so the admin can be rotated
+        token_interface::initialize_mint2(
             CpiContext::new(
                 token_program.to_account_info(),
-                token::InitializeMint {
+                token_interface::InitializeMint2 {
                     mint: token_mint.to_account_info(),
-                    rent: rent.to_account_info(),
                 },
             ),
             decimals,
-            authority,
-            Some(authority),
+            &mint_authority.key(),
+            Some(&mint_authority.key()),
         )?;
 
+        // Metadata lives on the mint itself, behind the metadata pointer
+        if let Some(metadata) = &extensions.metadata {
+            token_interface::token_metadata_initialize(
+                CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    token_interface::TokenMetadataInitialize {
+                        token_program_id: token_program.to_account_info(),
+                        mint: token_mint.to_account_info(),
+                        metadata: token_mint.to_account_info(),
+                        mint_authority: mint_authority.to_account_info(),
+                        update_authority: mint_authority.to_account_info(),
+                    },
+                    &[mint_authority_seeds],
+                ),
+                name.clone(),
+                symbol.clone(),
+                metadata.uri.clone(),
+            )?;
+        }
+
         // Create token account for the authority
-        let cpi_accounts = token::MintTo {
+        associated_token::create(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: authority.to_account_info(),
+                associated_token: ctx.accounts.authority_token_account.to_account_info(),
+                authority: authority.to_account_info(),
+                mint: token_mint.to_account_info(),
+                system_program: system_program.to_account_info(),
+                token_program: token_program.to_account_info(),
+            },
+        ))?;
+
+        let cpi_accounts = token_interface::MintTo {
             mint: token_mint.to_account_info(),
             to: ctx.accounts.authority_token_account.to_account_info(),
-            authority: authority.to_account_info(),
+            authority: mint_authority.to_account_info(),
         };
 
         let cpi_program = token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        token::mint_to(cpi_ctx, total_supply)?;
+        let signer_seeds = &[mint_authority_seeds];
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        token_interface::mint_to(cpi_ctx, total_supply)?;
+
+        // Initialize the token config
+        token_config.mint = token_mint.key();
+        token_config.authority = authority.key();
+        token_config.pending_authority = None;
+        token_config.total_burned = 0;
+        token_config.burned_shop = 0;
+        token_config.burned_recharge = 0;
+        token_config.burned_upgrade = 0;
+        token_config.burned_fee = 0;
+        token_config.burned_conversion = 0;
+        token_config.burned_via_cpi = 0;
+        token_config.paused_flags = 0;
+        token_config.mint_limit_per_window = 0;
+        token_config.mint_limit_window_seconds = 86400;
+        token_config.bump = ctx.bumps.token_config;
 
         msg!("$PIRATE token initialized successfully!");
         msg!("Name: {}", name);
         msg!("Symbol: {}", symbol);
         msg!("Decimals: {}", decimals);
         msg!("Total Supply: {}", total_supply);
+        msg!("Token program: {}", token_program.key());
 
         Ok(())
     }
@@ -60,15 +185,21 @@ pub mod pirate_token {
         ctx: Context<TransferTokens>,
         amount: u64,
     ) -> Result<()> {
-        let cpi_accounts = Transfer {
+        require!(
+            !ctx.accounts.token_config.is_paused(PAUSE_TRANSFERS),
+            PirateTokenError::Paused
+        );
+
+        let cpi_accounts = TransferChecked {
             from: ctx.accounts.from.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
             to: ctx.accounts.to.to_account_info(),
             authority: ctx.accounts.authority.to_account_info(),
         };
 
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        token::transfer(cpi_ctx, amount)?;
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
 
         msg!("Transferred {} $PIRATE tokens", amount);
         Ok(())
@@ -79,62 +210,1574 @@ pub mod pirate_token {
         ctx: Context<MintTokens>,
         amount: u64,
     ) -> Result<()> {
-        let cpi_accounts = token::MintTo {
+        require_role(
+            &ctx.accounts.token_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            Role::Minter,
+        )?;
+
+        require!(
+            !ctx.accounts.token_config.is_paused(PAUSE_MINTING),
+            PirateTokenError::Paused
+        );
+
+        // Enforce the per-recipient rolling window limit
+        let clock = Clock::get()?;
+        let token_config = &ctx.accounts.token_config;
+        let mint_limiter = &mut ctx.accounts.mint_limiter;
+        if mint_limiter.recipient == Pubkey::default() {
+            mint_limiter.mint = ctx.accounts.mint.key();
+            mint_limiter.recipient = ctx.accounts.to.owner;
+            mint_limiter.window_start = clock.unix_timestamp;
+            mint_limiter.bump = ctx.bumps.mint_limiter;
+        }
+        mint_limiter.record_mint(
+            amount,
+            clock.unix_timestamp,
+            token_config.mint_limit_per_window,
+            token_config.mint_limit_window_seconds,
+        )?;
+
+        let mint_key = ctx.accounts.mint.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"mint_authority",
+            mint_key.as_ref(),
+            &[ctx.bumps.mint_authority],
+        ]];
+
+        let cpi_accounts = token_interface::MintTo {
             mint: ctx.accounts.mint.to_account_info(),
             to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        token_interface::mint_to(cpi_ctx, amount)?;
+
+        msg!("Minted {} $PIRATE tokens", amount);
+        Ok(())
+    }
+
+    // Mint to many recipients in one transaction (tournament and leaderboard payouts).
+    // remaining_accounts holds a [recipient token account, mint limiter PDA] pair per
+    // amount; any failure reverts the whole batch
+    pub fn batch_mint<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchMint<'info>>,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        process_batch_mint(ctx, amounts)
+    }
+
+    // Transfer from one account to many recipients in one transaction.
+    // remaining_accounts holds one recipient token account per amount; any failure
+    // reverts the whole batch
+    pub fn batch_transfer<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchTransfer<'info>>,
+        amounts: Vec<u64>,
+    ) -> Result<()> {
+        process_batch_transfer(ctx, amounts)
+    }
+
+    // Burn $PIRATE tokens (sink for shop, recharge, upgrades and fees)
+    pub fn burn_tokens(
+        ctx: Context<BurnTokens>,
+        amount: u64,
+        reason: BurnReason,
+    ) -> Result<()> {
+        require!(amount > 0, PirateTokenError::InvalidAmount);
+
+        let cpi_accounts = token_interface::Burn {
+            mint: ctx.accounts.mint.to_account_info(),
+            from: ctx.accounts.from.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token_interface::burn(cpi_ctx, amount)?;
+
+        let caller_program =
+            resolve_burn_caller(&ctx.accounts.burn_caller, &ctx.accounts.caller_authority)?;
+
+        // Conversion burns are only counted when admiral_token mints the other side,
+        // and only for the pair its ConversionConfig is set up for
+        if reason == BurnReason::Conversion {
+            require!(
+                caller_program == Some(ADMIRAL_TOKEN_PROGRAM_ID),
+                PirateTokenError::InvalidBurnCaller
+            );
+            let conversion_config = ctx
+                .accounts
+                .conversion_config
+                .as_ref()
+                .ok_or(PirateTokenError::InvalidBurnCaller)?;
+            require_keys_eq!(
+                conversion_pirate_mint(conversion_config)?,
+                ctx.accounts.mint.key(),
+                PirateTokenError::InvalidBurnCaller
+            );
+        }
+
+        let token_config = &mut ctx.accounts.token_config;
+        token_config.record_burn(amount, reason, caller_program.is_some())?;
+        if let Some(burn_caller) = &mut ctx.accounts.burn_caller {
+            burn_caller.record_burn(amount)?;
+        }
+
+        emit!(TokensBurned {
+            mint: ctx.accounts.mint.key(),
+            owner: ctx.accounts.owner.key(),
+            amount,
+            reason,
+            caller_program,
+            total_burned: token_config.total_burned,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Burned {} $PIRATE tokens ({})", amount, reason.to_string());
+        Ok(())
+    }
+
+    // Pause or resume minting and transfers (emergency switch)
+    pub fn set_paused(
+        ctx: Context<SetPaused>,
+        paused_flags: u8,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.token_config,
+            &ctx.accounts.pauser,
+            &ctx.accounts.pauser_roles,
+            Role::Pauser,
+        )?;
+
+        let token_config = &mut ctx.accounts.token_config;
+        token_config.paused_flags = paused_flags;
+
+        emit!(PauseStateChanged {
+            mint: token_config.mint,
+            pauser: ctx.accounts.pauser.key(),
+            paused_flags,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("$PIRATE pause flags set to: {:#04b}", paused_flags);
+        Ok(())
+    }
+
+    // Grant a role for this token (Admin only)
+    pub fn grant_role(
+        ctx: Context<GrantRole>,
+        member: Pubkey,
+        role: Role,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.token_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            Role::Admin,
+        )?;
+        require!(role != Role::Admin, PirateTokenError::AdminRoleReserved);
+
+        let roles = &mut ctx.accounts.roles;
+        if roles.member == Pubkey::default() {
+            roles.config = ctx.accounts.token_config.key();
+            roles.member = member;
+            roles.bump = ctx.bumps.roles;
+        }
+        roles.grant(role);
+
+        emit!(RoleGranted {
+            config: roles.config,
+            member,
+            role,
+            granted_by: ctx.accounts.authority.key(),
+        });
+
+        msg!("$PIRATE role {} granted to: {}", role.to_string(), member);
+        Ok(())
+    }
+
+    // Revoke a role for this token (Admin only)
+    pub fn revoke_role(
+        ctx: Context<RevokeRole>,
+        role: Role,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.token_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            Role::Admin,
+        )?;
+
+        let roles = &mut ctx.accounts.roles;
+        roles.revoke(role);
+
+        emit!(RoleRevoked {
+            config: roles.config,
+            member: roles.member,
+            role,
+            revoked_by: ctx.accounts.authority.key(),
+        });
+
+        msg!("$PIRATE role {} revoked from: {}", role.to_string(), roles.member);
+        Ok(())
+    }
+
+    // Configure the per-recipient mint limit (0 disables it)
+    pub fn set_mint_limit(
+        ctx: Context<SetMintLimit>,
+        limit_per_window: u64,
+        window_seconds: i64,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.token_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            Role::Admin,
+        )?;
+
+        require!(window_seconds > 0, PirateTokenError::InvalidMintLimit);
+
+        let token_config = &mut ctx.accounts.token_config;
+        token_config.mint_limit_per_window = limit_per_window;
+        token_config.mint_limit_window_seconds = window_seconds;
+
+        emit!(MintLimitUpdated {
+            mint: token_config.mint,
+            limit_per_window,
+            window_seconds,
+        });
+
+        msg!("$PIRATE mint limit set to {} per {} seconds", limit_per_window, window_seconds);
+        Ok(())
+    }
+
+    // Grant a one-off allowance on top of a recipient's mint limit
+    pub fn grant_mint_override(
+        ctx: Context<GrantMintOverride>,
+        recipient: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.token_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            Role::Admin,
+        )?;
+
+        let mint_limiter = &mut ctx.accounts.mint_limiter;
+        if mint_limiter.recipient == Pubkey::default() {
+            mint_limiter.mint = ctx.accounts.token_config.mint;
+            mint_limiter.recipient = recipient;
+            mint_limiter.window_start = Clock::get()?.unix_timestamp;
+            mint_limiter.bump = ctx.bumps.mint_limiter;
+        }
+        mint_limiter.override_allowance = mint_limiter.override_allowance.saturating_add(amount);
+
+        emit!(MintOverrideGranted {
+            mint: mint_limiter.mint,
+            recipient,
+            amount,
+            override_allowance: mint_limiter.override_allowance,
+        });
+
+        msg!("$PIRATE mint override granted: {} for {}", amount, recipient);
+        Ok(())
+    }
+
+    // Propose a new admin (step one of the authority transfer)
+    pub fn propose_authority(
+        ctx: Context<ProposeAuthority>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        let token_config = &mut ctx.accounts.token_config;
+        token_config.pending_authority = Some(new_authority);
+
+        emit!(AuthorityTransferProposed {
+            mint: token_config.mint,
+            current_authority: token_config.authority,
+            pending_authority: new_authority,
+        });
+
+        msg!("$PIRATE authority transfer proposed to: {}", new_authority);
+        Ok(())
+    }
+
+    // Accept the admin role (step two of the authority transfer)
+    pub fn accept_authority(
+        ctx: Context<AcceptAuthority>,
+    ) -> Result<()> {
+        let token_config = &mut ctx.accounts.token_config;
+        let new_authority = ctx.accounts.new_authority.key();
+
+        require!(
+            token_config.pending_authority == Some(new_authority),
+            PirateTokenError::NotPendingAuthority
+        );
+
+        let previous_authority = token_config.authority;
+        token_config.authority = new_authority;
+        token_config.pending_authority = None;
+
+        emit!(AuthorityTransferred {
+            mint: token_config.mint,
+            previous_authority,
+            new_authority,
+        });
+
+        msg!("$PIRATE authority transferred to: {}", new_authority);
+        Ok(())
+    }
+
+    // Create an airdrop distributor for a $PIRATE campaign and fund its vault
+    pub fn create_distributor(
+        ctx: Context<CreateDistributor>,
+        campaign_id: u64,
+        merkle_root: [u8; 32],
+        max_total_claim: u64,
+        max_num_nodes: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.token_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            Role::Treasurer,
+        )?;
+
+        require!(
+            max_num_nodes > 0 && max_num_nodes <= MAX_AIRDROP_RECIPIENTS,
+            PirateTokenError::InvalidDistributor
+        );
+        require!(max_total_claim > 0, PirateTokenError::InvalidAmount);
+        require!(
+            expires_at > Clock::get()?.unix_timestamp,
+            PirateTokenError::InvalidDistributor
+        );
+
+        // Fund the vault with the full campaign budget
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.funder_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
             authority: ctx.accounts.authority.to_account_info(),
         };
 
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        token::mint_to(cpi_ctx, amount)?;
+        token_interface::transfer_checked(cpi_ctx, max_total_claim, ctx.accounts.mint.decimals)?;
 
-        msg!("Minted {} $PIRATE tokens", amount);
+        let distributor = &mut ctx.accounts.distributor;
+        distributor.mint = ctx.accounts.mint.key();
+        distributor.campaign_id = campaign_id;
+        distributor.merkle_root = merkle_root;
+        distributor.max_total_claim = max_total_claim;
+        distributor.max_num_nodes = max_num_nodes;
+        distributor.total_claimed = 0;
+        distributor.num_claimed = 0;
+        distributor.expires_at = expires_at;
+        distributor.clawback_receiver = ctx.accounts.funder_token_account.key();
+        distributor.clawed_back = false;
+        distributor.claimed_bitmap = vec![0; Distributor::bitmap_len(max_num_nodes)];
+        distributor.bump = ctx.bumps.distributor;
+
+        emit!(DistributorCreated {
+            distributor: distributor.key(),
+            campaign_id,
+            merkle_root,
+            max_total_claim,
+            max_num_nodes,
+            expires_at,
+        });
+
+        msg!("Airdrop campaign {} created: {} $PIRATE for up to {} players", campaign_id, max_total_claim, max_num_nodes);
         Ok(())
     }
+
+    // Claim a $PIRATE airdrop allocation with a merkle proof
+    pub fn claim_airdrop(
+        ctx: Context<ClaimAirdrop>,
+        index: u64,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        require!(
+            !ctx.accounts.token_config.is_paused(PAUSE_CLAIMS),
+            PirateTokenError::Paused
+        );
+
+        let clock = Clock::get()?;
+        let distributor = &ctx.accounts.distributor;
+        let claimant = ctx.accounts.claimant.key();
+
+        require!(!distributor.clawed_back, PirateTokenError::DistributorClosed);
+        require!(
+            clock.unix_timestamp < distributor.expires_at,
+            PirateTokenError::DistributorExpired
+        );
+        require!(index < distributor.max_num_nodes, PirateTokenError::InvalidProof);
+        require!(!distributor.is_claimed(index), PirateTokenError::AlreadyClaimed);
+
+        let leaf = airdrop_leaf(index, &claimant, amount);
+        require!(
+            verify_proof(&proof, distributor.merkle_root, leaf),
+            PirateTokenError::InvalidProof
+        );
+
+        let total_claimed = distributor
+            .total_claimed
+            .checked_add(amount)
+            .ok_or(PirateTokenError::MathOverflow)?;
+        require!(
+            total_claimed <= distributor.max_total_claim,
+            PirateTokenError::ExceedsMaxClaim
+        );
+
+        transfer_from_distributor_vault(
+            distributor,
+            &ctx.accounts.vault,
+            &ctx.accounts.mint,
+            ctx.accounts.claimant_token_account.to_account_info(),
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+
+        let distributor = &mut ctx.accounts.distributor;
+        distributor.set_claimed(index);
+        distributor.total_claimed = total_claimed;
+        distributor.num_claimed = distributor.num_claimed.saturating_add(1);
+
+        emit!(AirdropClaimed {
+            distributor: distributor.key(),
+            claimant,
+            index,
+            amount,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("Airdrop claimed: {} $PIRATE", amount);
+        Ok(())
+    }
+
+    // Return unclaimed airdrop funds once the campaign has expired
+    pub fn clawback_airdrop(
+        ctx: Context<ClawbackAirdrop>,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.token_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            Role::Treasurer,
+        )?;
+
+        let clock = Clock::get()?;
+        let distributor = &ctx.accounts.distributor;
+
+        require!(!distributor.clawed_back, PirateTokenError::DistributorClosed);
+        require!(
+            clock.unix_timestamp >= distributor.expires_at,
+            PirateTokenError::DistributorNotExpired
+        );
+
+        let unclaimed = ctx.accounts.vault.amount;
+        if unclaimed > 0 {
+            transfer_from_distributor_vault(
+                distributor,
+                &ctx.accounts.vault,
+                &ctx.accounts.mint,
+                ctx.accounts.clawback_receiver.to_account_info(),
+                &ctx.accounts.token_program,
+                unclaimed,
+            )?;
+        }
+
+        let distributor = &mut ctx.accounts.distributor;
+        distributor.clawed_back = true;
+
+        emit!(AirdropClawedBack {
+            distributor: distributor.key(),
+            amount: unclaimed,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("Airdrop clawback: {} unclaimed $PIRATE returned", unclaimed);
+        Ok(())
+    }
+
+    // View: current supply and burn totals. Simulate to read the result.
+    pub fn get_supply_info(
+        ctx: Context<GetSupplyInfo>,
+    ) -> Result<SupplyInfo> {
+        let token_config = &ctx.accounts.token_config;
+        let mint = &ctx.accounts.mint;
+
+        Ok(SupplyInfo {
+            mint: mint.key(),
+            decimals: mint.decimals,
+            supply: mint.supply,
+            total_burned: token_config.total_burned,
+            burned_shop: token_config.burned_shop,
+            burned_recharge: token_config.burned_recharge,
+            burned_upgrade: token_config.burned_upgrade,
+            burned_fee: token_config.burned_fee,
+            burned_conversion: token_config.burned_conversion,
+            burned_via_cpi: token_config.burned_via_cpi,
+            paused_flags: token_config.paused_flags,
+            mint_limit_per_window: token_config.mint_limit_per_window,
+            mint_limit_window_seconds: token_config.mint_limit_window_seconds,
+        })
+    }
+
+    // Register a program allowed to burn through CPI with its own counters (Admin only)
+    pub fn register_burn_caller(
+        ctx: Context<RegisterBurnCaller>,
+        program: Pubkey,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.token_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            Role::Admin,
+        )?;
+
+        let (authority, _) = Pubkey::find_program_address(&[b"burn_authority"], &program);
+
+        let burn_caller = &mut ctx.accounts.burn_caller;
+        burn_caller.mint = ctx.accounts.token_config.mint;
+        burn_caller.program = program;
+        burn_caller.authority = authority;
+        burn_caller.total_burned = 0;
+        burn_caller.burn_count = 0;
+        burn_caller.bump = ctx.bumps.burn_caller;
+
+        emit!(BurnCallerRegistered {
+            mint: burn_caller.mint,
+            program,
+            authority,
+        });
+
+        msg!("$PIRATE burn caller registered: {}", program);
+        Ok(())
+    }
+}
+
+// Helper functions
+// pirate_mint of admiral_token's ConversionConfig, laid out as
+// discriminator, admiral_mint, pirate_mint, ...
+fn conversion_pirate_mint(conversion_config: &AccountInfo) -> Result<Pubkey> {
+    require_keys_eq!(
+        *conversion_config.owner,
+        ADMIRAL_TOKEN_PROGRAM_ID,
+        PirateTokenError::InvalidBurnCaller
+    );
+    let data = conversion_config.try_borrow_data()?;
+    require!(
+        data.len() >= 72 && data[..8] == hash(b"account:ConversionConfig").to_bytes()[..8],
+        PirateTokenError::InvalidBurnCaller
+    );
+    Ok(Pubkey::new_from_array(data[40..72].try_into().unwrap()))
+}
+
+// Leaf hash for an airdrop allocation; prefixed so a leaf can never be passed off as a node
+pub fn airdrop_leaf(index: u64, claimant: &Pubkey, amount: u64) -> [u8; 32] {
+    keccak::hashv(&[
+        &[0u8],
+        &index.to_le_bytes(),
+        claimant.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+// Parent hash of two nodes, sorted so proofs don't need left/right flags
+pub fn airdrop_node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    keccak::hashv(&[&[1u8], left, right]).to_bytes()
+}
+
+fn verify_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof
+        .iter()
+        .fold(leaf, |node, sibling| airdrop_node(&node, sibling));
+    computed == root
+}
+
+// Transfers out of an airdrop vault, signed by the distributor PDA
+fn transfer_from_distributor_vault<'info>(
+    distributor: &Account<'info, Distributor>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<()> {
+    let campaign_id = distributor.campaign_id.to_le_bytes();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"distributor",
+        distributor.mint.as_ref(),
+        &campaign_id,
+        &[distributor.bump],
+    ]];
+
+    let cpi_accounts = TransferChecked {
+        from: vault.to_account_info(),
+        mint: mint.to_account_info(),
+        to,
+        authority: distributor.to_account_info(),
+    };
+
+    let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)
 }
 
 #[derive(Accounts)]
 pub struct InitializeToken<'info> {
+    /// CHECK: Created and initialized in the handler so extensions can be added first
+    #[account(mut)]
+    pub token_mint: Signer<'info>,
+
+    /// CHECK: Associated token account, created in the handler once the mint exists
+    #[account(mut)]
+    pub authority_token_account: UncheckedAccount<'info>,
+
     #[account(
         init,
         payer = authority,
-        mint::decimals = 9,
-        mint::authority = authority.key(),
+        space = 8 + TokenConfig::INIT_SPACE,
+        seeds = [b"token_config", token_mint.key().as_ref()],
+        bump
     )]
-    pub token_mint: Account<'info, token::Mint>,
+    pub token_config: Account<'info, TokenConfig>,
+
+    /// CHECK: PDA that holds mint, freeze and extension authority over the mint
+    #[account(seeds = [b"mint_authority", token_mint.key().as_ref()], bump)]
+    pub mint_authority: UncheckedAccount<'info>,
     
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct TransferTokens<'info> {
     #[account(
-        init,
+        seeds = [b"token_config", mint.key().as_ref()],
+        bump = token_config.bump,
+        has_one = mint
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub from: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub to: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct MintTokens<'info> {
+    #[account(
+        seeds = [b"token_config", mint.key().as_ref()],
+        bump = token_config.bump,
+        has_one = mint
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    #[account(mut, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub to: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
         payer = authority,
-        token::mint = token_mint,
-        token::authority = authority,
+        space = 8 + MintLimiter::INIT_SPACE,
+        seeds = [b"mint_limiter", mint.key().as_ref(), to.owner.as_ref()],
+        bump
     )]
-    pub authority_token_account: Account<'info, TokenAccount>,
-    
+    pub mint_limiter: Account<'info, MintLimiter>,
+    /// CHECK: PDA mint authority, signs the mint CPI
+    #[account(seeds = [b"mint_authority", mint.key().as_ref()], bump)]
+    pub mint_authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"roles", token_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
     #[account(mut)]
     pub authority: Signer<'info>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    pub rent: Sysvar<'info, Rent>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
-pub struct TransferTokens<'info> {
+pub struct BatchMint<'info> {
+    #[account(
+        seeds = [b"token_config", mint.key().as_ref()],
+        bump = token_config.bump,
+        has_one = mint
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    #[account(mut, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: PDA mint authority, signs the mint CPIs
+    #[account(seeds = [b"mint_authority", mint.key().as_ref()], bump)]
+    pub mint_authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"roles", token_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct BatchTransfer<'info> {
+    #[account(
+        seeds = [b"token_config", mint.key().as_ref()],
+        bump = token_config.bump,
+        has_one = mint
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub from: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct BurnTokens<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_config", mint.key().as_ref()],
+        bump = token_config.bump,
+        has_one = mint
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    #[account(mut, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = owner,
+        token::token_program = token_program
+    )]
+    pub from: InterfaceAccount<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    // CPI burns only: the calling program's counters and its signing burn authority PDA
+    #[account(
+        mut,
+        seeds = [b"burn_caller", mint.key().as_ref(), burn_caller.program.as_ref()],
+        bump = burn_caller.bump
+    )]
+    pub burn_caller: Option<Account<'info, BurnCaller>>,
+    pub caller_authority: Option<Signer<'info>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    /// CHECK: Conversion burns only: admiral_token's ConversionConfig, read in conversion_pirate_mint
+    #[account(seeds = [b"conversion_config"], bump, seeds::program = ADMIRAL_TOKEN_PROGRAM_ID)]
+    pub conversion_config: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_config", token_config.mint.as_ref()],
+        bump = token_config.bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    #[account(
+        seeds = [b"roles", token_config.key().as_ref(), pauser.key().as_ref()],
+        bump = pauser_roles.bump
+    )]
+    pub pauser_roles: Option<Account<'info, Roles>>,
+    pub pauser: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(member: Pubkey)]
+pub struct GrantRole<'info> {
+    #[account(
+        seeds = [b"token_config", token_config.mint.as_ref()],
+        bump = token_config.bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + Roles::INIT_SPACE,
+        seeds = [b"roles", token_config.key().as_ref(), member.as_ref()],
+        bump
+    )]
+    pub roles: Account<'info, Roles>,
+    #[account(
+        seeds = [b"roles", token_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
     #[account(mut)]
-    pub from: Account<'info, TokenAccount>,
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeRole<'info> {
+    #[account(
+        seeds = [b"token_config", token_config.mint.as_ref()],
+        bump = token_config.bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    #[account(
+        mut,
+        seeds = [b"roles", token_config.key().as_ref(), roles.member.as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Account<'info, Roles>,
+    #[account(
+        seeds = [b"roles", token_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetMintLimit<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_config", token_config.mint.as_ref()],
+        bump = token_config.bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    #[account(
+        seeds = [b"roles", token_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(recipient: Pubkey)]
+pub struct GrantMintOverride<'info> {
+    #[account(
+        seeds = [b"token_config", token_config.mint.as_ref()],
+        bump = token_config.bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + MintLimiter::INIT_SPACE,
+        seeds = [b"mint_limiter", token_config.mint.as_ref(), recipient.as_ref()],
+        bump
+    )]
+    pub mint_limiter: Account<'info, MintLimiter>,
+    #[account(
+        seeds = [b"roles", token_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
     #[account(mut)]
-    pub to: Account<'info, TokenAccount>,
     pub authority: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_config", token_config.mint.as_ref()],
+        bump = token_config.bump,
+        has_one = authority @ PirateTokenError::Unauthorized
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct MintTokens<'info> {
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_config", token_config.mint.as_ref()],
+        bump = token_config.bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: u64, merkle_root: [u8; 32], max_total_claim: u64, max_num_nodes: u64)]
+pub struct CreateDistributor<'info> {
+    #[account(
+        seeds = [b"token_config", mint.key().as_ref()],
+        bump = token_config.bump,
+        has_one = mint
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Distributor::space(max_num_nodes),
+        seeds = [b"distributor", mint.key().as_ref(), &campaign_id.to_le_bytes()],
+        bump
+    )]
+    pub distributor: Account<'info, Distributor>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"distributor_vault", distributor.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = distributor,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = authority,
+        token::token_program = token_program
+    )]
+    pub funder_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"roles", token_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+
     #[account(mut)]
-    pub mint: Account<'info, token::Mint>,
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAirdrop<'info> {
+    #[account(
+        seeds = [b"token_config", mint.key().as_ref()],
+        bump = token_config.bump,
+        has_one = mint
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"distributor", mint.key().as_ref(), &distributor.campaign_id.to_le_bytes()],
+        bump = distributor.bump,
+        has_one = mint
+    )]
+    pub distributor: Account<'info, Distributor>,
+
+    #[account(
+        mut,
+        seeds = [b"distributor_vault", distributor.key().as_ref()],
+        bump
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = claimant,
+        token::token_program = token_program
+    )]
+    pub claimant_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub claimant: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClawbackAirdrop<'info> {
+    #[account(
+        seeds = [b"token_config", mint.key().as_ref()],
+        bump = token_config.bump,
+        has_one = mint
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"distributor", mint.key().as_ref(), &distributor.campaign_id.to_le_bytes()],
+        bump = distributor.bump,
+        has_one = mint,
+        has_one = clawback_receiver
+    )]
+    pub distributor: Account<'info, Distributor>,
+
+    #[account(
+        mut,
+        seeds = [b"distributor_vault", distributor.key().as_ref()],
+        bump
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
     #[account(mut)]
-    pub to: Account<'info, TokenAccount>,
+    pub clawback_receiver: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"roles", token_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
     pub authority: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct GetSupplyInfo<'info> {
+    #[account(
+        seeds = [b"token_config", mint.key().as_ref()],
+        bump = token_config.bump,
+        has_one = mint
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
+#[derive(Accounts)]
+#[instruction(program: Pubkey)]
+pub struct RegisterBurnCaller<'info> {
+    #[account(
+        seeds = [b"token_config", token_config.mint.as_ref()],
+        bump = token_config.bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + BurnCaller::INIT_SPACE,
+        seeds = [b"burn_caller", token_config.mint.as_ref(), program.as_ref()],
+        bump
+    )]
+    pub burn_caller: Account<'info, BurnCaller>,
+    #[account(
+        seeds = [b"roles", token_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Data structures
+#[account]
+#[derive(InitSpace)]
+pub struct TokenConfig {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Option<Pubkey>,
+    pub total_burned: u64,
+    pub burned_shop: u64,
+    pub burned_recharge: u64,
+    pub burned_upgrade: u64,
+    pub burned_fee: u64,
+    pub burned_conversion: u64,
+    pub burned_via_cpi: u64,
+    pub paused_flags: u8,
+    pub mint_limit_per_window: u64,
+    pub mint_limit_window_seconds: i64,
+    pub bump: u8,
+}
+
+impl TokenConfig {
+    pub fn is_paused(&self, category: u8) -> bool {
+        self.paused_flags & category != 0
+    }
+
+    pub fn record_burn(&mut self, amount: u64, reason: BurnReason, via_cpi: bool) -> Result<()> {
+        let counter = match reason {
+            BurnReason::Shop => &mut self.burned_shop,
+            BurnReason::Recharge => &mut self.burned_recharge,
+            BurnReason::Upgrade => &mut self.burned_upgrade,
+            BurnReason::Fee => &mut self.burned_fee,
+            BurnReason::Conversion => &mut self.burned_conversion,
+        };
+        *counter = counter.checked_add(amount).ok_or(PirateTokenError::MathOverflow)?;
+
+        self.total_burned = self.total_burned.checked_add(amount).ok_or(PirateTokenError::MathOverflow)?;
+        if via_cpi {
+            self.burned_via_cpi = self.burned_via_cpi.checked_add(amount).ok_or(PirateTokenError::MathOverflow)?;
+        }
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Distributor {
+    pub mint: Pubkey,
+    pub campaign_id: u64,
+    pub merkle_root: [u8; 32],
+    pub max_total_claim: u64,
+    pub max_num_nodes: u64,
+    pub total_claimed: u64,
+    pub num_claimed: u64,
+    pub expires_at: i64,
+    pub clawback_receiver: Pubkey,
+    pub clawed_back: bool,
+    pub claimed_bitmap: Vec<u8>,
+    pub bump: u8,
+}
+
+impl Distributor {
+    pub fn bitmap_len(max_num_nodes: u64) -> usize {
+        ((max_num_nodes + 7) / 8) as usize
+    }
+
+    pub fn space(max_num_nodes: u64) -> usize {
+        8 + 32 + 8 + 32 + 8 + 8 + 8 + 8 + 8 + 32 + 1
+            + (4 + Self::bitmap_len(max_num_nodes))
+            + 1
+    }
+
+    pub fn is_claimed(&self, index: u64) -> bool {
+        self.claimed_bitmap[(index / 8) as usize] & (1 << (index % 8)) != 0
+    }
+
+    pub fn set_claimed(&mut self, index: u64) {
+        self.claimed_bitmap[(index / 8) as usize] |= 1 << (index % 8);
+    }
+}
+
+// Return data of get_supply_info
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SupplyInfo {
+    pub mint: Pubkey,
+    pub decimals: u8,
+    pub supply: u64,
+    pub total_burned: u64,
+    pub burned_shop: u64,
+    pub burned_recharge: u64,
+    pub burned_upgrade: u64,
+    pub burned_fee: u64,
+    pub burned_conversion: u64,
+    pub burned_via_cpi: u64,
+    pub paused_flags: u8,
+    pub mint_limit_per_window: u64,
+    pub mint_limit_window_seconds: i64,
+}
+
+// Instruction arguments
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct MintExtensionArgs {
+    pub metadata: Option<TokenMetadataArgs>,
+}
+
+impl MintExtensionArgs {
+    pub fn is_empty(&self) -> bool {
+        self.metadata.is_none()
+    }
+
+    pub fn extension_types(&self) -> Vec<ExtensionType> {
+        let mut extension_types = Vec::new();
+        if self.metadata.is_some() {
+            extension_types.push(ExtensionType::MetadataPointer);
+        }
+        extension_types
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TokenMetadataArgs {
+    pub uri: String,
+}
+
+// Enums
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum BurnReason {
+    Shop,
+    Recharge,
+    Upgrade,
+    Fee,
+    Conversion,
+}
+
+impl BurnReason {
+    pub fn to_string(&self) -> String {
+        match self {
+            BurnReason::Shop => "shop".to_string(),
+            BurnReason::Recharge => "recharge".to_string(),
+            BurnReason::Upgrade => "upgrade".to_string(),
+            BurnReason::Fee => "fee".to_string(),
+            BurnReason::Conversion => "conversion".to_string(),
+        }
+    }
+}
+
+// Events
+#[event]
+pub struct MintLimitUpdated {
+    pub mint: Pubkey,
+    pub limit_per_window: u64,
+    pub window_seconds: i64,
+}
+
+#[event]
+pub struct MintOverrideGranted {
+    pub mint: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub override_allowance: u64,
+}
+
+#[event]
+pub struct AuthorityTransferProposed {
+    pub mint: Pubkey,
+    pub current_authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub mint: Pubkey,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct PauseStateChanged {
+    pub mint: Pubkey,
+    pub pauser: Pubkey,
+    pub paused_flags: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BatchMinted {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub recipients: u16,
+    pub total_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BatchTransferred {
+    pub mint: Pubkey,
+    pub from: Pubkey,
+    pub recipients: u16,
+    pub total_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokensBurned {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub reason: BurnReason,
+    pub caller_program: Option<Pubkey>,
+    pub total_burned: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DistributorCreated {
+    pub distributor: Pubkey,
+    pub campaign_id: u64,
+    pub merkle_root: [u8; 32],
+    pub max_total_claim: u64,
+    pub max_num_nodes: u64,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct AirdropClaimed {
+    pub distributor: Pubkey,
+    pub claimant: Pubkey,
+    pub index: u64,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AirdropClawedBack {
+    pub distributor: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoleGranted {
+    pub config: Pubkey,
+    pub member: Pubkey,
+    pub role: Role,
+    pub granted_by: Pubkey,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub config: Pubkey,
+    pub member: Pubkey,
+    pub role: Role,
+    pub revoked_by: Pubkey,
+}
+
+#[event]
+pub struct BurnCallerRegistered {
+    pub mint: Pubkey,
+    pub program: Pubkey,
+    pub authority: Pubkey,
+}
+
+// Error codes
+#[error_code]
+pub enum PirateTokenError {
+    #[msg("Amount must be greater than zero")]
+    InvalidAmount,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("Mint extensions require the Token-2022 program")]
+    ExtensionsRequireToken2022,
+    #[msg("This operation is paused")]
+    Paused,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Signer is not the pending authority")]
+    NotPendingAuthority,
+    #[msg("Invalid distributor parameters")]
+    InvalidDistributor,
+    #[msg("Invalid merkle proof")]
+    InvalidProof,
+    #[msg("Airdrop already claimed")]
+    AlreadyClaimed,
+    #[msg("Claim exceeds the campaign budget")]
+    ExceedsMaxClaim,
+    #[msg("Airdrop campaign has expired")]
+    DistributorExpired,
+    #[msg("Airdrop campaign has not expired yet")]
+    DistributorNotExpired,
+    #[msg("Airdrop campaign is closed")]
+    DistributorClosed,
+    #[msg("Burn reason not allowed for this caller")]
+    InvalidBurnCaller,
+    #[msg("Invalid mint limit")]
+    InvalidMintLimit,
+    #[msg("Mint limit for this recipient exceeded")]
+    MintLimitExceeded,
+    #[msg("Signer is missing the required role")]
+    MissingRole,
+    #[msg("Batch amounts and recipient accounts don't match or exceed the batch limit")]
+    InvalidBatch,
+    #[msg("Batch recipient is not a token account for this mint")]
+    InvalidBatchRecipient,
+    #[msg("Mint limiter account does not match the recipient")]
+    InvalidMintLimiter,
+    #[msg("Insufficient balance for the batch")]
+    InsufficientBalance,
+    #[msg("The Admin role belongs to the config authority and can't be granted")]
+    AdminRoleReserved,
+}
+
+// Off-chain airdrop tree builder, used by the deploy scripts to publish a root and proofs
+#[cfg(not(target_os = "solana"))]
+pub mod airdrop_tree {
+    use super::{airdrop_leaf, airdrop_node};
+    use anchor_lang::prelude::Pubkey;
+    use std::str::FromStr;
+
+    pub struct AirdropEntry {
+        pub index: u64,
+        pub claimant: Pubkey,
+        pub amount: u64,
+        pub proof: Vec<[u8; 32]>,
+    }
+
+    pub struct AirdropTree {
+        pub root: [u8; 32],
+        pub max_total_claim: u64,
+        pub entries: Vec<AirdropEntry>,
+    }
+
+    /// Builds the tree from `wallet,amount` CSV lines. A header row and blank lines are skipped;
+    /// a wallet listed twice is rejected rather than given two allocations.
+    pub fn build_from_csv(csv: &str) -> Result<AirdropTree, String> {
+        let mut entries: Vec<AirdropEntry> = Vec::new();
+
+        for (line_number, line) in csv.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || (line_number == 0 && line.to_lowercase().starts_with("wallet")) {
+                continue;
+            }
+
+            let mut columns = line.split(',').map(str::trim);
+            let (wallet, amount) = match (columns.next(), columns.next()) {
+                (Some(wallet), Some(amount)) => (wallet, amount),
+                _ => return Err(format!("line {}: expected wallet,amount", line_number + 1)),
+            };
+
+            let claimant = Pubkey::from_str(wallet)
+                .map_err(|_| format!("line {}: invalid wallet {}", line_number + 1, wallet))?;
+            let amount = amount
+                .parse::<u64>()
+                .map_err(|_| format!("line {}: invalid amount {}", line_number + 1, amount))?;
+            if entries.iter().any(|entry| entry.claimant == claimant) {
+                return Err(format!("line {}: duplicate wallet {}", line_number + 1, wallet));
+            }
+
+            entries.push(AirdropEntry {
+                index: entries.len() as u64,
+                claimant,
+                amount,
+                proof: Vec::new(),
+            });
+        }
+
+        if entries.is_empty() {
+            return Err("no airdrop entries".to_string());
+        }
+
+        let max_total_claim = entries
+            .iter()
+            .try_fold(0u64, |total, entry| total.checked_add(entry.amount))
+            .ok_or_else(|| "total airdrop amount overflows u64".to_string())?;
+
+        // Build every layer of the tree; an odd node is carried up unchanged
+        let mut layers = vec![entries
+            .iter()
+            .map(|entry| airdrop_leaf(entry.index, &entry.claimant, entry.amount))
+            .collect::<Vec<_>>()];
+
+        while layers.last().unwrap().len() > 1 {
+            let next = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => airdrop_node(left, right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+
+        for entry in entries.iter_mut() {
+            let mut position = entry.index as usize;
+            for layer in &layers[..layers.len() - 1] {
+                let sibling = position ^ 1;
+                if sibling < layer.len() {
+                    entry.proof.push(layer[sibling]);
+                }
+                position /= 2;
+            }
+        }
+
+        Ok(AirdropTree {
+            root: layers.last().unwrap()[0],
+            max_total_claim,
+            entries,
+        })
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::super::verify_proof;
+        use super::*;
+
+        fn csv_for(amounts: &[u64]) -> (Vec<Pubkey>, String) {
+            let wallets: Vec<Pubkey> = amounts.iter().map(|_| Pubkey::new_unique()).collect();
+            let mut csv = "wallet,amount\n".to_string();
+            for (wallet, amount) in wallets.iter().zip(amounts) {
+                csv.push_str(&format!("{},{}\n", wallet, amount));
+            }
+            (wallets, csv)
+        }
+
+        #[test]
+        fn proofs_verify_against_root() {
+            // Odd sizes exercise the carried-up node
+            for count in 1..=7u64 {
+                let amounts: Vec<u64> = (1..=count).map(|n| n * 100).collect();
+                let (wallets, csv) = csv_for(&amounts);
+                let tree = build_from_csv(&csv).unwrap();
+
+                assert_eq!(tree.entries.len(), count as usize);
+                assert_eq!(tree.max_total_claim, amounts.iter().sum::<u64>());
+                for (entry, wallet) in tree.entries.iter().zip(&wallets) {
+                    assert_eq!(entry.claimant, *wallet);
+                    let leaf = airdrop_leaf(entry.index, &entry.claimant, entry.amount);
+                    assert!(verify_proof(&entry.proof, tree.root, leaf));
+                }
+            }
+        }
+
+        #[test]
+        fn proof_rejects_other_allocation() {
+            let (_, csv) = csv_for(&[100, 200, 300]);
+            let tree = build_from_csv(&csv).unwrap();
+            let entry = &tree.entries[1];
+
+            let inflated = airdrop_leaf(entry.index, &entry.claimant, entry.amount + 1);
+            assert!(!verify_proof(&entry.proof, tree.root, inflated));
+            let moved = airdrop_leaf(entry.index + 1, &entry.claimant, entry.amount);
+            assert!(!verify_proof(&entry.proof, tree.root, moved));
+        }
+
+        #[test]
+        fn header_and_blank_lines_are_skipped() {
+            let wallet = Pubkey::new_unique();
+            let tree = build_from_csv(&format!("Wallet,Amount\n\n {} , 42 \n\n", wallet)).unwrap();
+
+            assert_eq!(tree.entries.len(), 1);
+            assert_eq!(tree.entries[0].claimant, wallet);
+            assert_eq!(tree.entries[0].amount, 42);
+            assert_eq!(tree.root, airdrop_leaf(0, &wallet, 42));
+        }
+
+        #[test]
+        fn duplicate_wallet_is_rejected() {
+            let wallet = Pubkey::new_unique();
+            let csv = format!("{},10\n{},20\n", wallet, wallet);
+
+            let error = build_from_csv(&csv).err().unwrap();
+            assert!(error.starts_with("line 2: duplicate wallet"), "{}", error);
+        }
+
+        #[test]
+        fn malformed_rows_are_rejected() {
+            let wallet = Pubkey::new_unique();
+            let cases = [
+                (format!("{}\n", wallet), "line 1: expected wallet,amount"),
+                ("not-a-wallet,10\n".to_string(), "line 1: invalid wallet"),
+                (format!("{},ten\n", wallet), "line 1: invalid amount"),
+                (format!("{},-5\n", wallet), "line 1: invalid amount"),
+            ];
+
+            for (csv, expected) in cases {
+                let error = build_from_csv(&csv).err().unwrap();
+                assert!(error.starts_with(expected), "{}", error);
+            }
+        }
+
+        #[test]
+        fn overflowing_total_is_rejected() {
+            let csv = format!("{},{}\n{},1\n", Pubkey::new_unique(), u64::MAX, Pubkey::new_unique());
+            assert!(build_from_csv(&csv).is_err());
+        }
+
+        #[test]
+        fn empty_input_is_rejected() {
+            for csv in ["", "wallet,amount\n", "\n\n"] {
+                assert_eq!(build_from_csv(csv).err().unwrap(), "no airdrop entries");
+            }
+        }
+    }
 }
@@ -1,13 +1,134 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer, Mint};
+use anchor_lang::solana_program::hash::{hash, hashv};
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::sysvar::slot_hashes;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id, AssociatedToken};
+use anchor_spl::token_2022::{
+    spl_token_2022::{extension::ExtensionType, instruction::AuthorityType, state::Mint as MintState},
+    Token2022,
+};
+use anchor_spl::token_interface::{
+    self, spl_token_metadata_interface::state::Field, CloseAccount, Mint, TokenAccount,
+    TokenInterface, TransferChecked,
+};
 use std::collections::HashMap;
 
 declare_id!("PLYRrgstry111111111111111111111111111111111");
 
+// Pause categories for the emergency switch
+pub const PAUSE_CLAIMS: u8 = 1 << 0;
+pub const PAUSE_TRANSFERS: u8 = 1 << 1;
+pub const PAUSE_SHOP: u8 = 1 << 2;
+pub const PAUSE_WAGERS: u8 = 1 << 3;
+
+// admiral_token program, owner of the staking accounts read for reward boosts
+pub const ADMIRAL_TOKEN_PROGRAM_ID: Pubkey = pubkey!("AdmiralToken111111111111111111111111111111111");
+// pirate_token program, burns $PIRATE spent in the shop
+pub const PIRATE_TOKEN_PROGRAM_ID: Pubkey = pubkey!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+// marketplace program, moves inventory items in and out of listings
+pub const MARKETPLACE_PROGRAM_ID: Pubkey = pubkey!("KaboomMarket11111111111111111111111111111111");
+// Borsh index of BurnReason::Shop in both token programs
+pub const BURN_REASON_SHOP: u8 = 0;
+// One whole $ADMIRAL in base units (9 decimals)
+pub const ADMIRAL_UNIT: u64 = 1_000_000_000;
+
+// Cooldowns between reward claims
+pub const DAILY_CLAIM_INTERVAL: i64 = 86_400;
+pub const WEEKLY_CLAIM_INTERVAL: i64 = 604_800;
+
+// Default chapter layout: 5 chapters of 8 levels, each ending with a boss level.
+// Defeated chapter bosses are tracked in a u8 bitmask on the profile.
+pub const DEFAULT_CHAPTER_COUNT: u8 = 5;
+pub const DEFAULT_LEVELS_PER_CHAPTER: u8 = 8;
+pub const MAX_CHAPTERS: u8 = 8;
+
+// $PIRATE paid for the first defeat of each chapter boss, from reward-system.js.
+// Chapters past the default layout get theirs when set_chapter_layout adds them.
+pub const DEFAULT_BOSS_REWARDS: [u64; MAX_CHAPTERS as usize] =
+    [500, 1_000, 2_000, 3_000, 5_000, 0, 0, 0];
+
+// Progression achievements seeded as the default achievement rules, with the
+// rewards from reward-system.js
+pub const CHAPTER_MASTER_ACHIEVEMENT: &str = "chapter_master";
+pub const CHAPTER_MASTER_REWARD: u64 = 500;
+pub const GAME_MASTER_ACHIEVEMENT: &str = "game_master";
+pub const GAME_MASTER_REWARD: u64 = 2_000;
+pub const BOSS_SLAYER_ACHIEVEMENT: &str = "boss_slayer";
+pub const BOSS_SLAYER_BOSSES: u64 = 5;
+pub const BOSS_SLAYER_REWARD: u64 = 1_000;
+pub const TOKEN_COLLECTOR_ACHIEVEMENT: &str = "token_collector";
+pub const TOKEN_COLLECTOR_BALANCE: u64 = 10_000;
+pub const TOKEN_COLLECTOR_REWARD: u64 = 5_000;
+
+// Rule-based achievements evaluated by the registry itself
+pub const MAX_ACHIEVEMENT_RULES: usize = 16;
+
+// Achievements a profile can hold. Issued badges are tracked in a u64 bitmask by
+// the achievement's position, so a burned badge can't be minted again.
+pub const MAX_ACHIEVEMENTS: usize = 64;
+
+// Metadata field holding a skin's character trait
+pub const SKIN_CHARACTER_FIELD: &str = "character";
+
+// VIP tiers above the base tier, and the largest reward multiplier a tier can have
+pub const MAX_VIP_TIERS: usize = 5;
+pub const MAX_VIP_MULTIPLIER_BPS: u16 = 30_000;
+
+// Tiers a battle pass can have; claimed tiers are tracked in a u64 bitmask
+pub const MAX_PASS_TIERS: usize = 50;
+
+// Weighted entries a loot table can have
+pub const MAX_LOOT_ENTRIES: usize = 16;
+// A chest roll uses the hash of a slot this far past the commit. SlotHashes only
+// keeps the last 512 slots (~3.5 minutes), so the reveal has to land within that window.
+pub const CHEST_REVEAL_DELAY_SLOTS: u64 = 4;
+pub const SLOT_HASHES_WINDOW: u64 = 512;
+
+// Distinct items a player inventory can hold
+pub const MAX_INVENTORY_ITEMS: usize = 32;
+
+// House fee on decided wager matches is capped at 10%
+pub const MAX_WAGER_FEE_BPS: u16 = 1_000;
+// An accepted match that isn't settled within this window can be refunded
+pub const MATCH_SETTLE_TIMEOUT: i64 = 86_400;
+
+// Ranked places a tournament payout table can have
+pub const MAX_PAYOUT_PLACES: usize = 10;
+
 #[program]
 pub mod player_registry {
     use super::*;
 
+    /// Initialize the registry config
+    pub fn initialize_registry(
+        ctx: Context<InitializeRegistry>,
+    ) -> Result<()> {
+        let registry_config = &mut ctx.accounts.registry_config;
+
+        registry_config.authority = ctx.accounts.authority.key();
+        registry_config.pending_authority = None;
+        registry_config.treasury_authority = ctx.accounts.authority.key();
+        registry_config.pending_treasury_authority = None;
+        registry_config.paused_flags = 0;
+        registry_config.stake_pool = Pubkey::default();
+        registry_config.wager_fee_bps = 0;
+        registry_config.chapter_count = DEFAULT_CHAPTER_COUNT;
+        registry_config.levels_per_chapter = DEFAULT_LEVELS_PER_CHAPTER;
+        registry_config.boss_rewards = DEFAULT_BOSS_REWARDS;
+        registry_config.pirate_mint = Pubkey::default();
+        registry_config.admiral_mint = Pubkey::default();
+        registry_config.bump = ctx.bumps.registry_config;
+
+        let achievement_rules = &mut ctx.accounts.achievement_rules;
+        achievement_rules.rules = AchievementRules::defaults();
+        achievement_rules.bump = ctx.bumps.achievement_rules;
+
+        msg!("Player registry initialized by: {}", registry_config.authority);
+        Ok(())
+    }
+
     /// Initialize a new player profile
     pub fn initialize_player(
         ctx: Context<InitializePlayer>,
@@ -41,6 +162,11 @@ pub mod player_registry {
         player_profile.is_active = true;
         player_profile.streak_days = 0;
         player_profile.last_login = clock.unix_timestamp;
+        player_profile.equipped_skin = None;
+        player_profile.equipped_character = SkinCharacter::BombGuy;
+        player_profile.vip_tier = 0;
+        player_profile.chapter_bosses_defeated = 0;
+        player_profile.badges_issued = 0;
 
         msg!("Player profile initialized for: {}", ctx.accounts.player.key());
         msg!("Username: {}", player_profile.username);
@@ -54,14 +180,33 @@ pub mod player_registry {
         new_score: u64,
         level_completed: bool,
     ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.game_server,
+            &ctx.accounts.game_server_roles,
+            &[Role::GameServer],
+        )?;
+
+        let registry_config = &ctx.accounts.registry_config;
         let player_profile = &mut ctx.accounts.player_profile;
         let clock = Clock::get()?;
         
-        // Validate level progression
+        // Validate level progression: levels unlock one at a time, and the next
+        // chapter only once the current chapter's boss has been defeated
         require!(
-            new_level >= player_profile.level && new_level <= 40,
+            new_level >= player_profile.level
+                && new_level <= player_profile.level.saturating_add(1)
+                && new_level <= registry_config.max_level(),
             PlayerRegistryError::InvalidLevelProgression
         );
+        require!(
+            registry_config.can_advance(
+                player_profile.level,
+                new_level,
+                player_profile.chapter_bosses_defeated
+            ),
+            PlayerRegistryError::ChapterBossNotDefeated
+        );
         
         require!(
             new_score >= player_profile.score,
@@ -80,13 +225,40 @@ pub mod player_registry {
         if level_completed && new_level > old_level {
             player_profile.total_levels_completed = player_profile.total_levels_completed.saturating_add(1);
             
-            // Award tokens for level completion
-            let level_reward = calculate_level_reward(new_level);
+            // Award tokens for level completion, boosted by staked $ADMIRAL
+            let staked_admiral = read_staked_admiral(
+                ctx.accounts.stake_account.as_ref(),
+                &ctx.accounts.registry_config,
+                &player_profile.player,
+            )?;
+            let vip_multiplier_bps = update_vip_tier(
+                player_profile,
+                ctx.accounts.vip_config.as_ref(),
+                staked_admiral,
+            );
+            let level_reward = boosted_reward(
+                calculate_level_reward(new_level),
+                staked_admiral,
+                vip_multiplier_bps,
+            );
             player_profile.pirate_tokens = player_profile.pirate_tokens.saturating_add(level_reward);
             
             msg!("Level {} completed! Reward: {} $PIRATE", new_level, level_reward);
+
+            award_pass_xp(
+                ctx.accounts.battle_pass.as_ref(),
+                ctx.accounts.pass_progress.as_mut(),
+                |battle_pass| battle_pass.xp_per_level,
+                clock.unix_timestamp,
+            )?;
         }
 
+        apply_achievement_rules(
+            player_profile,
+            &ctx.accounts.achievement_rules,
+            &ctx.accounts.registry_config,
+        );
+
         msg!("Player level updated to: {}", new_level);
         msg!("Total score: {}", player_profile.total_score);
         Ok(())
@@ -99,6 +271,13 @@ pub mod player_registry {
         achievement_name: String,
         reward_amount: u64,
     ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.granter,
+            &ctx.accounts.granter_roles,
+            &[Role::GameServer, Role::Moderator],
+        )?;
+
         let player_profile = &mut ctx.accounts.player_profile;
         let clock = Clock::get()?;
         
@@ -113,6 +292,10 @@ pub mod player_registry {
             achievement_id.len() <= 50 && achievement_name.len() <= 100,
             PlayerRegistryError::InvalidAchievementData
         );
+        require!(
+            player_profile.achievements.len() < MAX_ACHIEVEMENTS,
+            PlayerRegistryError::TooManyAchievements
+        );
 
         // Add achievement
         player_profile.achievements.push(achievement_id.clone());
@@ -127,29 +310,101 @@ pub mod player_registry {
             msg!("Achievement unlocked: {}!", achievement_name);
         }
 
+        apply_achievement_rules(
+            player_profile,
+            &ctx.accounts.achievement_rules,
+            &ctx.accounts.registry_config,
+        );
+
+        // Issue the soulbound badge when this achievement has a badge mint
+        if let (Some(badge_mint), Some(player_badge_account)) = (
+            &ctx.accounts.badge_mint,
+            &ctx.accounts.player_badge_account,
+        ) {
+            mint_badge(
+                player_profile,
+                &achievement_id,
+                badge_mint,
+                player_badge_account,
+                &ctx.accounts.badge_authority,
+                ctx.bumps.badge_authority,
+                &ctx.accounts.token_program,
+            )?;
+
+            emit!(BadgeIssued {
+                player: player_profile.player,
+                achievement_id,
+                badge_mint: badge_mint.key(),
+                backfilled: false,
+            });
+        }
+
         Ok(())
     }
 
-    /// Record boss defeat
+    /// Record boss defeat. The first defeat of a chapter boss completes the chapter
+    /// and pays that chapter's configured boss reward; repeat kills are only logged.
     pub fn record_boss_defeat(
         ctx: Context<RecordBossDefeat>,
         boss_id: String,
         boss_level: u8,
-        reward_amount: u64,
     ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.game_server,
+            &ctx.accounts.game_server_roles,
+            &[Role::GameServer],
+        )?;
+
+        let registry_config = &ctx.accounts.registry_config;
         let player_profile = &mut ctx.accounts.player_profile;
         let clock = Clock::get()?;
+
+        // The boss guards the last level of a chapter the player has reached
+        require!(
+            registry_config.is_boss_level(boss_level) && player_profile.level >= boss_level,
+            PlayerRegistryError::InvalidBossLevel
+        );
         
-        // Update boss defeat stats
-        player_profile.total_bosses_defeated = player_profile.total_bosses_defeated.saturating_add(1);
         player_profile.updated_at = clock.unix_timestamp;
 
-        // Award tokens for boss defeat
+        // Completing a chapter unlocks the next one; the progression achievements
+        // are achievement rules on the chapter fields
+        let chapter = registry_config.chapter_of(boss_level);
+        let chapter_bit = 1u8 << chapter;
+        let first_defeat = player_profile.chapter_bosses_defeated & chapter_bit == 0;
+        let mut reward_amount = 0;
+        if first_defeat {
+            player_profile.chapter_bosses_defeated |= chapter_bit;
+            player_profile.total_bosses_defeated = player_profile.total_bosses_defeated.saturating_add(1);
+            reward_amount = registry_config.boss_reward(chapter);
+            msg!("Chapter {} completed", chapter + 1);
+        }
+
+        // Award tokens for boss defeat, multiplied by the VIP tier
+        let staked_admiral = read_staked_admiral(
+            ctx.accounts.stake_account.as_ref(),
+            &ctx.accounts.registry_config,
+            &player_profile.player,
+        )?;
+        let vip_multiplier_bps = update_vip_tier(
+            player_profile,
+            ctx.accounts.vip_config.as_ref(),
+            staked_admiral,
+        );
+        let reward_amount =
+            apply_vip_multiplier(reward_amount, vip_multiplier_bps.unwrap_or(10_000));
         if reward_amount > 0 {
             player_profile.pirate_tokens = player_profile.pirate_tokens.saturating_add(reward_amount);
             msg!("Boss {} defeated! Reward: {} $PIRATE", boss_id, reward_amount);
         }
 
+        apply_achievement_rules(
+            player_profile,
+            &ctx.accounts.achievement_rules,
+            &ctx.accounts.registry_config,
+        );
+
         msg!("Boss defeat recorded: {} (Level {})", boss_id, boss_level);
         Ok(())
     }
@@ -160,16 +415,35 @@ pub mod player_registry {
     ) -> Result<()> {
         let player_profile = &mut ctx.accounts.player_profile;
         let clock = Clock::get()?;
+
+        require!(
+            !ctx.accounts.registry_config.is_paused(PAUSE_CLAIMS),
+            PlayerRegistryError::Paused
+        );
         
         // Check if enough time has passed since last claim
         let time_since_last_claim = clock.unix_timestamp.saturating_sub(player_profile.last_daily_claim);
         require!(
-            time_since_last_claim >= 86400, // 24 hours in seconds
+            time_since_last_claim >= DAILY_CLAIM_INTERVAL,
             PlayerRegistryError::DailyRewardNotReady
         );
 
-        // Calculate daily reward
-        let daily_reward = calculate_daily_reward(player_profile.streak_days);
+        // Calculate daily reward, boosted by staked $ADMIRAL
+        let staked_admiral = read_staked_admiral(
+            ctx.accounts.stake_account.as_ref(),
+            &ctx.accounts.registry_config,
+            &player_profile.player,
+        )?;
+        let vip_multiplier_bps = update_vip_tier(
+            player_profile,
+            ctx.accounts.vip_config.as_ref(),
+            staked_admiral,
+        );
+        let daily_reward = boosted_reward(
+            calculate_daily_reward(player_profile.streak_days),
+            staked_admiral,
+            vip_multiplier_bps,
+        );
         
         // Update player data
         player_profile.pirate_tokens = player_profile.pirate_tokens.saturating_add(daily_reward);
@@ -177,6 +451,12 @@ pub mod player_registry {
         player_profile.streak_days = player_profile.streak_days.saturating_add(1);
         player_profile.updated_at = clock.unix_timestamp;
 
+        apply_achievement_rules(
+            player_profile,
+            &ctx.accounts.achievement_rules,
+            &ctx.accounts.registry_config,
+        );
+
         msg!("Daily reward claimed: {} $PIRATE", daily_reward);
         msg!("Streak: {} days", player_profile.streak_days);
         Ok(())
@@ -188,11 +468,16 @@ pub mod player_registry {
     ) -> Result<()> {
         let player_profile = &mut ctx.accounts.player_profile;
         let clock = Clock::get()?;
+
+        require!(
+            !ctx.accounts.registry_config.is_paused(PAUSE_CLAIMS),
+            PlayerRegistryError::Paused
+        );
         
         // Check if enough time has passed since last claim
         let time_since_last_claim = clock.unix_timestamp.saturating_sub(player_profile.last_weekly_claim);
         require!(
-            time_since_last_claim >= 604800, // 7 days in seconds
+            time_since_last_claim >= WEEKLY_CLAIM_INTERVAL,
             PlayerRegistryError::WeeklyRewardNotReady
         );
 
@@ -208,6 +493,12 @@ pub mod player_registry {
         player_profile.last_weekly_claim = clock.unix_timestamp;
         player_profile.updated_at = clock.unix_timestamp;
 
+        apply_achievement_rules(
+            player_profile,
+            &ctx.accounts.achievement_rules,
+            &ctx.accounts.registry_config,
+        );
+
         msg!("Weekly reward claimed: {} $ADMIRAL", weekly_reward);
         Ok(())
     }
@@ -232,7 +523,19 @@ pub mod player_registry {
         amount: u64,
         token_type: TokenType,
     ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.treasury_authority,
+            &ctx.accounts.treasury_authority_roles,
+            &[Role::Treasurer],
+        )?;
+
         let player_profile = &mut ctx.accounts.player_profile;
+
+        require!(
+            !ctx.accounts.registry_config.is_paused(PAUSE_TRANSFERS),
+            PlayerRegistryError::Paused
+        );
         
         // Check if player has enough tokens
         let available_tokens = match token_type {
@@ -248,14 +551,15 @@ pub mod player_registry {
         // Transfer tokens from treasury to player
         let transfer_ctx = CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
+            TransferChecked {
                 from: ctx.accounts.treasury_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.player_token_account.to_account_info(),
                 authority: ctx.accounts.treasury_authority.to_account_info(),
             },
         );
         
-        token::transfer(transfer_ctx, amount)?;
+        token_interface::transfer_checked(transfer_ctx, amount, ctx.accounts.mint.decimals)?;
 
         // Update player's token balance
         match token_type {
@@ -284,205 +588,5884 @@ pub mod player_registry {
         msg!("Player account deactivated: {}", player_profile.username);
         Ok(())
     }
-}
 
-// Helper functions
-fn calculate_level_reward(level: u8) -> u64 {
-    match level {
-        1..=10 => 10 + (level as u64 * 5),
-        11..=20 => 60 + ((level - 10) as u64 * 10),
-        21..=30 => 160 + ((level - 20) as u64 * 20),
-        31..=40 => 360 + ((level - 30) as u64 * 40),
-        _ => 1000,
+    /// Pause or resume reward claims and token transfers (emergency switch)
+    pub fn set_paused(
+        ctx: Context<SetPaused>,
+        paused_flags: u8,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.pauser,
+            &ctx.accounts.pauser_roles,
+            &[Role::Pauser],
+        )?;
+
+        let registry_config = &mut ctx.accounts.registry_config;
+        registry_config.paused_flags = paused_flags;
+
+        emit!(PauseStateChanged {
+            pauser: ctx.accounts.pauser.key(),
+            paused_flags,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Registry pause flags set to: {:#04b}", paused_flags);
+        Ok(())
     }
-}
 
-fn calculate_daily_reward(streak_days: u32) -> u64 {
-    let base_reward = 25;
-    let streak_bonus = (streak_days as u64).saturating_mul(5);
-    base_reward.saturating_add(streak_bonus).min(100) // Cap at 100 tokens
-}
+    /// Grant a registry role (Admin only)
+    pub fn grant_role(
+        ctx: Context<GrantRole>,
+        member: Pubkey,
+        role: Role,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            &[Role::Admin],
+        )?;
+        require!(role != Role::Admin, PlayerRegistryError::AdminRoleReserved);
 
-fn calculate_weekly_reward(levels_completed: u32, bosses_defeated: u32, achievements: u32) -> u64 {
-    let base_reward = 10;
-    let level_bonus = (levels_completed as u64).saturating_mul(2);
-    let boss_bonus = (bosses_defeated as u64).saturating_mul(5);
-    let achievement_bonus = (achievements as u64).saturating_mul(3);
-    
-    base_reward
-        .saturating_add(level_bonus)
-        .saturating_add(boss_bonus)
-        .saturating_add(achievement_bonus)
-        .min(100) // Cap at 100 tokens
-}
+        let roles = &mut ctx.accounts.roles;
+        if roles.member == Pubkey::default() {
+            roles.config = ctx.accounts.registry_config.key();
+            roles.member = member;
+            roles.bump = ctx.bumps.roles;
+        }
+        roles.grant(role);
 
-// Account structures
-#[derive(Accounts)]
-pub struct InitializePlayer<'info> {
-    #[account(
-        init,
-        payer = player,
-        space = 8 + PlayerProfile::INIT_SPACE,
-        seeds = [b"player_profile", player.key().as_ref()],
-        bump
-    )]
-    pub player_profile: Account<'info, PlayerProfile>,
-    
-    #[account(mut)]
-    pub player: Signer<'info>,
-    pub system_program: Program<'info, System>,
-}
+        emit!(RoleGranted {
+            config: roles.config,
+            member,
+            role,
+            granted_by: ctx.accounts.authority.key(),
+        });
 
-#[derive(Accounts)]
-pub struct UpdatePlayerLevel<'info> {
-    #[account(
-        mut,
-        seeds = [b"player_profile", player.key().as_ref()],
-        bump,
-        has_one = player
-    )]
-    pub player_profile: Account<'info, PlayerProfile>,
-    pub player: Signer<'info>,
-}
+        msg!("Registry role {} granted to: {}", role.to_string(), member);
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct AddAchievement<'info> {
-    #[account(
-        mut,
-        seeds = [b"player_profile", player.key().as_ref()],
-        bump,
-        has_one = player
-    )]
-    pub player_profile: Account<'info, PlayerProfile>,
-    pub player: Signer<'info>,
-}
+    /// Revoke a registry role (Admin only)
+    pub fn revoke_role(
+        ctx: Context<RevokeRole>,
+        role: Role,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            &[Role::Admin],
+        )?;
 
-#[derive(Accounts)]
-pub struct RecordBossDefeat<'info> {
-    #[account(
-        mut,
-        seeds = [b"player_profile", player.key().as_ref()],
-        bump,
-        has_one = player
-    )]
-    pub player_profile: Account<'info, PlayerProfile>,
-    pub player: Signer<'info>,
-}
+        let roles = &mut ctx.accounts.roles;
+        roles.revoke(role);
 
-#[derive(Accounts)]
-pub struct ClaimDailyReward<'info> {
-    #[account(
-        mut,
-        seeds = [b"player_profile", player.key().as_ref()],
-        bump,
-        has_one = player
-    )]
-    pub player_profile: Account<'info, PlayerProfile>,
-    pub player: Signer<'info>,
-}
+        emit!(RoleRevoked {
+            config: roles.config,
+            member: roles.member,
+            role,
+            revoked_by: ctx.accounts.authority.key(),
+        });
 
-#[derive(Accounts)]
-pub struct ClaimWeeklyReward<'info> {
-    #[account(
-        mut,
-        seeds = [b"player_profile", player.key().as_ref()],
-        bump,
-        has_one = player
-    )]
-    pub player_profile: Account<'info, PlayerProfile>,
-    pub player: Signer<'info>,
-}
+        msg!("Registry role {} revoked from: {}", role.to_string(), roles.member);
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct UpdateLoginTime<'info> {
-    #[account(
-        mut,
-        seeds = [b"player_profile", player.key().as_ref()],
-        bump,
-        has_one = player
-    )]
-    pub player_profile: Account<'info, PlayerProfile>,
-    pub player: Signer<'info>,
-}
+    /// Set the $ADMIRAL staking pool trusted for reward boosts
+    pub fn set_stake_pool(
+        ctx: Context<SetStakePool>,
+        stake_pool: Pubkey,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            &[Role::Admin],
+        )?;
 
-#[derive(Accounts)]
-pub struct TransferTokens<'info> {
-    #[account(
-        mut,
-        seeds = [b"player_profile", player.key().as_ref()],
-        bump,
-        has_one = player
-    )]
-    pub player_profile: Account<'info, PlayerProfile>,
-    
-    #[account(mut)]
-    pub player: Signer<'info>,
-    
-    #[account(mut)]
-    pub treasury_token_account: Account<'info, TokenAccount>,
-    
-    #[account(mut)]
-    pub player_token_account: Account<'info, TokenAccount>,
-    
-    pub treasury_authority: Signer<'info>,
-    pub token_program: Program<'info, Token>,
-}
+        ctx.accounts.registry_config.stake_pool = stake_pool;
 
-#[derive(Accounts)]
-pub struct DeactivatePlayer<'info> {
-    #[account(
-        mut,
-        seeds = [b"player_profile", player.key().as_ref()],
-        bump,
-        has_one = player
-    )]
-    pub player_profile: Account<'info, PlayerProfile>,
-    pub player: Signer<'info>,
-}
+        msg!("Registry stake pool set to: {}", stake_pool);
+        Ok(())
+    }
 
-// Data structures
-#[account]
-#[derive(InitSpace)]
-pub struct PlayerProfile {
-    pub player: Pubkey,
-    pub username: String,
-    pub level: u8,
-    pub score: u64,
-    pub total_score: u64,
-    pub pirate_tokens: u64,
-    pub admiral_tokens: u64,
-    pub total_levels_completed: u32,
-    pub total_bosses_defeated: u32,
-    pub achievements: Vec<String>,
-    pub achievement_count: u32,
-    pub last_daily_claim: i64,
-    pub last_weekly_claim: i64,
-    pub created_at: i64,
-    pub updated_at: i64,
-    pub is_active: bool,
-    pub streak_days: u32,
-    pub last_login: i64,
-}
+    /// Set the $PIRATE and $ADMIRAL mints that tournaments, wagers and the battle
+    /// pass must use for each currency (Admin only)
+    pub fn set_currency_mints(
+        ctx: Context<SetCurrencyMints>,
+        pirate_mint: Pubkey,
+        admiral_mint: Pubkey,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            &[Role::Admin],
+        )?;
 
-// Enums
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
-pub enum TokenType {
-    Pirate,
-    Admiral,
-}
+        let registry_config = &mut ctx.accounts.registry_config;
+        registry_config.pirate_mint = pirate_mint;
+        registry_config.admiral_mint = admiral_mint;
 
-impl TokenType {
-    pub fn to_string(&self) -> String {
-        match self {
-            TokenType::Pirate => "PIRATE".to_string(),
-            TokenType::Admiral => "ADMIRAL".to_string(),
-        }
+        msg!("Currency mints set: $PIRATE {} $ADMIRAL {}", pirate_mint, admiral_mint);
+        Ok(())
     }
-}
 
-// Error codes
-#[error_code]
-pub enum PlayerRegistryError {
+    /// Add chapters to the layout (Admin only). Existing progress, boss levels and
+    /// completed-chapter bits are keyed to the current layout, so chapters can only
+    /// be appended; the chapter length and existing chapters never change.
+    /// `boss_rewards` holds the boss reward of each added chapter, in order.
+    pub fn set_chapter_layout(
+        ctx: Context<SetChapterLayout>,
+        chapter_count: u8,
+        boss_rewards: Vec<u64>,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            &[Role::Admin],
+        )?;
+
+        let registry_config = &mut ctx.accounts.registry_config;
+        require!(
+            chapter_count > registry_config.chapter_count
+                && chapter_count <= MAX_CHAPTERS
+                && chapter_count as u16 * registry_config.levels_per_chapter as u16 <= u8::MAX as u16
+                && boss_rewards.len() == (chapter_count - registry_config.chapter_count) as usize,
+            PlayerRegistryError::InvalidChapterLayout
+        );
+        let added = registry_config.chapter_count as usize..chapter_count as usize;
+        registry_config.boss_rewards[added].copy_from_slice(&boss_rewards);
+        registry_config.chapter_count = chapter_count;
+
+        msg!(
+            "Chapter layout set: {} chapters of {} levels",
+            chapter_count,
+            registry_config.levels_per_chapter
+        );
+        Ok(())
+    }
+
+    /// Set the house fee taken from decided wager matches (Admin only)
+    pub fn set_wager_fee(
+        ctx: Context<SetWagerFee>,
+        wager_fee_bps: u16,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            &[Role::Admin],
+        )?;
+        require!(
+            wager_fee_bps <= MAX_WAGER_FEE_BPS,
+            PlayerRegistryError::InvalidWagerFee
+        );
+
+        ctx.accounts.registry_config.wager_fee_bps = wager_fee_bps;
+
+        msg!("Wager house fee set to {} bps", wager_fee_bps);
+        Ok(())
+    }
+
+    /// Propose a new registry admin or treasury authority
+    pub fn propose_authority(
+        ctx: Context<ProposeAuthority>,
+        kind: AuthorityKind,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        let registry_config = &mut ctx.accounts.registry_config;
+
+        let current_authority = match kind {
+            AuthorityKind::Admin => {
+                registry_config.pending_authority = Some(new_authority);
+                registry_config.authority
+            },
+            AuthorityKind::Treasury => {
+                registry_config.pending_treasury_authority = Some(new_authority);
+                registry_config.treasury_authority
+            },
+        };
+
+        emit!(AuthorityTransferProposed {
+            kind,
+            current_authority,
+            pending_authority: new_authority,
+        });
+
+        msg!("{} authority transfer proposed to: {}", kind.to_string(), new_authority);
+        Ok(())
+    }
+
+    /// Accept a proposed registry admin or treasury authority
+    pub fn accept_authority(
+        ctx: Context<AcceptAuthority>,
+        kind: AuthorityKind,
+    ) -> Result<()> {
+        let registry_config = &mut ctx.accounts.registry_config;
+        let new_authority = ctx.accounts.new_authority.key();
+
+        let (authority, pending_authority) = match kind {
+            AuthorityKind::Admin => (
+                &mut registry_config.authority,
+                &mut registry_config.pending_authority,
+            ),
+            AuthorityKind::Treasury => (
+                &mut registry_config.treasury_authority,
+                &mut registry_config.pending_treasury_authority,
+            ),
+        };
+
+        require!(
+            *pending_authority == Some(new_authority),
+            PlayerRegistryError::NotPendingAuthority
+        );
+
+        let previous_authority = *authority;
+        *authority = new_authority;
+        *pending_authority = None;
+
+        emit!(AuthorityTransferred {
+            kind,
+            previous_authority,
+            new_authority,
+        });
+
+        msg!("{} authority transferred to: {}", kind.to_string(), new_authority);
+        Ok(())
+    }
+
+    /// Create the non-transferable Token-2022 badge mint for an achievement
+    pub fn initialize_badge_mint(
+        ctx: Context<InitializeBadgeMint>,
+        achievement_id: String,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            &[Role::Admin],
+        )?;
+
+        require!(
+            achievement_id.len() <= 50 && name.len() <= 100 && symbol.len() <= 10 && uri.len() <= 200,
+            PlayerRegistryError::InvalidAchievementData
+        );
+
+        let badge_mint = &ctx.accounts.badge_mint;
+        let badge_authority = &ctx.accounts.badge_authority;
+        let token_program = &ctx.accounts.token_program;
+
+        let mint_bump = ctx.bumps.badge_mint;
+        let mint_seeds: &[&[u8]] = &[b"badge_mint", achievement_id.as_bytes(), &[mint_bump]];
+        let authority_bump = ctx.bumps.badge_authority;
+        let authority_seeds: &[&[u8]] = &[b"badge_authority", &[authority_bump]];
+
+        // Create the mint account with room for the badge extensions
+        let mint_space = ExtensionType::try_calculate_account_len::<MintState>(&[
+            ExtensionType::NonTransferable,
+            ExtensionType::MetadataPointer,
+        ])?;
+        let metadata_space = token_metadata_space(&name, &symbol, &uri);
+        let lamports = Rent::get()?.minimum_balance(mint_space + metadata_space);
+
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: badge_mint.to_account_info(),
+                },
+                &[mint_seeds],
+            ),
+            lamports,
+            mint_space as u64,
+            &token_program.key(),
+        )?;
+
+        // Extensions must be initialized before the mint itself
+        token_interface::non_transferable_mint_initialize(CpiContext::new(
+            token_program.to_account_info(),
+            token_interface::NonTransferableMintInitialize {
+                token_program_id: token_program.to_account_info(),
+                mint: badge_mint.to_account_info(),
+            },
+        ))?;
+
+        token_interface::metadata_pointer_initialize(
+            CpiContext::new(
+                token_program.to_account_info(),
+                token_interface::MetadataPointerInitialize {
+                    token_program_id: token_program.to_account_info(),
+                    mint: badge_mint.to_account_info(),
+                },
+            ),
+            Some(badge_authority.key()),
+            Some(badge_mint.key()),
+        )?;
+
+        token_interface::initialize_mint2(
+            CpiContext::new(
+                token_program.to_account_info(),
+                token_interface::InitializeMint2 {
+                    mint: badge_mint.to_account_info(),
+                },
+            ),
+            0,
+            &badge_authority.key(),
+            None,
+        )?;
+
+        // Badge artwork and name live on the mint itself
+        token_interface::token_metadata_initialize(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                token_interface::TokenMetadataInitialize {
+                    token_program_id: token_program.to_account_info(),
+                    mint: badge_mint.to_account_info(),
+                    metadata: badge_mint.to_account_info(),
+                    mint_authority: badge_authority.to_account_info(),
+                    update_authority: badge_authority.to_account_info(),
+                },
+                &[authority_seeds],
+            ),
+            name,
+            symbol,
+            uri,
+        )?;
+
+        msg!("Badge mint created for achievement: {}", achievement_id);
+        Ok(())
+    }
+
+    /// View: level completion reward, including the staking boost or VIP multiplier
+    /// for the current stake when a profile (and optionally its stake account) is
+    /// passed. Simulate to read the result.
+    pub fn quote_level_reward(
+        ctx: Context<QuoteLevelReward>,
+        level: u8,
+    ) -> Result<LevelRewardQuote> {
+        let staked_admiral = match &ctx.accounts.player_profile {
+            Some(player_profile) => read_staked_admiral(
+                ctx.accounts.stake_account.as_ref(),
+                &ctx.accounts.registry_config,
+                &player_profile.player,
+            )?,
+            None => 0,
+        };
+        let vip_multiplier_bps =
+            vip_multiplier_for(ctx.accounts.vip_config.as_ref(), staked_admiral);
+        let base_reward = calculate_level_reward(level);
+
+        Ok(LevelRewardQuote {
+            level,
+            base_reward,
+            boost_bps: quoted_boost_bps(staked_admiral, vip_multiplier_bps),
+            vip_multiplier_bps: vip_multiplier_bps.unwrap_or(10_000),
+            reward: boosted_reward(base_reward, staked_admiral, vip_multiplier_bps),
+        })
+    }
+
+    /// View: the player's next daily reward and when it can be claimed
+    pub fn quote_daily_reward(
+        ctx: Context<QuoteDailyReward>,
+    ) -> Result<DailyRewardQuote> {
+        let player_profile = &ctx.accounts.player_profile;
+        let registry_config = &ctx.accounts.registry_config;
+        let staked_admiral = read_staked_admiral(
+            ctx.accounts.stake_account.as_ref(),
+            registry_config,
+            &player_profile.player,
+        )?;
+
+        let next_claim_at = player_profile
+            .last_daily_claim
+            .saturating_add(DAILY_CLAIM_INTERVAL);
+        let now = Clock::get()?.unix_timestamp;
+        let vip_multiplier_bps =
+            vip_multiplier_for(ctx.accounts.vip_config.as_ref(), staked_admiral);
+        let base_reward = calculate_daily_reward(player_profile.streak_days);
+
+        Ok(DailyRewardQuote {
+            streak_days: player_profile.streak_days,
+            base_reward,
+            boost_bps: quoted_boost_bps(staked_admiral, vip_multiplier_bps),
+            vip_multiplier_bps: vip_multiplier_bps.unwrap_or(10_000),
+            reward: boosted_reward(base_reward, staked_admiral, vip_multiplier_bps),
+            next_claim_at,
+            claimable: now >= next_claim_at && !registry_config.is_paused(PAUSE_CLAIMS),
+        })
+    }
+
+    /// View: the player's next weekly reward and when it can be claimed
+    pub fn quote_weekly_reward(
+        ctx: Context<QuoteWeeklyReward>,
+    ) -> Result<WeeklyRewardQuote> {
+        let player_profile = &ctx.accounts.player_profile;
+        let next_claim_at = player_profile
+            .last_weekly_claim
+            .saturating_add(WEEKLY_CLAIM_INTERVAL);
+        let now = Clock::get()?.unix_timestamp;
+
+        Ok(WeeklyRewardQuote {
+            reward: calculate_weekly_reward(
+                player_profile.total_levels_completed,
+                player_profile.total_bosses_defeated,
+                player_profile.achievement_count,
+            ),
+            next_claim_at,
+            claimable: now >= next_claim_at
+                && !ctx.accounts.registry_config.is_paused(PAUSE_CLAIMS),
+        })
+    }
+
+    /// Add an item to the shop catalog (Admin only)
+    pub fn create_shop_item(
+        ctx: Context<CreateShopItem>,
+        item_id: u16,
+        args: ShopItemArgs,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            &[Role::Admin],
+        )?;
+        validate_shop_item(&args)?;
+
+        let shop_item = &mut ctx.accounts.shop_item;
+        shop_item.item_id = item_id;
+        shop_item.sold = 0;
+        shop_item.bump = ctx.bumps.shop_item;
+        shop_item.apply(args);
+
+        emit!(ShopItemUpdated {
+            item_id,
+            price: shop_item.price,
+            currency: shop_item.currency.clone(),
+            max_supply: shop_item.max_supply,
+            active: shop_item.active,
+        });
+
+        msg!("Shop item {} created: {}", item_id, shop_item.name);
+        Ok(())
+    }
+
+    /// Update a catalog item's price, stock, limits or sale window (Admin only)
+    pub fn update_shop_item(
+        ctx: Context<UpdateShopItem>,
+        args: ShopItemArgs,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            &[Role::Admin],
+        )?;
+        validate_shop_item(&args)?;
+
+        let shop_item = &mut ctx.accounts.shop_item;
+        require!(
+            args.max_supply == 0 || args.max_supply >= shop_item.sold,
+            PlayerRegistryError::InvalidShopItem
+        );
+        shop_item.apply(args);
+
+        emit!(ShopItemUpdated {
+            item_id: shop_item.item_id,
+            price: shop_item.price,
+            currency: shop_item.currency.clone(),
+            max_supply: shop_item.max_supply,
+            active: shop_item.active,
+        });
+
+        msg!("Shop item {} updated", shop_item.item_id);
+        Ok(())
+    }
+
+    /// Buy a catalog item, burning the price or paying it to the treasury
+    pub fn purchase_item(
+        ctx: Context<PurchaseItem>,
+        quantity: u32,
+    ) -> Result<()> {
+        require!(
+            !ctx.accounts.registry_config.is_paused(PAUSE_SHOP),
+            PlayerRegistryError::Paused
+        );
+        require!(quantity > 0, PlayerRegistryError::InvalidQuantity);
+
+        let now = Clock::get()?.unix_timestamp;
+        let shop_item = &ctx.accounts.shop_item;
+        require!(shop_item.is_on_sale(now), PlayerRegistryError::ItemNotAvailable);
+
+        let sold = shop_item
+            .sold
+            .checked_add(quantity)
+            .ok_or(PlayerRegistryError::InvalidQuantity)?;
+        require!(
+            shop_item.max_supply == 0 || sold <= shop_item.max_supply,
+            PlayerRegistryError::OutOfStock
+        );
+
+        let total_price = shop_item
+            .price
+            .checked_mul(quantity as u64)
+            .ok_or(PlayerRegistryError::InvalidQuantity)?;
+
+        // Record the purchase before moving tokens
+        let inventory = &mut ctx.accounts.inventory;
+        if inventory.player == Pubkey::default() {
+            inventory.player = ctx.accounts.player.key();
+            inventory.bump = ctx.bumps.inventory;
+        }
+        let entry = inventory.entry_mut(shop_item.item_id)?;
+        let purchased = entry.purchased.saturating_add(quantity);
+        require!(
+            shop_item.per_player_limit == 0 || purchased <= shop_item.per_player_limit,
+            PlayerRegistryError::PurchaseLimitExceeded
+        );
+        entry.purchased = purchased;
+        entry.quantity = entry.quantity.saturating_add(quantity);
+
+        match shop_item.payment {
+            PaymentMode::Burn => burn_for_purchase(&ctx.accounts, total_price)?,
+            PaymentMode::Treasury => {
+                let treasury_token_account = ctx
+                    .accounts
+                    .treasury_token_account
+                    .as_ref()
+                    .ok_or(PlayerRegistryError::MissingPaymentAccounts)?;
+                let transfer_ctx = CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.player_token_account.to_account_info(),
+                        mint: ctx.accounts.mint.to_account_info(),
+                        to: treasury_token_account.to_account_info(),
+                        authority: ctx.accounts.player.to_account_info(),
+                    },
+                );
+                token_interface::transfer_checked(transfer_ctx, total_price, ctx.accounts.mint.decimals)?;
+            }
+        }
+
+        let shop_item = &mut ctx.accounts.shop_item;
+        shop_item.sold = sold;
+
+        emit!(ItemPurchased {
+            player: ctx.accounts.player.key(),
+            item_id: shop_item.item_id,
+            quantity,
+            total_price,
+            currency: shop_item.currency.clone(),
+            payment: shop_item.payment,
+            timestamp: now,
+        });
+
+        msg!("Purchased {} x item {} for {} {}", quantity, shop_item.item_id, total_price, shop_item.currency.to_string());
+        Ok(())
+    }
+
+    /// Use up consumables during a run (GameServer only)
+    pub fn consume_item(
+        ctx: Context<ConsumeItem>,
+        quantity: u32,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.game_server,
+            &ctx.accounts.game_server_roles,
+            &[Role::GameServer],
+        )?;
+        require!(quantity > 0, PlayerRegistryError::InvalidQuantity);
+
+        let shop_item = &ctx.accounts.shop_item;
+        require!(
+            shop_item.kind == ItemKind::Consumable,
+            PlayerRegistryError::ItemNotConsumable
+        );
+
+        let inventory = &mut ctx.accounts.inventory;
+        let entry = inventory
+            .items
+            .iter_mut()
+            .find(|entry| entry.item_id == shop_item.item_id)
+            .ok_or(PlayerRegistryError::InsufficientItems)?;
+        require!(entry.quantity >= quantity, PlayerRegistryError::InsufficientItems);
+        entry.quantity -= quantity;
+        let remaining = entry.quantity;
+
+        emit!(ItemConsumed {
+            player: inventory.player,
+            item_id: shop_item.item_id,
+            quantity,
+            remaining,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Consumed {} x item {} ({} left)", quantity, shop_item.item_id, remaining);
+        Ok(())
+    }
+
+    /// Issue the badge for an achievement recorded before its badge mint existed.
+    /// Permissionless: the achievement must already be on the profile and its
+    /// badge must never have been issued.
+    pub fn backfill_badge(
+        ctx: Context<BackfillBadge>,
+        achievement_id: String,
+    ) -> Result<()> {
+        let player_profile = &mut ctx.accounts.player_profile;
+        require!(
+            ctx.accounts.player_badge_account.amount == 0,
+            PlayerRegistryError::BadgeAlreadyIssued
+        );
+
+        mint_badge(
+            player_profile,
+            &achievement_id,
+            &ctx.accounts.badge_mint,
+            &ctx.accounts.player_badge_account,
+            &ctx.accounts.badge_authority,
+            ctx.bumps.badge_authority,
+            &ctx.accounts.token_program,
+        )?;
+
+        emit!(BadgeIssued {
+            player: player_profile.player,
+            achievement_id: achievement_id.clone(),
+            badge_mint: ctx.accounts.badge_mint.key(),
+            backfilled: true,
+        });
+
+        msg!("Badge backfilled for achievement: {}", achievement_id);
+        Ok(())
+    }
+
+    /// Create the skin collection mint every skin NFT is a member of (Admin only)
+    pub fn initialize_skin_collection(
+        ctx: Context<InitializeSkinCollection>,
+        name: String,
+        symbol: String,
+        uri: String,
+        max_size: u32,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            &[Role::Admin],
+        )?;
+        require!(
+            name.len() <= 100 && symbol.len() <= 10 && uri.len() <= 200,
+            PlayerRegistryError::InvalidSkinData
+        );
+
+        let collection_mint = &ctx.accounts.collection_mint;
+        let skin_authority = &ctx.accounts.skin_authority;
+        let token_program = &ctx.accounts.token_program;
+
+        let mint_seeds: &[&[u8]] = &[b"skin_collection", &[ctx.bumps.collection_mint]];
+        let authority_seeds: &[&[u8]] = &[b"skin_authority", &[ctx.bumps.skin_authority]];
+
+        // Pointers are allocated up front; metadata and the group are appended
+        // by their initialize instructions, so only their rent is prepaid
+        let mint_space = ExtensionType::try_calculate_account_len::<MintState>(&[
+            ExtensionType::MetadataPointer,
+            ExtensionType::GroupPointer,
+        ])?;
+        let full_space = ExtensionType::try_calculate_account_len::<MintState>(&[
+            ExtensionType::MetadataPointer,
+            ExtensionType::GroupPointer,
+            ExtensionType::TokenGroup,
+        ])? + token_metadata_space(&name, &symbol, &uri);
+        let lamports = Rent::get()?.minimum_balance(full_space);
+
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: collection_mint.to_account_info(),
+                },
+                &[mint_seeds],
+            ),
+            lamports,
+            mint_space as u64,
+            &token_program.key(),
+        )?;
+
+        token_interface::metadata_pointer_initialize(
+            CpiContext::new(
+                token_program.to_account_info(),
+                token_interface::MetadataPointerInitialize {
+                    token_program_id: token_program.to_account_info(),
+                    mint: collection_mint.to_account_info(),
+                },
+            ),
+            Some(skin_authority.key()),
+            Some(collection_mint.key()),
+        )?;
+
+        token_interface::group_pointer_initialize(
+            CpiContext::new(
+                token_program.to_account_info(),
+                token_interface::GroupPointerInitialize {
+                    token_program_id: token_program.to_account_info(),
+                    mint: collection_mint.to_account_info(),
+                },
+            ),
+            Some(skin_authority.key()),
+            Some(collection_mint.key()),
+        )?;
+
+        token_interface::initialize_mint2(
+            CpiContext::new(
+                token_program.to_account_info(),
+                token_interface::InitializeMint2 {
+                    mint: collection_mint.to_account_info(),
+                },
+            ),
+            0,
+            &skin_authority.key(),
+            None,
+        )?;
+
+        token_interface::token_metadata_initialize(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                token_interface::TokenMetadataInitialize {
+                    token_program_id: token_program.to_account_info(),
+                    mint: collection_mint.to_account_info(),
+                    metadata: collection_mint.to_account_info(),
+                    mint_authority: skin_authority.to_account_info(),
+                    update_authority: skin_authority.to_account_info(),
+                },
+                &[authority_seeds],
+            ),
+            name,
+            symbol,
+            uri,
+        )?;
+
+        token_interface::token_group_initialize(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                token_interface::TokenGroupInitialize {
+                    token_program_id: token_program.to_account_info(),
+                    group: collection_mint.to_account_info(),
+                    mint: collection_mint.to_account_info(),
+                    mint_authority: skin_authority.to_account_info(),
+                },
+                &[authority_seeds],
+            ),
+            Some(skin_authority.key()),
+            max_size,
+        )?;
+
+        msg!("Skin collection created: {}", collection_mint.key());
+        Ok(())
+    }
+
+    /// Mint a skin NFT for one of the character sprite sets to a player
+    /// (Admin or GameServer, e.g. for drops and shop fulfilment)
+    pub fn mint_skin(
+        ctx: Context<MintSkin>,
+        character: SkinCharacter,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.minter,
+            &ctx.accounts.minter_roles,
+            &[Role::Admin, Role::GameServer],
+        )?;
+        require!(
+            name.len() <= 100 && symbol.len() <= 10 && uri.len() <= 200,
+            PlayerRegistryError::InvalidSkinData
+        );
+
+        // Fulfil a chest roll that landed on a skin of this character
+        if let Some(chest_opening) = ctx.accounts.chest_opening.as_mut() {
+            require!(
+                chest_opening.status == ChestStatus::AwaitingSkin
+                    && chest_opening.result == Some(LootReward::Skin { character }),
+                PlayerRegistryError::InvalidChestOpening
+            );
+            chest_opening.status = ChestStatus::Fulfilled;
+        }
+
+        let skin_mint = &ctx.accounts.skin_mint;
+        let skin_authority = &ctx.accounts.skin_authority;
+        let token_program = &ctx.accounts.token_program;
+        let authority_seeds: &[&[u8]] = &[b"skin_authority", &[ctx.bumps.skin_authority]];
+
+        // The character trait is stored as an additional metadata field
+        let character_value = character.to_string();
+        let mint_space = ExtensionType::try_calculate_account_len::<MintState>(&[
+            ExtensionType::MetadataPointer,
+            ExtensionType::GroupMemberPointer,
+        ])?;
+        let full_space = ExtensionType::try_calculate_account_len::<MintState>(&[
+            ExtensionType::MetadataPointer,
+            ExtensionType::GroupMemberPointer,
+            ExtensionType::TokenGroupMember,
+        ])? + token_metadata_space(&name, &symbol, &uri)
+            + 4 + SKIN_CHARACTER_FIELD.len() + 4 + character_value.len();
+        let lamports = Rent::get()?.minimum_balance(full_space);
+
+        system_program::create_account(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: ctx.accounts.minter.to_account_info(),
+                    to: skin_mint.to_account_info(),
+                },
+            ),
+            lamports,
+            mint_space as u64,
+            &token_program.key(),
+        )?;
+
+        token_interface::metadata_pointer_initialize(
+            CpiContext::new(
+                token_program.to_account_info(),
+                token_interface::MetadataPointerInitialize {
+                    token_program_id: token_program.to_account_info(),
+                    mint: skin_mint.to_account_info(),
+                },
+            ),
+            Some(skin_authority.key()),
+            Some(skin_mint.key()),
+        )?;
+
+        token_interface::group_member_pointer_initialize(
+            CpiContext::new(
+                token_program.to_account_info(),
+                token_interface::GroupMemberPointerInitialize {
+                    token_program_id: token_program.to_account_info(),
+                    mint: skin_mint.to_account_info(),
+                },
+            ),
+            Some(skin_authority.key()),
+            Some(skin_mint.key()),
+        )?;
+
+        token_interface::initialize_mint2(
+            CpiContext::new(
+                token_program.to_account_info(),
+                token_interface::InitializeMint2 {
+                    mint: skin_mint.to_account_info(),
+                },
+            ),
+            0,
+            &skin_authority.key(),
+            None,
+        )?;
+
+        token_interface::token_metadata_initialize(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                token_interface::TokenMetadataInitialize {
+                    token_program_id: token_program.to_account_info(),
+                    mint: skin_mint.to_account_info(),
+                    metadata: skin_mint.to_account_info(),
+                    mint_authority: skin_authority.to_account_info(),
+                    update_authority: skin_authority.to_account_info(),
+                },
+                &[authority_seeds],
+            ),
+            name,
+            symbol,
+            uri,
+        )?;
+
+        token_interface::token_metadata_update_field(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                token_interface::TokenMetadataUpdateField {
+                    token_program_id: token_program.to_account_info(),
+                    metadata: skin_mint.to_account_info(),
+                    update_authority: skin_authority.to_account_info(),
+                },
+                &[authority_seeds],
+            ),
+            Field::Key(SKIN_CHARACTER_FIELD.to_string()),
+            character_value,
+        )?;
+
+        token_interface::token_member_initialize(CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            token_interface::TokenMemberInitialize {
+                token_program_id: token_program.to_account_info(),
+                member: skin_mint.to_account_info(),
+                member_mint: skin_mint.to_account_info(),
+                member_mint_authority: skin_authority.to_account_info(),
+                group: ctx.accounts.collection_mint.to_account_info(),
+                group_update_authority: skin_authority.to_account_info(),
+            },
+            &[authority_seeds],
+        ))?;
+
+        // Mint the single token, then drop the mint authority so supply stays at one
+        associated_token::create(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: ctx.accounts.minter.to_account_info(),
+                associated_token: ctx.accounts.recipient_skin_account.to_account_info(),
+                authority: ctx.accounts.recipient.to_account_info(),
+                mint: skin_mint.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: token_program.to_account_info(),
+            },
+        ))?;
+
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                token_interface::MintTo {
+                    mint: skin_mint.to_account_info(),
+                    to: ctx.accounts.recipient_skin_account.to_account_info(),
+                    authority: skin_authority.to_account_info(),
+                },
+                &[authority_seeds],
+            ),
+            1,
+        )?;
+
+        token_interface::set_authority(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                token_interface::SetAuthority {
+                    current_authority: skin_authority.to_account_info(),
+                    account_or_mint: skin_mint.to_account_info(),
+                },
+                &[authority_seeds],
+            ),
+            AuthorityType::MintTokens,
+            None,
+        )?;
+
+        let skin = &mut ctx.accounts.skin;
+        skin.mint = skin_mint.key();
+        skin.character = character;
+        skin.bump = ctx.bumps.skin;
+
+        emit!(SkinMinted {
+            mint: skin.mint,
+            character,
+            recipient: ctx.accounts.recipient.key(),
+        });
+
+        msg!("{} skin minted: {}", character.to_string(), skin.mint);
+        Ok(())
+    }
+
+    /// Equip a skin NFT the player holds in their associated token account
+    pub fn equip_skin(
+        ctx: Context<EquipSkin>,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.player_skin_account.amount == 1,
+            PlayerRegistryError::SkinNotOwned
+        );
+
+        let player_profile = &mut ctx.accounts.player_profile;
+        let skin = &ctx.accounts.skin;
+        player_profile.equipped_skin = Some(skin.mint);
+        player_profile.equipped_character = skin.character;
+        player_profile.updated_at = Clock::get()?.unix_timestamp;
+
+        emit!(SkinEquipped {
+            player: player_profile.player,
+            mint: Some(skin.mint),
+            character: skin.character,
+        });
+
+        msg!("Equipped {} skin: {}", skin.character.to_string(), skin.mint);
+        Ok(())
+    }
+
+    /// Go back to the default Bomb Guy sprites
+    pub fn unequip_skin(
+        ctx: Context<UnequipSkin>,
+    ) -> Result<()> {
+        let player_profile = &mut ctx.accounts.player_profile;
+        player_profile.equipped_skin = None;
+        player_profile.equipped_character = SkinCharacter::BombGuy;
+        player_profile.updated_at = Clock::get()?.unix_timestamp;
+
+        emit!(SkinEquipped {
+            player: player_profile.player,
+            mint: None,
+            character: SkinCharacter::BombGuy,
+        });
+
+        msg!("Skin unequipped");
+        Ok(())
+    }
+
+    /// Re-verify the equipped skin and unequip it if the player no longer holds it.
+    /// Permissionless so the game server or a marketplace can clear stale skins.
+    pub fn refresh_equipped_skin(
+        ctx: Context<RefreshEquippedSkin>,
+    ) -> Result<()> {
+        let player_profile = &mut ctx.accounts.player_profile;
+        require!(
+            player_profile.equipped_skin == Some(ctx.accounts.skin.mint),
+            PlayerRegistryError::SkinNotEquipped
+        );
+
+        let player_skin_account = &ctx.accounts.player_skin_account;
+        let still_held = !player_skin_account.data_is_empty()
+            && InterfaceAccount::<TokenAccount>::try_from(&player_skin_account.to_account_info())
+                .map(|account| account.amount == 1)
+                .unwrap_or(false);
+
+        if !still_held {
+            player_profile.equipped_skin = None;
+            player_profile.equipped_character = SkinCharacter::BombGuy;
+            player_profile.updated_at = Clock::get()?.unix_timestamp;
+
+            emit!(SkinEquipped {
+                player: player_profile.player,
+                mint: None,
+                character: SkinCharacter::BombGuy,
+            });
+        }
+
+        msg!("Equipped skin still held: {}", still_held);
+        Ok(())
+    }
+
+    /// Create a tournament with an escrowed entry fee and a payout table (GameServer or Admin)
+    pub fn create_tournament(
+        ctx: Context<CreateTournament>,
+        tournament_id: u64,
+        args: TournamentArgs,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            &[Role::Admin, Role::GameServer],
+        )?;
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            args.entry_fee > 0
+                && args.min_entrants > 0
+                && args.min_entrants <= args.max_entrants
+                && args.starts_at > now
+                && args.ends_at > args.starts_at,
+            PlayerRegistryError::InvalidTournament
+        );
+        require!(
+            !args.payout_bps.is_empty()
+                && args.payout_bps.len() <= MAX_PAYOUT_PLACES
+                && args.payout_bps.len() <= args.min_entrants as usize
+                && args.payout_bps.iter().map(|bps| *bps as u32).sum::<u32>() == 10_000,
+            PlayerRegistryError::InvalidPayoutTable
+        );
+        require_keys_eq!(
+            ctx.accounts.mint.key(),
+            ctx.accounts.registry_config.mint_for(&args.currency),
+            PlayerRegistryError::InvalidTournament
+        );
+
+        let tournament = &mut ctx.accounts.tournament;
+        tournament.tournament_id = tournament_id;
+        tournament.mint = ctx.accounts.mint.key();
+        tournament.currency = args.currency;
+        tournament.entry_fee = args.entry_fee;
+        tournament.min_entrants = args.min_entrants;
+        tournament.max_entrants = args.max_entrants;
+        tournament.entrant_count = 0;
+        tournament.starts_at = args.starts_at;
+        tournament.ends_at = args.ends_at;
+        tournament.payout_bps = args.payout_bps;
+        tournament.winners = Vec::new();
+        tournament.claimed = Vec::new();
+        tournament.prize_pool = 0;
+        tournament.status = TournamentStatus::Open;
+        tournament.bump = ctx.bumps.tournament;
+        tournament.vault_bump = ctx.bumps.vault;
+
+        emit!(TournamentCreated {
+            tournament: tournament.key(),
+            tournament_id,
+            currency: tournament.currency.clone(),
+            entry_fee: tournament.entry_fee,
+            max_entrants: tournament.max_entrants,
+            starts_at: tournament.starts_at,
+            ends_at: tournament.ends_at,
+        });
+
+        msg!("Tournament {} created", tournament_id);
+        Ok(())
+    }
+
+    /// Pay the entry fee into the tournament vault
+    pub fn enter_tournament(
+        ctx: Context<EnterTournament>,
+    ) -> Result<()> {
+        let tournament = &mut ctx.accounts.tournament;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            tournament.status == TournamentStatus::Open && now < tournament.starts_at,
+            PlayerRegistryError::TournamentClosed
+        );
+        require!(
+            tournament.entrant_count < tournament.max_entrants,
+            PlayerRegistryError::TournamentFull
+        );
+
+        // Record what actually reached the vault, so a refund can't exceed it
+        // when the mint takes a transfer fee
+        let vault_before = ctx.accounts.vault.amount;
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.player_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.player.to_account_info(),
+            },
+        );
+        token_interface::transfer_checked(transfer_ctx, tournament.entry_fee, ctx.accounts.mint.decimals)?;
+        ctx.accounts.vault.reload()?;
+
+        tournament.entrant_count += 1;
+
+        let entry = &mut ctx.accounts.entry;
+        entry.tournament = tournament.key();
+        entry.player = ctx.accounts.player.key();
+        entry.amount_paid = ctx.accounts.vault.amount - vault_before;
+        entry.bump = ctx.bumps.entry;
+
+        emit!(TournamentEntered {
+            tournament: tournament.key(),
+            player: entry.player,
+            entrant_count: tournament.entrant_count,
+        });
+
+        msg!("Entered tournament {} ({} of {})", tournament.tournament_id, tournament.entrant_count, tournament.max_entrants);
+        Ok(())
+    }
+
+    /// Submit the final ranking after the tournament ends (GameServer only).
+    /// remaining_accounts holds each winner's TournamentEntry, in rank order.
+    pub fn submit_rankings<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SubmitRankings<'info>>,
+        winners: Vec<Pubkey>,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.game_server,
+            &ctx.accounts.game_server_roles,
+            &[Role::GameServer],
+        )?;
+
+        let tournament = &mut ctx.accounts.tournament;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            tournament.status == TournamentStatus::Open && now >= tournament.ends_at,
+            PlayerRegistryError::TournamentNotFinished
+        );
+        require!(
+            tournament.entrant_count >= tournament.min_entrants,
+            PlayerRegistryError::TournamentNotFilled
+        );
+        require!(
+            winners.len() == tournament.payout_bps.len()
+                && ctx.remaining_accounts.len() == winners.len(),
+            PlayerRegistryError::InvalidRankings
+        );
+
+        // Every ranked player must have entered, and only once
+        let tournament_key = tournament.key();
+        for (i, (winner, entry_info)) in winners.iter().zip(ctx.remaining_accounts.iter()).enumerate() {
+            require!(!winners[..i].contains(winner), PlayerRegistryError::InvalidRankings);
+            let entry = Account::<TournamentEntry>::try_from(entry_info)?;
+            require!(
+                entry.tournament == tournament_key && entry.player == *winner,
+                PlayerRegistryError::InvalidRankings
+            );
+        }
+
+        tournament.prize_pool = ctx.accounts.vault.amount;
+        tournament.claimed = vec![false; winners.len()];
+        tournament.winners = winners;
+        tournament.status = TournamentStatus::Settled;
+
+        emit!(TournamentSettled {
+            tournament: tournament_key,
+            winners: tournament.winners.clone(),
+            prize_pool: tournament.prize_pool,
+        });
+
+        msg!("Tournament {} settled, prize pool {}", tournament.tournament_id, tournament.prize_pool);
+        Ok(())
+    }
+
+    /// Pay out one ranked place. Permissionless so payouts can be cranked.
+    pub fn claim_tournament_payout(
+        ctx: Context<ClaimTournamentPayout>,
+        rank: u8,
+    ) -> Result<()> {
+        require!(
+            !ctx.accounts.registry_config.is_paused(PAUSE_CLAIMS),
+            PlayerRegistryError::Paused
+        );
+
+        let tournament = &mut ctx.accounts.tournament;
+        let rank = rank as usize;
+        require!(
+            tournament.status == TournamentStatus::Settled,
+            PlayerRegistryError::TournamentNotFinished
+        );
+        require!(rank < tournament.winners.len(), PlayerRegistryError::InvalidRankings);
+        require!(!tournament.claimed[rank], PlayerRegistryError::PayoutAlreadyClaimed);
+        require_keys_eq!(
+            ctx.accounts.winner_token_account.owner,
+            tournament.winners[rank],
+            PlayerRegistryError::InvalidRankings
+        );
+
+        let payout = tournament.payout_for(rank);
+        tournament.claimed[rank] = true;
+
+        let tournament_id = tournament.tournament_id.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[b"tournament", tournament_id.as_ref(), &[tournament.bump]]];
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.winner_token_account.to_account_info(),
+                    authority: tournament.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            payout,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        emit!(TournamentPayout {
+            tournament: tournament.key(),
+            winner: tournament.winners[rank],
+            rank: rank as u8,
+            amount: payout,
+        });
+
+        msg!("Tournament {} place {} paid {}", tournament.tournament_id, rank + 1, payout);
+        Ok(())
+    }
+
+    /// Cancel a tournament before it is settled so entries can be refunded (GameServer or Admin)
+    pub fn cancel_tournament(
+        ctx: Context<CancelTournament>,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            &[Role::Admin, Role::GameServer],
+        )?;
+
+        let tournament = &mut ctx.accounts.tournament;
+        require!(
+            tournament.status == TournamentStatus::Open,
+            PlayerRegistryError::TournamentClosed
+        );
+        tournament.status = TournamentStatus::Cancelled;
+
+        emit!(TournamentCancelled {
+            tournament: tournament.key(),
+            entrant_count: tournament.entrant_count,
+        });
+
+        msg!("Tournament {} cancelled", tournament.tournament_id);
+        Ok(())
+    }
+
+    /// Refund an entry when the tournament was cancelled or didn't reach its
+    /// minimum entrants by the start time. Permissionless so refunds can be cranked.
+    pub fn refund_tournament_entry(
+        ctx: Context<RefundTournamentEntry>,
+    ) -> Result<()> {
+        let tournament = &ctx.accounts.tournament;
+        let refund = ctx.accounts.entry.amount_paid;
+        let now = Clock::get()?.unix_timestamp;
+        let unfilled = tournament.status == TournamentStatus::Open
+            && now >= tournament.starts_at
+            && tournament.entrant_count < tournament.min_entrants;
+        require!(
+            tournament.status == TournamentStatus::Cancelled || unfilled,
+            PlayerRegistryError::RefundNotAvailable
+        );
+
+        let tournament_id = tournament.tournament_id.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[b"tournament", tournament_id.as_ref(), &[tournament.bump]]];
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.player_token_account.to_account_info(),
+                    authority: tournament.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            refund,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        emit!(TournamentRefunded {
+            tournament: tournament.key(),
+            player: ctx.accounts.entry.player,
+            amount: refund,
+        });
+
+        msg!("Tournament {} entry refunded", tournament.tournament_id);
+        Ok(())
+    }
+
+    /// Open a 1v1 wager, escrowing the challenger's stake. `opponent` restricts
+    /// who can accept; None leaves the challenge open to anyone.
+    pub fn create_match(
+        ctx: Context<CreateMatch>,
+        match_id: u64,
+        invited: Option<Pubkey>,
+        stake: u64,
+    ) -> Result<()> {
+        let registry_config = &ctx.accounts.registry_config;
+        require!(
+            !registry_config.is_paused(PAUSE_WAGERS),
+            PlayerRegistryError::Paused
+        );
+        require!(stake > 0, PlayerRegistryError::InvalidWager);
+        require!(
+            invited != Some(ctx.accounts.challenger.key()),
+            PlayerRegistryError::InvalidWager
+        );
+
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.challenger_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.challenger.to_account_info(),
+            },
+        );
+        token_interface::transfer_checked(transfer_ctx, stake, ctx.accounts.mint.decimals)?;
+
+        let wager_match = &mut ctx.accounts.wager_match;
+        wager_match.match_id = match_id;
+        wager_match.challenger = ctx.accounts.challenger.key();
+        wager_match.invited = invited;
+        wager_match.opponent = Pubkey::default();
+        wager_match.mint = ctx.accounts.mint.key();
+        wager_match.stake = stake;
+        // Snapshot the fee so a later change can't apply to an accepted match
+        wager_match.fee_bps = registry_config.wager_fee_bps;
+        wager_match.status = MatchStatus::Open;
+        wager_match.created_at = Clock::get()?.unix_timestamp;
+        wager_match.accepted_at = 0;
+        wager_match.bump = ctx.bumps.wager_match;
+        wager_match.vault_bump = ctx.bumps.vault;
+
+        emit!(MatchCreated {
+            wager_match: wager_match.key(),
+            challenger: wager_match.challenger,
+            invited,
+            stake,
+            fee_bps: wager_match.fee_bps,
+        });
+
+        msg!("Match {} created with a {} stake", match_id, stake);
+        Ok(())
+    }
+
+    /// Withdraw a challenge nobody has accepted yet
+    pub fn cancel_match(
+        ctx: Context<CancelMatch>,
+    ) -> Result<()> {
+        let wager_match = &ctx.accounts.wager_match;
+        require!(
+            wager_match.status == MatchStatus::Open,
+            PlayerRegistryError::MatchNotOpen
+        );
+
+        pay_from_match_vault(
+            wager_match,
+            &ctx.accounts.vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.challenger_token_account,
+            &ctx.accounts.token_program,
+            wager_match.stake,
+        )?;
+        close_match_vault(
+            wager_match,
+            &ctx.accounts.vault,
+            &ctx.accounts.challenger.to_account_info(),
+            &ctx.accounts.token_program,
+        )?;
+
+        emit!(MatchClosed {
+            wager_match: wager_match.key(),
+            result: None,
+            winner: None,
+            house_fee: 0,
+            timed_out: false,
+        });
+
+        msg!("Match {} cancelled", wager_match.match_id);
+        Ok(())
+    }
+
+    /// Accept a challenge by escrowing an equal stake
+    pub fn accept_match(
+        ctx: Context<AcceptMatch>,
+    ) -> Result<()> {
+        require!(
+            !ctx.accounts.registry_config.is_paused(PAUSE_WAGERS),
+            PlayerRegistryError::Paused
+        );
+
+        let wager_match = &mut ctx.accounts.wager_match;
+        let opponent = ctx.accounts.opponent.key();
+        require!(
+            wager_match.status == MatchStatus::Open,
+            PlayerRegistryError::MatchNotOpen
+        );
+        require!(
+            opponent != wager_match.challenger
+                && wager_match.invited.map_or(true, |invited| invited == opponent),
+            PlayerRegistryError::NotInvited
+        );
+
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.opponent_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.opponent.to_account_info(),
+            },
+        );
+        token_interface::transfer_checked(transfer_ctx, wager_match.stake, ctx.accounts.mint.decimals)?;
+
+        wager_match.opponent = opponent;
+        wager_match.status = MatchStatus::Active;
+        wager_match.accepted_at = Clock::get()?.unix_timestamp;
+
+        emit!(MatchAccepted {
+            wager_match: wager_match.key(),
+            opponent,
+            settle_deadline: wager_match.accepted_at.saturating_add(MATCH_SETTLE_TIMEOUT),
+        });
+
+        msg!("Match {} accepted by {}", wager_match.match_id, opponent);
+        Ok(())
+    }
+
+    /// Settle an accepted match (GameServer only). The winner takes the pot minus
+    /// the house fee; a draw returns both stakes without a fee.
+    pub fn settle_match(
+        ctx: Context<SettleMatch>,
+        result: MatchResult,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.game_server,
+            &ctx.accounts.game_server_roles,
+            &[Role::GameServer],
+        )?;
+
+        let wager_match = &ctx.accounts.wager_match;
+        require!(
+            wager_match.status == MatchStatus::Active,
+            PlayerRegistryError::MatchNotActive
+        );
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now < wager_match.accepted_at.saturating_add(MATCH_SETTLE_TIMEOUT),
+            PlayerRegistryError::MatchTimedOut
+        );
+
+        let pot = wager_match.stake.saturating_mul(2);
+        let (winner, house_fee) = match result {
+            MatchResult::Draw => {
+                pay_from_match_vault(
+                    wager_match,
+                    &ctx.accounts.vault,
+                    &ctx.accounts.mint,
+                    &ctx.accounts.challenger_token_account,
+                    &ctx.accounts.token_program,
+                    wager_match.stake,
+                )?;
+                pay_from_match_vault(
+                    wager_match,
+                    &ctx.accounts.vault,
+                    &ctx.accounts.mint,
+                    &ctx.accounts.opponent_token_account,
+                    &ctx.accounts.token_program,
+                    wager_match.stake,
+                )?;
+                (None, 0)
+            }
+            MatchResult::ChallengerWins | MatchResult::OpponentWins => {
+                let house_fee = (pot as u128 * wager_match.fee_bps as u128 / 10_000) as u64;
+                let (winner, winner_token_account) = if result == MatchResult::ChallengerWins {
+                    (wager_match.challenger, &ctx.accounts.challenger_token_account)
+                } else {
+                    (wager_match.opponent, &ctx.accounts.opponent_token_account)
+                };
+
+                pay_from_match_vault(
+                    wager_match,
+                    &ctx.accounts.vault,
+                    &ctx.accounts.mint,
+                    winner_token_account,
+                    &ctx.accounts.token_program,
+                    pot - house_fee,
+                )?;
+                if house_fee > 0 {
+                    pay_from_match_vault(
+                        wager_match,
+                        &ctx.accounts.vault,
+                        &ctx.accounts.mint,
+                        &ctx.accounts.treasury_token_account,
+                        &ctx.accounts.token_program,
+                        house_fee,
+                    )?;
+                }
+                (Some(winner), house_fee)
+            }
+        };
+
+        close_match_vault(
+            wager_match,
+            &ctx.accounts.vault,
+            &ctx.accounts.challenger.to_account_info(),
+            &ctx.accounts.token_program,
+        )?;
+
+        emit!(MatchClosed {
+            wager_match: wager_match.key(),
+            result: Some(result),
+            winner,
+            house_fee,
+            timed_out: false,
+        });
+
+        msg!("Match {} settled", wager_match.match_id);
+        Ok(())
+    }
+
+    /// Refund both stakes when an accepted match was never settled.
+    /// Permissionless, either player (or a crank) can call it after the timeout.
+    pub fn refund_timed_out_match(
+        ctx: Context<RefundTimedOutMatch>,
+    ) -> Result<()> {
+        let wager_match = &ctx.accounts.wager_match;
+        require!(
+            wager_match.status == MatchStatus::Active,
+            PlayerRegistryError::MatchNotActive
+        );
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now >= wager_match.accepted_at.saturating_add(MATCH_SETTLE_TIMEOUT),
+            PlayerRegistryError::MatchNotTimedOut
+        );
+
+        pay_from_match_vault(
+            wager_match,
+            &ctx.accounts.vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.challenger_token_account,
+            &ctx.accounts.token_program,
+            wager_match.stake,
+        )?;
+        pay_from_match_vault(
+            wager_match,
+            &ctx.accounts.vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.opponent_token_account,
+            &ctx.accounts.token_program,
+            wager_match.stake,
+        )?;
+        close_match_vault(
+            wager_match,
+            &ctx.accounts.vault,
+            &ctx.accounts.challenger.to_account_info(),
+            &ctx.accounts.token_program,
+        )?;
+
+        emit!(MatchClosed {
+            wager_match: wager_match.key(),
+            result: None,
+            winner: None,
+            house_fee: 0,
+            timed_out: true,
+        });
+
+        msg!("Match {} timed out, stakes refunded", wager_match.match_id);
+        Ok(())
+    }
+
+    /// Define a quest (Admin only). Quests sharing a cadence rotate by period index:
+    /// the quest is live when `period % rotation_length == rotation_slot`.
+    pub fn create_quest(
+        ctx: Context<CreateQuest>,
+        quest_id: u16,
+        args: QuestArgs,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            &[Role::Admin],
+        )?;
+        validate_quest(&args)?;
+
+        let quest = &mut ctx.accounts.quest;
+        quest.quest_id = quest_id;
+        quest.bump = ctx.bumps.quest;
+        quest.apply(args);
+
+        emit!(QuestUpdated {
+            quest_id,
+            cadence: quest.cadence,
+            objective: quest.objective,
+            target: quest.target,
+            reward_token: quest.reward_token.clone(),
+            reward_amount: quest.reward_amount,
+            active: quest.active,
+        });
+
+        msg!("Quest {} created: {}", quest_id, quest.name);
+        Ok(())
+    }
+
+    /// Update a quest's objective, reward or rotation (Admin only)
+    pub fn update_quest(
+        ctx: Context<UpdateQuest>,
+        args: QuestArgs,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            &[Role::Admin],
+        )?;
+        validate_quest(&args)?;
+
+        let quest = &mut ctx.accounts.quest;
+        quest.apply(args);
+
+        emit!(QuestUpdated {
+            quest_id: quest.quest_id,
+            cadence: quest.cadence,
+            objective: quest.objective,
+            target: quest.target,
+            reward_token: quest.reward_token.clone(),
+            reward_amount: quest.reward_amount,
+            active: quest.active,
+        });
+
+        msg!("Quest {} updated", quest.quest_id);
+        Ok(())
+    }
+
+    /// Add objective progress for a player on a live quest (GameServer only).
+    /// Progress from an earlier period is reset first.
+    pub fn record_quest_progress(
+        ctx: Context<RecordQuestProgress>,
+        amount: u32,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.game_server,
+            &ctx.accounts.game_server_roles,
+            &[Role::GameServer],
+        )?;
+        require!(amount > 0, PlayerRegistryError::InvalidQuantity);
+
+        let quest = &ctx.accounts.quest;
+        let now = Clock::get()?.unix_timestamp;
+        require!(quest.is_live(now), PlayerRegistryError::QuestNotActive);
+        let period = quest.cadence.period_index(now);
+
+        let quest_progress = &mut ctx.accounts.quest_progress;
+        if quest_progress.quest == Pubkey::default() {
+            quest_progress.quest = quest.key();
+            quest_progress.player = ctx.accounts.player.key();
+            quest_progress.bump = ctx.bumps.quest_progress;
+        }
+        if quest_progress.period != period {
+            quest_progress.period = period;
+            quest_progress.progress = 0;
+            quest_progress.claimed = false;
+        }
+        quest_progress.progress = quest_progress.progress.saturating_add(amount).min(quest.target);
+
+        emit!(QuestProgressed {
+            player: quest_progress.player,
+            quest_id: quest.quest_id,
+            period,
+            progress: quest_progress.progress,
+            target: quest.target,
+        });
+
+        msg!(
+            "Quest {} progress: {}/{}",
+            quest.quest_id,
+            quest_progress.progress,
+            quest.target
+        );
+        Ok(())
+    }
+
+    /// Claim the reward of a quest completed in the current period
+    pub fn claim_quest(
+        ctx: Context<ClaimQuest>,
+    ) -> Result<()> {
+        require!(
+            !ctx.accounts.registry_config.is_paused(PAUSE_CLAIMS),
+            PlayerRegistryError::Paused
+        );
+
+        let quest = &ctx.accounts.quest;
+        let quest_progress = &mut ctx.accounts.quest_progress;
+        let now = Clock::get()?.unix_timestamp;
+        let period = quest.cadence.period_index(now);
+
+        require!(
+            quest_progress.period == period,
+            PlayerRegistryError::QuestExpired
+        );
+        require!(!quest_progress.claimed, PlayerRegistryError::QuestAlreadyClaimed);
+        require!(
+            quest_progress.progress >= quest.target,
+            PlayerRegistryError::QuestNotComplete
+        );
+        quest_progress.claimed = true;
+
+        let player_profile = &mut ctx.accounts.player_profile;
+        match quest.reward_token {
+            TokenType::Pirate => {
+                player_profile.pirate_tokens = player_profile.pirate_tokens.saturating_add(quest.reward_amount);
+            },
+            TokenType::Admiral => {
+                player_profile.admiral_tokens = player_profile.admiral_tokens.saturating_add(quest.reward_amount);
+            },
+        }
+        player_profile.updated_at = now;
+
+        apply_achievement_rules(
+            player_profile,
+            &ctx.accounts.achievement_rules,
+            &ctx.accounts.registry_config,
+        );
+
+        award_pass_xp(
+            ctx.accounts.battle_pass.as_ref(),
+            ctx.accounts.pass_progress.as_mut(),
+            |battle_pass| battle_pass.xp_per_quest,
+            now,
+        )?;
+
+        emit!(QuestClaimed {
+            player: player_profile.player,
+            quest_id: quest.quest_id,
+            period,
+            reward_token: quest.reward_token.clone(),
+            reward_amount: quest.reward_amount,
+        });
+
+        msg!(
+            "Quest {} claimed: {} ${}",
+            quest.quest_id,
+            quest.reward_amount,
+            quest.reward_token.to_string()
+        );
+        Ok(())
+    }
+
+    /// Configure VIP tiers (Admin only). `thresholds[i]` is the staked $ADMIRAL (in
+    /// base units) needed for tier i + 1, which multiplies level, daily and boss
+    /// rewards by `multipliers_bps[i]`. Tier 0 earns the base reward. Once tiers are
+    /// configured they replace the staking boost rather than stacking on it.
+    pub fn set_vip_tiers(
+        ctx: Context<SetVipTiers>,
+        thresholds: Vec<u64>,
+        multipliers_bps: Vec<u16>,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            &[Role::Admin],
+        )?;
+        require!(
+            !thresholds.is_empty()
+                && thresholds.len() <= MAX_VIP_TIERS
+                && thresholds.len() == multipliers_bps.len()
+                && thresholds[0] > 0
+                && thresholds.windows(2).all(|pair| pair[0] < pair[1]),
+            PlayerRegistryError::InvalidVipTiers
+        );
+        require!(
+            multipliers_bps[0] >= 10_000
+                && multipliers_bps.windows(2).all(|pair| pair[0] <= pair[1])
+                && multipliers_bps.iter().all(|bps| *bps <= MAX_VIP_MULTIPLIER_BPS),
+            PlayerRegistryError::InvalidVipTiers
+        );
+
+        let vip_config = &mut ctx.accounts.vip_config;
+        vip_config.thresholds = thresholds;
+        vip_config.multipliers_bps = multipliers_bps;
+        vip_config.bump = ctx.bumps.vip_config;
+
+        emit!(VipTiersUpdated {
+            thresholds: vip_config.thresholds.clone(),
+            multipliers_bps: vip_config.multipliers_bps.clone(),
+        });
+
+        msg!("VIP tiers updated: {} tiers", vip_config.thresholds.len());
+        Ok(())
+    }
+
+    /// Recompute the caller's VIP tier from their $ADMIRAL stake
+    pub fn refresh_vip_tier(
+        ctx: Context<RefreshVipTier>,
+    ) -> Result<()> {
+        let player_profile = &mut ctx.accounts.player_profile;
+        let staked_admiral = read_staked_admiral(
+            ctx.accounts.stake_account.as_ref(),
+            &ctx.accounts.registry_config,
+            &player_profile.player,
+        )?;
+        update_vip_tier(player_profile, Some(&ctx.accounts.vip_config), staked_admiral);
+
+        msg!("VIP tier: {}", player_profile.vip_tier);
+        Ok(())
+    }
+
+    /// Create a season's battle pass (Admin only). Premium passes are paid in the
+    /// configured $ADMIRAL mint.
+    pub fn create_battle_pass(
+        ctx: Context<CreateBattlePass>,
+        season: u16,
+        args: BattlePassArgs,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            &[Role::Admin],
+        )?;
+        validate_battle_pass(&args)?;
+        require_keys_eq!(
+            args.premium_mint,
+            ctx.accounts.registry_config.admiral_mint,
+            PlayerRegistryError::InvalidBattlePass
+        );
+
+        let battle_pass = &mut ctx.accounts.battle_pass;
+        battle_pass.season = season;
+        battle_pass.bump = ctx.bumps.battle_pass;
+        battle_pass.apply(args);
+
+        emit!(BattlePassUpdated {
+            season,
+            tier_count: battle_pass.tiers.len() as u16,
+            xp_per_tier: battle_pass.xp_per_tier,
+            premium_price: battle_pass.premium_price,
+            starts_at: battle_pass.starts_at,
+            ends_at: battle_pass.ends_at,
+        });
+
+        msg!("Battle pass created for season {}: {}", season, battle_pass.name);
+        Ok(())
+    }
+
+    /// Replace a battle pass's tiers and settings before its season starts (Admin only)
+    pub fn update_battle_pass(
+        ctx: Context<UpdateBattlePass>,
+        args: BattlePassArgs,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            &[Role::Admin],
+        )?;
+        validate_battle_pass(&args)?;
+        require_keys_eq!(
+            args.premium_mint,
+            ctx.accounts.registry_config.admiral_mint,
+            PlayerRegistryError::InvalidBattlePass
+        );
+
+        let battle_pass = &mut ctx.accounts.battle_pass;
+        require!(
+            Clock::get()?.unix_timestamp < battle_pass.starts_at,
+            PlayerRegistryError::BattlePassStarted
+        );
+        battle_pass.apply(args);
+
+        emit!(BattlePassUpdated {
+            season: battle_pass.season,
+            tier_count: battle_pass.tiers.len() as u16,
+            xp_per_tier: battle_pass.xp_per_tier,
+            premium_price: battle_pass.premium_price,
+            starts_at: battle_pass.starts_at,
+            ends_at: battle_pass.ends_at,
+        });
+
+        msg!("Battle pass for season {} updated", battle_pass.season);
+        Ok(())
+    }
+
+    /// Join a season's battle pass on the free track
+    pub fn join_battle_pass(
+        ctx: Context<JoinBattlePass>,
+    ) -> Result<()> {
+        let battle_pass = &ctx.accounts.battle_pass;
+        require!(
+            Clock::get()?.unix_timestamp < battle_pass.ends_at,
+            PlayerRegistryError::BattlePassNotLive
+        );
+
+        let pass_progress = &mut ctx.accounts.pass_progress;
+        pass_progress.battle_pass = battle_pass.key();
+        pass_progress.player = ctx.accounts.player.key();
+        pass_progress.xp = 0;
+        pass_progress.premium = false;
+        pass_progress.free_claimed = 0;
+        pass_progress.premium_claimed = 0;
+        pass_progress.bump = ctx.bumps.pass_progress;
+
+        msg!("Joined the season {} battle pass", battle_pass.season);
+        Ok(())
+    }
+
+    /// Unlock the premium track by paying the pass price in $ADMIRAL to the treasury
+    pub fn unlock_premium_pass(
+        ctx: Context<UnlockPremiumPass>,
+    ) -> Result<()> {
+        let battle_pass = &ctx.accounts.battle_pass;
+        let pass_progress = &mut ctx.accounts.pass_progress;
+        require!(
+            Clock::get()?.unix_timestamp < battle_pass.ends_at,
+            PlayerRegistryError::BattlePassNotLive
+        );
+        require!(
+            !pass_progress.premium,
+            PlayerRegistryError::PremiumPassAlreadyUnlocked
+        );
+
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.player_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.treasury_token_account.to_account_info(),
+                authority: ctx.accounts.player.to_account_info(),
+            },
+        );
+        token_interface::transfer_checked(transfer_ctx, battle_pass.premium_price, ctx.accounts.mint.decimals)?;
+
+        pass_progress.premium = true;
+
+        emit!(PremiumPassUnlocked {
+            player: pass_progress.player,
+            season: battle_pass.season,
+            price: battle_pass.premium_price,
+        });
+
+        msg!("Premium pass unlocked for season {}", battle_pass.season);
+        Ok(())
+    }
+
+    /// Claim a reached tier's reward on the free or premium track, once per track
+    pub fn claim_pass_tier(
+        ctx: Context<ClaimPassTier>,
+        tier: u16,
+        premium: bool,
+    ) -> Result<()> {
+        require!(
+            !ctx.accounts.registry_config.is_paused(PAUSE_CLAIMS),
+            PlayerRegistryError::Paused
+        );
+
+        let battle_pass = &ctx.accounts.battle_pass;
+        let pass_progress = &mut ctx.accounts.pass_progress;
+        let pass_tier = battle_pass
+            .tiers
+            .get(tier as usize)
+            .ok_or(PlayerRegistryError::InvalidPassTier)?;
+        require!(
+            pass_progress.xp >= battle_pass.xp_required(tier),
+            PlayerRegistryError::PassTierLocked
+        );
+        require!(
+            !premium || pass_progress.premium,
+            PlayerRegistryError::PremiumPassRequired
+        );
+
+        let bit = 1u64 << tier;
+        let claimed = if premium {
+            &mut pass_progress.premium_claimed
+        } else {
+            &mut pass_progress.free_claimed
+        };
+        require!(*claimed & bit == 0, PlayerRegistryError::PassTierAlreadyClaimed);
+        *claimed |= bit;
+
+        let reward = if premium { pass_tier.premium.clone() } else { pass_tier.free.clone() };
+        match &reward {
+            PassReward::None => {},
+            PassReward::Tokens { token_type, amount } => {
+                let player_profile = &mut ctx.accounts.player_profile;
+                match token_type {
+                    TokenType::Pirate => {
+                        player_profile.pirate_tokens = player_profile.pirate_tokens.saturating_add(*amount);
+                    },
+                    TokenType::Admiral => {
+                        player_profile.admiral_tokens = player_profile.admiral_tokens.saturating_add(*amount);
+                    },
+                }
+            },
+            PassReward::Item { item_id, quantity } => {
+                let inventory = &mut ctx.accounts.inventory;
+                if inventory.player == Pubkey::default() {
+                    inventory.player = pass_progress.player;
+                    inventory.bump = ctx.bumps.inventory;
+                }
+                let entry = inventory.entry_mut(*item_id)?;
+                entry.quantity = entry.quantity.saturating_add(*quantity);
+            },
+        }
+
+        apply_achievement_rules(
+            &mut ctx.accounts.player_profile,
+            &ctx.accounts.achievement_rules,
+            &ctx.accounts.registry_config,
+        );
+
+        emit!(PassTierClaimed {
+            player: pass_progress.player,
+            season: battle_pass.season,
+            tier,
+            premium,
+            reward,
+        });
+
+        msg!(
+            "Season {} tier {} ({}) claimed",
+            battle_pass.season,
+            tier,
+            if premium { "premium" } else { "free" }
+        );
+        Ok(())
+    }
+
+    /// Give a player catalog items outside the shop, e.g. chest drops (GameServer only)
+    pub fn grant_item(
+        ctx: Context<GrantItem>,
+        quantity: u32,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.game_server,
+            &ctx.accounts.game_server_roles,
+            &[Role::GameServer],
+        )?;
+        require!(quantity > 0, PlayerRegistryError::InvalidQuantity);
+
+        let inventory = &mut ctx.accounts.inventory;
+        if inventory.player == Pubkey::default() {
+            inventory.player = ctx.accounts.player.key();
+            inventory.bump = ctx.bumps.inventory;
+        }
+        let item_id = ctx.accounts.shop_item.item_id;
+        let entry = inventory.entry_mut(item_id)?;
+        entry.quantity = entry.quantity.saturating_add(quantity);
+
+        emit!(ItemGranted {
+            player: inventory.player,
+            item_id,
+            quantity,
+            granted_by: ctx.accounts.game_server.key(),
+        });
+
+        msg!("Granted {} x item {}", quantity, item_id);
+        Ok(())
+    }
+
+    /// Create a loot table for a chest item (Admin only). The weights are the published odds.
+    pub fn create_loot_table(
+        ctx: Context<CreateLootTable>,
+        table_id: u16,
+        args: LootTableArgs,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            &[Role::Admin],
+        )?;
+        validate_loot_table(&args)?;
+
+        let loot_table = &mut ctx.accounts.loot_table;
+        loot_table.table_id = table_id;
+        loot_table.bump = ctx.bumps.loot_table;
+        loot_table.apply(args);
+
+        emit!(LootTableUpdated {
+            table_id,
+            chest_item_id: loot_table.chest_item_id,
+            weights: loot_table.entries.iter().map(|entry| entry.weight).collect(),
+            total_weight: loot_table.total_weight,
+            version: loot_table.version,
+            active: loot_table.active,
+        });
+
+        msg!("Loot table {} created: {}", table_id, loot_table.name);
+        Ok(())
+    }
+
+    /// Replace a loot table's entries and odds (Admin only)
+    pub fn update_loot_table(
+        ctx: Context<UpdateLootTable>,
+        args: LootTableArgs,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            &[Role::Admin],
+        )?;
+        validate_loot_table(&args)?;
+
+        let loot_table = &mut ctx.accounts.loot_table;
+        loot_table.apply(args);
+
+        emit!(LootTableUpdated {
+            table_id: loot_table.table_id,
+            chest_item_id: loot_table.chest_item_id,
+            weights: loot_table.entries.iter().map(|entry| entry.weight).collect(),
+            total_weight: loot_table.total_weight,
+            version: loot_table.version,
+            active: loot_table.active,
+        });
+
+        msg!("Loot table {} updated", loot_table.table_id);
+        Ok(())
+    }
+
+    /// Spend a chest and commit to `hash(secret)`. The roll is fixed by the secret and
+    /// the hash of a slot that doesn't exist yet, so neither the player nor the game
+    /// server can pick the outcome.
+    pub fn open_chest(
+        ctx: Context<OpenChest>,
+        commitment: [u8; 32],
+    ) -> Result<()> {
+        let loot_table = &ctx.accounts.loot_table;
+        require!(loot_table.active, PlayerRegistryError::LootTableInactive);
+
+        let inventory = &mut ctx.accounts.inventory;
+        let entry = inventory
+            .items
+            .iter_mut()
+            .find(|entry| entry.item_id == loot_table.chest_item_id)
+            .ok_or(PlayerRegistryError::InsufficientItems)?;
+        require!(entry.quantity > 0, PlayerRegistryError::InsufficientItems);
+        entry.quantity -= 1;
+
+        let clock = Clock::get()?;
+        let chest_opening = &mut ctx.accounts.chest_opening;
+        chest_opening.player = ctx.accounts.player.key();
+        chest_opening.loot_table = loot_table.key();
+        chest_opening.loot_table_version = loot_table.version;
+        chest_opening.commitment = commitment;
+        chest_opening.reveal_slot = clock.slot.saturating_add(CHEST_REVEAL_DELAY_SLOTS);
+        chest_opening.status = ChestStatus::Pending;
+        chest_opening.result = None;
+        chest_opening.bump = ctx.bumps.chest_opening;
+
+        msg!(
+            "Chest from table {} committed, reveal after slot {}",
+            loot_table.table_id,
+            chest_opening.reveal_slot
+        );
+        Ok(())
+    }
+
+    /// Reveal the secret and roll the loot table. Token and item rewards are credited
+    /// here; a skin is minted afterwards by mint_skin against this opening.
+    pub fn reveal_chest(
+        ctx: Context<RevealChest>,
+        secret: [u8; 32],
+    ) -> Result<()> {
+        let loot_table = &ctx.accounts.loot_table;
+        let chest_opening = &mut ctx.accounts.chest_opening;
+        require!(
+            chest_opening.status == ChestStatus::Pending,
+            PlayerRegistryError::InvalidChestOpening
+        );
+        require!(
+            hash(&secret).to_bytes() == chest_opening.commitment,
+            PlayerRegistryError::InvalidChestSecret
+        );
+        require!(
+            loot_table.version == chest_opening.loot_table_version,
+            PlayerRegistryError::LootTableChanged
+        );
+
+        let current_slot = Clock::get()?.slot;
+        require!(
+            current_slot > chest_opening.reveal_slot,
+            PlayerRegistryError::ChestNotReady
+        );
+        let slot_hash = find_slot_hash(
+            &ctx.accounts.slot_hashes.try_borrow_data()?,
+            chest_opening.reveal_slot,
+        )
+        .ok_or(PlayerRegistryError::ChestExpired)?;
+
+        let seed = hashv(&[&secret, &slot_hash, chest_opening.key().as_ref()]).to_bytes();
+        let roll = u64::from_le_bytes(seed[..8].try_into().unwrap()) % loot_table.total_weight as u64;
+        let entry_index = loot_table.entry_for(roll);
+        let reward = loot_table.entries[entry_index].reward.clone();
+
+        match &reward {
+            LootReward::Tokens { token_type, amount } => {
+                let player_profile = &mut ctx.accounts.player_profile;
+                match token_type {
+                    TokenType::Pirate => {
+                        player_profile.pirate_tokens = player_profile.pirate_tokens.saturating_add(*amount);
+                    },
+                    TokenType::Admiral => {
+                        player_profile.admiral_tokens = player_profile.admiral_tokens.saturating_add(*amount);
+                    },
+                }
+                chest_opening.status = ChestStatus::Fulfilled;
+            },
+            LootReward::Item { item_id, quantity } => {
+                let entry = ctx.accounts.inventory.entry_mut(*item_id)?;
+                entry.quantity = entry.quantity.saturating_add(*quantity);
+                chest_opening.status = ChestStatus::Fulfilled;
+            },
+            LootReward::Skin { .. } => {
+                chest_opening.status = ChestStatus::AwaitingSkin;
+            },
+        }
+        apply_achievement_rules(
+            &mut ctx.accounts.player_profile,
+            &ctx.accounts.achievement_rules,
+            &ctx.accounts.registry_config,
+        );
+        chest_opening.result = Some(reward.clone());
+
+        emit!(ChestOpened {
+            player: chest_opening.player,
+            table_id: loot_table.table_id,
+            roll,
+            total_weight: loot_table.total_weight,
+            entry_index: entry_index as u8,
+            reward,
+        });
+
+        msg!("Chest opened: roll {} of {}", roll, loot_table.total_weight);
+        Ok(())
+    }
+
+    /// Close a finished chest opening, or one whose reveal window has passed
+    /// (the chest is forfeited, otherwise withholding a bad roll would be a reroll)
+    pub fn close_chest_opening(
+        ctx: Context<CloseChestOpening>,
+    ) -> Result<()> {
+        let chest_opening = &ctx.accounts.chest_opening;
+        let expired = chest_opening.status == ChestStatus::Pending
+            && Clock::get()?.slot
+                > chest_opening.reveal_slot.saturating_add(SLOT_HASHES_WINDOW);
+        require!(
+            chest_opening.status == ChestStatus::Fulfilled || expired,
+            PlayerRegistryError::InvalidChestOpening
+        );
+
+        msg!("Chest opening closed{}", if expired { " (expired)" } else { "" });
+        Ok(())
+    }
+
+    /// Replace the rule-based achievements (Admin only). Each rule unlocks its
+    /// achievement once a profile field passes the threshold; the registry checks
+    /// them after every profile update. The registry starts with the chapter_master,
+    /// game_master, boss_slayer and token_collector rules, so keep them in the set
+    /// to keep those achievements.
+    pub fn set_achievement_rules(
+        ctx: Context<SetAchievementRules>,
+        rules: Vec<AchievementRule>,
+    ) -> Result<()> {
+        require_role(
+            &ctx.accounts.registry_config,
+            &ctx.accounts.authority,
+            &ctx.accounts.authority_roles,
+            &[Role::Admin],
+        )?;
+        require!(
+            rules.len() <= MAX_ACHIEVEMENT_RULES,
+            PlayerRegistryError::InvalidAchievementRule
+        );
+        for (index, rule) in rules.iter().enumerate() {
+            require!(
+                !rule.achievement_id.is_empty()
+                    && rule.achievement_id.len() <= 50
+                    && !rules[..index].iter().any(|other| other.achievement_id == rule.achievement_id),
+                PlayerRegistryError::InvalidAchievementRule
+            );
+        }
+
+        let achievement_rules = &mut ctx.accounts.achievement_rules;
+        achievement_rules.rules = rules;
+
+        emit!(AchievementRulesUpdated {
+            achievement_ids: achievement_rules
+                .rules
+                .iter()
+                .map(|rule| rule.achievement_id.clone())
+                .collect(),
+        });
+
+        msg!("Achievement rules updated: {} rules", achievement_rules.rules.len());
+        Ok(())
+    }
+
+    /// Evaluate the achievement rules against a profile. Permissionless, for
+    /// rules added after the player already met them.
+    pub fn evaluate_achievements(
+        ctx: Context<EvaluateAchievements>,
+    ) -> Result<()> {
+        let player_profile = &mut ctx.accounts.player_profile;
+        let unlocked = apply_achievement_rules(
+            player_profile,
+            &ctx.accounts.achievement_rules,
+            &ctx.accounts.registry_config,
+        );
+        if unlocked > 0 {
+            player_profile.updated_at = Clock::get()?.unix_timestamp;
+        }
+
+        msg!("{} achievements unlocked", unlocked);
+        Ok(())
+    }
+
+    /// Take items out of a player's inventory into a marketplace listing. Only the
+    /// marketplace program can call this, signing with its market authority PDA.
+    pub fn escrow_market_item(
+        ctx: Context<EscrowMarketItem>,
+        quantity: u32,
+    ) -> Result<()> {
+        require!(quantity > 0, PlayerRegistryError::InvalidQuantity);
+
+        let item_id = ctx.accounts.shop_item.item_id;
+        let inventory = &mut ctx.accounts.inventory;
+        let entry = inventory
+            .items
+            .iter_mut()
+            .find(|entry| entry.item_id == item_id)
+            .ok_or(PlayerRegistryError::InsufficientItems)?;
+        require!(entry.quantity >= quantity, PlayerRegistryError::InsufficientItems);
+        entry.quantity -= quantity;
+        let remaining = entry.quantity;
+
+        emit!(MarketItemMoved {
+            player: inventory.player,
+            item_id,
+            quantity,
+            remaining,
+            escrowed: true,
+        });
+
+        msg!("Escrowed {} x item {} for the marketplace", quantity, item_id);
+        Ok(())
+    }
+
+    /// Deliver items held by a marketplace listing to a player's inventory: the
+    /// buyer on a sale, the seller on a cancel. Marketplace program only.
+    pub fn release_market_item(
+        ctx: Context<ReleaseMarketItem>,
+        quantity: u32,
+    ) -> Result<()> {
+        require!(quantity > 0, PlayerRegistryError::InvalidQuantity);
+
+        let item_id = ctx.accounts.shop_item.item_id;
+        let inventory = &mut ctx.accounts.inventory;
+        if inventory.player == Pubkey::default() {
+            inventory.player = ctx.accounts.recipient.key();
+            inventory.bump = ctx.bumps.inventory;
+        }
+        let entry = inventory.entry_mut(item_id)?;
+        entry.quantity = entry
+            .quantity
+            .checked_add(quantity)
+            .ok_or(PlayerRegistryError::InvalidQuantity)?;
+        let remaining = entry.quantity;
+
+        emit!(MarketItemMoved {
+            player: inventory.player,
+            item_id,
+            quantity,
+            remaining,
+            escrowed: false,
+        });
+
+        msg!("Released {} x item {} from the marketplace", quantity, item_id);
+        Ok(())
+    }
+}
+
+// Helper functions
+fn calculate_level_reward(level: u8) -> u64 {
+    match level {
+        1..=10 => 10 + (level as u64 * 5),
+        11..=20 => 60 + ((level - 10) as u64 * 10),
+        21..=30 => 160 + ((level - 20) as u64 * 20),
+        31..=40 => 360 + ((level - 30) as u64 * 40),
+        _ => 1000,
+    }
+}
+
+fn calculate_daily_reward(streak_days: u32) -> u64 {
+    let base_reward = 25;
+    let streak_bonus = (streak_days as u64).saturating_mul(5);
+    base_reward.saturating_add(streak_bonus).min(100) // Cap at 100 tokens
+}
+
+// +1% per 100 $ADMIRAL staked, capped at +50%
+fn staking_boost_bps(staked_admiral: u64) -> u64 {
+    (staked_admiral / (100 * ADMIRAL_UNIT)).saturating_mul(100).min(5_000)
+}
+
+fn apply_staking_boost(reward: u64, staked_admiral: u64) -> u64 {
+    reward.saturating_mul(10_000 + staking_boost_bps(staked_admiral)) / 10_000
+}
+
+// Reads the player's staked $ADMIRAL from an admiral_token StakeAccount
+fn read_staked_admiral(
+    stake_account: Option<&UncheckedAccount>,
+    registry_config: &RegistryConfig,
+    player: &Pubkey,
+) -> Result<u64> {
+    let stake_account = match stake_account {
+        Some(stake_account) => stake_account,
+        None => return Ok(0),
+    };
+
+    require_keys_eq!(
+        *stake_account.owner,
+        ADMIRAL_TOKEN_PROGRAM_ID,
+        PlayerRegistryError::InvalidStakeAccount
+    );
+
+    let data = stake_account.try_borrow_data()?;
+    let discriminator = hash(b"account:StakeAccount").to_bytes();
+    require!(
+        data.len() >= 8 && data[..8] == discriminator[..8],
+        PlayerRegistryError::InvalidStakeAccount
+    );
+
+    let stake = StakeAccountPrefix::deserialize(&mut &data[8..])?;
+    require!(
+        stake.pool == registry_config.stake_pool && stake.owner == *player,
+        PlayerRegistryError::InvalidStakeAccount
+    );
+
+    Ok(stake.amount)
+}
+
+fn calculate_weekly_reward(levels_completed: u32, bosses_defeated: u32, achievements: u32) -> u64 {
+    let base_reward = 10;
+    let level_bonus = (levels_completed as u64).saturating_mul(2);
+    let boss_bonus = (bosses_defeated as u64).saturating_mul(5);
+    let achievement_bonus = (achievements as u64).saturating_mul(3);
+    
+    base_reward
+        .saturating_add(level_bonus)
+        .saturating_add(boss_bonus)
+        .saturating_add(achievement_bonus)
+        .min(100) // Cap at 100 tokens
+}
+
+fn token_metadata_space(name: &str, symbol: &str, uri: &str) -> usize {
+    // type + length header, update authority, mint, three strings and an empty field list
+    4 + 32 + 32 + (4 + name.len()) + (4 + symbol.len()) + (4 + uri.len()) + 4
+}
+
+// The registry authority holds every role; anyone else needs one of the allowed Roles grants
+fn require_role(
+    registry_config: &RegistryConfig,
+    signer: &Signer,
+    roles: &Option<Account<Roles>>,
+    allowed: &[Role],
+) -> Result<()> {
+    if signer.key() == registry_config.authority {
+        return Ok(());
+    }
+
+    match roles {
+        Some(roles) if allowed.iter().any(|role| roles.has(*role)) => Ok(()),
+        _ => err!(PlayerRegistryError::MissingRole),
+    }
+}
+
+fn validate_shop_item(args: &ShopItemArgs) -> Result<()> {
+    require!(
+        !args.name.is_empty() && args.name.len() <= 32 && args.price > 0,
+        PlayerRegistryError::InvalidShopItem
+    );
+    require!(
+        args.ends_at == 0 || args.ends_at > args.starts_at,
+        PlayerRegistryError::InvalidShopItem
+    );
+    Ok(())
+}
+
+// CPI into the currency's token program burn_tokens with the Shop reason, signed by
+// this program's burn authority so the burn is attributed to the registry
+fn burn_for_purchase(accounts: &PurchaseItem, amount: u64) -> Result<()> {
+    let (token_config, burn_caller, burn_authority, currency_program) = match (
+        &accounts.currency_token_config,
+        &accounts.burn_caller,
+        &accounts.burn_authority,
+        &accounts.currency_program,
+    ) {
+        (Some(token_config), Some(burn_caller), Some(burn_authority), Some(currency_program)) => {
+            (token_config, burn_caller, burn_authority, currency_program)
+        }
+        _ => return err!(PlayerRegistryError::MissingPaymentAccounts),
+    };
+
+    let (expected_authority, burn_authority_bump) =
+        Pubkey::find_program_address(&[b"burn_authority"], &crate::ID);
+    require_keys_eq!(
+        burn_authority.key(),
+        expected_authority,
+        PlayerRegistryError::MissingPaymentAccounts
+    );
+
+    let program_id = match accounts.shop_item.currency {
+        TokenType::Pirate => PIRATE_TOKEN_PROGRAM_ID,
+        TokenType::Admiral => ADMIRAL_TOKEN_PROGRAM_ID,
+    };
+    require_keys_eq!(
+        currency_program.key(),
+        program_id,
+        PlayerRegistryError::MissingPaymentAccounts
+    );
+
+    let mut data = hash(b"global:burn_tokens").to_bytes()[..8].to_vec();
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.push(BURN_REASON_SHOP);
+
+    let instruction = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(token_config.key(), false),
+            AccountMeta::new(accounts.mint.key(), false),
+            AccountMeta::new(accounts.player_token_account.key(), false),
+            AccountMeta::new_readonly(accounts.player.key(), true),
+            AccountMeta::new(burn_caller.key(), false),
+            AccountMeta::new_readonly(burn_authority.key(), true),
+            AccountMeta::new_readonly(accounts.token_program.key(), false),
+            // pirate_token's optional conversion_config, passed as absent; admiral_token ignores it
+            AccountMeta::new_readonly(program_id, false),
+        ],
+        data,
+    };
+
+    invoke_signed(
+        &instruction,
+        &[
+            token_config.to_account_info(),
+            accounts.mint.to_account_info(),
+            accounts.player_token_account.to_account_info(),
+            accounts.player.to_account_info(),
+            burn_caller.to_account_info(),
+            burn_authority.to_account_info(),
+            accounts.token_program.to_account_info(),
+            currency_program.to_account_info(),
+        ],
+        &[&[b"burn_authority", &[burn_authority_bump]]],
+    )?;
+    Ok(())
+}
+
+// Mints the badge for a recorded achievement and marks it issued on the profile;
+// each badge can be issued once, even if the player later burns it
+fn mint_badge<'info>(
+    player_profile: &mut PlayerProfile,
+    achievement_id: &str,
+    badge_mint: &InterfaceAccount<'info, Mint>,
+    player_badge_account: &InterfaceAccount<'info, TokenAccount>,
+    badge_authority: &UncheckedAccount<'info>,
+    badge_authority_bump: u8,
+    token_program: &Program<'info, Token2022>,
+) -> Result<()> {
+    let badge_bit = player_profile
+        .badge_bit(achievement_id)
+        .ok_or(PlayerRegistryError::AchievementNotRecorded)?;
+    require!(
+        player_profile.badges_issued & badge_bit == 0,
+        PlayerRegistryError::BadgeAlreadyIssued
+    );
+    player_profile.badges_issued |= badge_bit;
+
+    let authority_seeds: &[&[u8]] = &[b"badge_authority", &[badge_authority_bump]];
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            token_interface::MintTo {
+                mint: badge_mint.to_account_info(),
+                to: player_badge_account.to_account_info(),
+                authority: badge_authority.to_account_info(),
+            },
+            &[authority_seeds],
+        ),
+        1,
+    )
+}
+
+// Transfers out of a match vault, signed by the match PDA
+fn pay_from_match_vault<'info>(
+    wager_match: &Account<'info, Match>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<()> {
+    let match_id = wager_match.match_id.to_le_bytes();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"match",
+        wager_match.challenger.as_ref(),
+        match_id.as_ref(),
+        &[wager_match.bump],
+    ]];
+
+    let cpi_accounts = TransferChecked {
+        from: vault.to_account_info(),
+        mint: mint.to_account_info(),
+        to: to.to_account_info(),
+        authority: wager_match.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)
+}
+
+// Closes an emptied match vault, returning its rent to the challenger who paid it
+fn close_match_vault<'info>(
+    wager_match: &Account<'info, Match>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    challenger: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    let match_id = wager_match.match_id.to_le_bytes();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"match",
+        wager_match.challenger.as_ref(),
+        match_id.as_ref(),
+        &[wager_match.bump],
+    ]];
+
+    token_interface::close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: vault.to_account_info(),
+            destination: challenger.clone(),
+            authority: wager_match.to_account_info(),
+        },
+        signer_seeds,
+    ))
+}
+
+fn validate_quest(args: &QuestArgs) -> Result<()> {
+    require!(
+        !args.name.is_empty()
+            && args.name.len() <= 32
+            && args.target > 0
+            && args.rotation_length > 0
+            && args.rotation_slot < args.rotation_length,
+        PlayerRegistryError::InvalidQuest
+    );
+    Ok(())
+}
+
+// Re-derives the profile's VIP tier from staked $ADMIRAL and returns the tier's
+// reward multiplier, or None without a VipConfig. Only the stake counts: it sits
+// behind the unstake cooldown, so it can't be shuffled between wallets.
+fn update_vip_tier(
+    player_profile: &mut PlayerProfile,
+    vip_config: Option<&Account<VipConfig>>,
+    staked_admiral: u64,
+) -> Option<u16> {
+    let vip_config = vip_config?;
+    let tier = vip_config.tier_for(staked_admiral);
+
+    if tier != player_profile.vip_tier {
+        emit!(VipTierChanged {
+            player: player_profile.player,
+            previous_tier: player_profile.vip_tier,
+            new_tier: tier,
+            staked_admiral,
+        });
+        player_profile.vip_tier = tier;
+    }
+
+    Some(vip_config.multiplier_bps(tier))
+}
+
+// The multiplier update_vip_tier would apply for this stake, without touching the profile
+fn vip_multiplier_for(vip_config: Option<&Account<VipConfig>>, staked_admiral: u64) -> Option<u16> {
+    vip_config.map(|vip_config| vip_config.multiplier_bps(vip_config.tier_for(staked_admiral)))
+}
+
+fn apply_vip_multiplier(reward: u64, multiplier_bps: u16) -> u64 {
+    (reward as u128 * multiplier_bps as u128 / 10_000) as u64
+}
+
+// VIP tiers are earned by staking, so when they're configured they take the place
+// of the staking boost instead of paying for the same stake twice
+fn boosted_reward(base_reward: u64, staked_admiral: u64, vip_multiplier_bps: Option<u16>) -> u64 {
+    match vip_multiplier_bps {
+        Some(multiplier_bps) => apply_vip_multiplier(base_reward, multiplier_bps),
+        None => apply_staking_boost(base_reward, staked_admiral),
+    }
+}
+
+fn quoted_boost_bps(staked_admiral: u64, vip_multiplier_bps: Option<u16>) -> u16 {
+    match vip_multiplier_bps {
+        Some(_) => 0,
+        None => staking_boost_bps(staked_admiral) as u16,
+    }
+}
+
+fn validate_battle_pass(args: &BattlePassArgs) -> Result<()> {
+    require!(
+        !args.name.is_empty()
+            && args.name.len() <= 32
+            && !args.tiers.is_empty()
+            && args.tiers.len() <= MAX_PASS_TIERS
+            && args.xp_per_tier > 0
+            && args.premium_price > 0
+            && args.ends_at > args.starts_at,
+        PlayerRegistryError::InvalidBattlePass
+    );
+    Ok(())
+}
+
+// Adds battle pass XP when the player's progress for a live pass was passed in
+fn award_pass_xp(
+    battle_pass: Option<&Account<BattlePass>>,
+    pass_progress: Option<&mut Account<PassProgress>>,
+    xp_for: impl Fn(&BattlePass) -> u32,
+    now: i64,
+) -> Result<()> {
+    let (battle_pass, pass_progress) = match (battle_pass, pass_progress) {
+        (Some(battle_pass), Some(pass_progress)) => (battle_pass, pass_progress),
+        _ => return Ok(()),
+    };
+    require_keys_eq!(
+        pass_progress.battle_pass,
+        battle_pass.key(),
+        PlayerRegistryError::InvalidBattlePass
+    );
+    if !battle_pass.is_live(now) {
+        return Ok(());
+    }
+
+    let xp = xp_for(battle_pass);
+    pass_progress.xp = pass_progress.xp.saturating_add(xp as u64);
+
+    emit!(PassXpEarned {
+        player: pass_progress.player,
+        season: battle_pass.season,
+        xp,
+        total_xp: pass_progress.xp,
+    });
+    Ok(())
+}
+
+fn validate_loot_table(args: &LootTableArgs) -> Result<()> {
+    require!(
+        !args.name.is_empty()
+            && args.name.len() <= 32
+            && !args.entries.is_empty()
+            && args.entries.len() <= MAX_LOOT_ENTRIES
+            && args.entries.iter().all(|entry| entry.weight > 0)
+            && args.entries.iter().map(|entry| entry.weight as u64).sum::<u64>() <= u32::MAX as u64,
+        PlayerRegistryError::InvalidLootTable
+    );
+    Ok(())
+}
+
+// Looks up the hash of the first slot at or after `slot` in the SlotHashes sysvar
+// data, without deserializing all of it. Skipped slots never get an entry, so the
+// reveal slot itself may be missing. Returns None if the window has moved past it.
+// Layout: u64 entry count, then (u64 slot, [u8; 32] hash) entries, newest first.
+fn find_slot_hash(data: &[u8], slot: u64) -> Option<[u8; 32]> {
+    let count = u64::from_le_bytes(data.get(..8)?.try_into().unwrap()) as usize;
+    let entries = data.get(8..8 + count.checked_mul(40)?)?;
+    let entry_slot = |index: usize| {
+        u64::from_le_bytes(entries[index * 40..index * 40 + 8].try_into().unwrap())
+    };
+
+    // Number of leading (newer) entries at or after the slot
+    let (mut low, mut high) = (0usize, count);
+    while low < high {
+        let mid = (low + high) / 2;
+        if entry_slot(mid) >= slot {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    if low == 0 {
+        return None;
+    }
+
+    // If every entry is newer, older slots were evicted and one of them may have
+    // been the real successor, so only an exact match is trustworthy
+    let index = low - 1;
+    if low == count && entry_slot(index) != slot {
+        return None;
+    }
+    Some(entries[index * 40 + 8..(index + 1) * 40].try_into().unwrap())
+}
+
+// Records an achievement unlocked by an achievement rule and credits its reward.
+// Badges for these are issued through backfill_badge. Applies the same limits as
+// add_achievement; a full profile simply stops unlocking instead of failing the
+// instruction that triggered the check.
+fn grant_achievement(player_profile: &mut PlayerProfile, achievement_id: &str, reward: u64) -> bool {
+    if achievement_id.len() > 50
+        || player_profile.achievements.len() >= MAX_ACHIEVEMENTS
+        || player_profile.achievements.iter().any(|id| id == achievement_id)
+    {
+        return false;
+    }
+
+    player_profile.achievements.push(achievement_id.to_string());
+    player_profile.achievement_count = player_profile.achievement_count.saturating_add(1);
+    player_profile.pirate_tokens = player_profile.pirate_tokens.saturating_add(reward);
+
+    emit!(AchievementUnlocked {
+        player: player_profile.player,
+        achievement_id: achievement_id.to_string(),
+        reward,
+    });
+    msg!("Achievement unlocked: {}! Reward: {} $PIRATE", achievement_id, reward);
+    true
+}
+
+// Unlocks and pays every rule achievement the profile now meets. Rewards can
+// satisfy further rules (e.g. a $PIRATE balance threshold), so repeat until stable.
+fn apply_achievement_rules(
+    player_profile: &mut PlayerProfile,
+    achievement_rules: &AchievementRules,
+    registry_config: &RegistryConfig,
+) -> u32 {
+    let mut unlocked = 0;
+    loop {
+        let mut unlocked_this_pass = false;
+        for rule in achievement_rules.rules.iter() {
+            if rule.is_met(player_profile, registry_config)
+                && grant_achievement(player_profile, &rule.achievement_id, rule.reward)
+            {
+                unlocked += 1;
+                unlocked_this_pass = true;
+            }
+        }
+        if !unlocked_this_pass {
+            return unlocked;
+        }
+    }
+}
+
+// Account structures
+#[derive(Accounts)]
+pub struct InitializeRegistry<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RegistryConfig::INIT_SPACE,
+        seeds = [b"registry_config"],
+        bump
+    )]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AchievementRules::INIT_SPACE,
+        seeds = [b"achievement_rules"],
+        bump
+    )]
+    pub achievement_rules: Account<'info, AchievementRules>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializePlayer<'info> {
+    #[account(
+        init,
+        payer = player,
+        space = 8 + PlayerProfile::INIT_SPACE,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+    
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePlayerLevel<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump,
+        has_one = player
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+    /// CHECK: Profile owner, only used to derive the profile PDA
+    pub player: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), game_server.key().as_ref()],
+        bump = game_server_roles.bump
+    )]
+    pub game_server_roles: Option<Account<'info, Roles>>,
+    pub game_server: Signer<'info>,
+
+    // Achievement rules evaluated after the instruction
+    #[account(seeds = [b"achievement_rules"], bump = achievement_rules.bump)]
+    pub achievement_rules: Account<'info, AchievementRules>,
+
+    /// CHECK: Optional admiral_token StakeAccount, validated in read_staked_admiral
+    pub stake_account: Option<UncheckedAccount<'info>>,
+
+    #[account(seeds = [b"vip_config"], bump = vip_config.bump)]
+    pub vip_config: Option<Account<'info, VipConfig>>,
+
+    // Only passed when the player has joined the live battle pass
+    #[account(
+        seeds = [b"battle_pass", battle_pass.season.to_le_bytes().as_ref()],
+        bump = battle_pass.bump
+    )]
+    pub battle_pass: Option<Account<'info, BattlePass>>,
+    #[account(
+        mut,
+        seeds = [b"pass_progress", pass_progress.battle_pass.as_ref(), player.key().as_ref()],
+        bump = pass_progress.bump
+    )]
+    pub pass_progress: Option<Account<'info, PassProgress>>,
+}
+
+#[derive(Accounts)]
+#[instruction(achievement_id: String)]
+pub struct AddAchievement<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump,
+        has_one = player
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+    /// CHECK: Profile owner, only used to derive the profile PDA
+    pub player: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), granter.key().as_ref()],
+        bump = granter_roles.bump
+    )]
+    pub granter_roles: Option<Account<'info, Roles>>,
+    #[account(mut)]
+    pub granter: Signer<'info>,
+
+    // Achievement rules evaluated after the instruction
+    #[account(seeds = [b"achievement_rules"], bump = achievement_rules.bump)]
+    pub achievement_rules: Account<'info, AchievementRules>,
+
+    // Only passed when the achievement has a badge mint
+    #[account(
+        mut,
+        seeds = [b"badge_mint", achievement_id.as_bytes()],
+        bump,
+        mint::token_program = token_program
+    )]
+    pub badge_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(
+        init_if_needed,
+        payer = granter,
+        associated_token::mint = badge_mint,
+        associated_token::authority = player,
+        associated_token::token_program = token_program
+    )]
+    pub player_badge_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: PDA that holds mint authority over every badge
+    #[account(seeds = [b"badge_authority"], bump)]
+    pub badge_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RecordBossDefeat<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump,
+        has_one = player
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+    /// CHECK: Profile owner, only used to derive the profile PDA
+    pub player: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), game_server.key().as_ref()],
+        bump = game_server_roles.bump
+    )]
+    pub game_server_roles: Option<Account<'info, Roles>>,
+    pub game_server: Signer<'info>,
+
+    // Achievement rules evaluated after the instruction
+    #[account(seeds = [b"achievement_rules"], bump = achievement_rules.bump)]
+    pub achievement_rules: Account<'info, AchievementRules>,
+
+    /// CHECK: Optional admiral_token StakeAccount, validated in read_staked_admiral
+    pub stake_account: Option<UncheckedAccount<'info>>,
+
+    #[account(seeds = [b"vip_config"], bump = vip_config.bump)]
+    pub vip_config: Option<Account<'info, VipConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimDailyReward<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump,
+        has_one = player
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+    pub player: Signer<'info>,
+
+    // Achievement rules evaluated after the instruction
+    #[account(seeds = [b"achievement_rules"], bump = achievement_rules.bump)]
+    pub achievement_rules: Account<'info, AchievementRules>,
+
+    /// CHECK: Optional admiral_token StakeAccount, validated in read_staked_admiral
+    pub stake_account: Option<UncheckedAccount<'info>>,
+
+    #[account(seeds = [b"vip_config"], bump = vip_config.bump)]
+    pub vip_config: Option<Account<'info, VipConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimWeeklyReward<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump,
+        has_one = player
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+    pub player: Signer<'info>,
+
+    // Achievement rules evaluated after the instruction
+    #[account(seeds = [b"achievement_rules"], bump = achievement_rules.bump)]
+    pub achievement_rules: Account<'info, AchievementRules>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateLoginTime<'info> {
+    #[account(
+        mut,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump,
+        has_one = player
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, token_type: TokenType)]
+pub struct TransferTokens<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump,
+        has_one = player
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+    
+    #[account(mut)]
+    pub player: Signer<'info>,
+    
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = player,
+        token::token_program = token_program
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // The balance debited from the profile is chosen by token_type, so the mint must match it
+    #[account(
+        address = registry_config.mint_for(&token_type) @ PlayerRegistryError::InvalidTokenMint,
+        mint::token_program = token_program
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), treasury_authority.key().as_ref()],
+        bump = treasury_authority_roles.bump
+    )]
+    pub treasury_authority_roles: Option<Account<'info, Roles>>,
+    pub treasury_authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct DeactivatePlayer<'info> {
+    #[account(
+        mut,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump,
+        has_one = player
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(achievement_id: String)]
+pub struct InitializeBadgeMint<'info> {
+    /// CHECK: Created and initialized in the handler so extensions can be added first
+    #[account(
+        mut,
+        seeds = [b"badge_mint", achievement_id.as_bytes()],
+        bump
+    )]
+    pub badge_mint: UncheckedAccount<'info>,
+
+    /// CHECK: PDA that holds mint and metadata authority over every badge
+    #[account(seeds = [b"badge_authority"], bump)]
+    pub badge_authority: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        mut,
+        seeds = [b"registry_config"],
+        bump = registry_config.bump
+    )]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), pauser.key().as_ref()],
+        bump = pauser_roles.bump
+    )]
+    pub pauser_roles: Option<Account<'info, Roles>>,
+    pub pauser: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"registry_config"],
+        bump = registry_config.bump,
+        has_one = authority @ PlayerRegistryError::Unauthorized
+    )]
+    pub registry_config: Account<'info, RegistryConfig>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"registry_config"],
+        bump = registry_config.bump
+    )]
+    pub registry_config: Account<'info, RegistryConfig>,
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetStakePool<'info> {
+    #[account(
+        mut,
+        seeds = [b"registry_config"],
+        bump = registry_config.bump
+    )]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetCurrencyMints<'info> {
+    #[account(
+        mut,
+        seeds = [b"registry_config"],
+        bump = registry_config.bump
+    )]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(member: Pubkey)]
+pub struct GrantRole<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + Roles::INIT_SPACE,
+        seeds = [b"roles", registry_config.key().as_ref(), member.as_ref()],
+        bump
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeRole<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"roles", registry_config.key().as_ref(), roles.member.as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct QuoteLevelReward<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(seeds = [b"player_profile", player_profile.player.as_ref()], bump)]
+    pub player_profile: Option<Account<'info, PlayerProfile>>,
+
+    /// CHECK: Optional admiral_token StakeAccount, validated in read_staked_admiral
+    pub stake_account: Option<UncheckedAccount<'info>>,
+
+    // Applies the VIP multiplier for the current stake to the quote
+    #[account(seeds = [b"vip_config"], bump = vip_config.bump)]
+    pub vip_config: Option<Account<'info, VipConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct QuoteDailyReward<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(seeds = [b"player_profile", player_profile.player.as_ref()], bump)]
+    pub player_profile: Account<'info, PlayerProfile>,
+
+    /// CHECK: Optional admiral_token StakeAccount, validated in read_staked_admiral
+    pub stake_account: Option<UncheckedAccount<'info>>,
+
+    // Applies the VIP multiplier for the current stake to the quote
+    #[account(seeds = [b"vip_config"], bump = vip_config.bump)]
+    pub vip_config: Option<Account<'info, VipConfig>>,
+}
+
+#[derive(Accounts)]
+pub struct QuoteWeeklyReward<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(seeds = [b"player_profile", player_profile.player.as_ref()], bump)]
+    pub player_profile: Account<'info, PlayerProfile>,
+}
+
+#[derive(Accounts)]
+#[instruction(item_id: u16)]
+pub struct CreateShopItem<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ShopItem::INIT_SPACE,
+        seeds = [b"shop_item", item_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub shop_item: Account<'info, ShopItem>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateShopItem<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"shop_item", shop_item.item_id.to_le_bytes().as_ref()],
+        bump = shop_item.bump
+    )]
+    pub shop_item: Account<'info, ShopItem>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PurchaseItem<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"shop_item", shop_item.item_id.to_le_bytes().as_ref()],
+        bump = shop_item.bump
+    )]
+    pub shop_item: Account<'info, ShopItem>,
+
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = 8 + Inventory::INIT_SPACE,
+        seeds = [b"inventory", player.key().as_ref()],
+        bump
+    )]
+    pub inventory: Account<'info, Inventory>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(
+        mut,
+        address = shop_item.mint @ PlayerRegistryError::InvalidShopItem,
+        mint::token_program = token_program
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = player,
+        token::token_program = token_program
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // Treasury payments only
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = registry_config.treasury_authority,
+        token::token_program = token_program
+    )]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Burn payments only
+    /// CHECK: The currency program's TokenConfig, validated by it during the burn
+    #[account(mut)]
+    pub currency_token_config: Option<UncheckedAccount<'info>>,
+    /// CHECK: The currency program's BurnCaller for the registry, validated by it during the burn
+    #[account(mut)]
+    pub burn_caller: Option<UncheckedAccount<'info>>,
+    /// CHECK: The registry's burn authority PDA, checked and signed for in burn_for_purchase
+    pub burn_authority: Option<UncheckedAccount<'info>>,
+    /// CHECK: pirate_token or admiral_token, checked against the item currency in burn_for_purchase
+    pub currency_program: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConsumeItem<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        seeds = [b"shop_item", shop_item.item_id.to_le_bytes().as_ref()],
+        bump = shop_item.bump
+    )]
+    pub shop_item: Account<'info, ShopItem>,
+
+    #[account(
+        mut,
+        seeds = [b"inventory", inventory.player.as_ref()],
+        bump = inventory.bump
+    )]
+    pub inventory: Account<'info, Inventory>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), game_server.key().as_ref()],
+        bump = game_server_roles.bump
+    )]
+    pub game_server_roles: Option<Account<'info, Roles>>,
+    pub game_server: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(achievement_id: String)]
+pub struct BackfillBadge<'info> {
+    #[account(
+        mut,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump,
+        has_one = player
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+    /// CHECK: Profile owner, only used to derive the profile PDA and badge account
+    pub player: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"badge_mint", achievement_id.as_bytes()],
+        bump,
+        mint::token_program = token_program
+    )]
+    pub badge_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = badge_mint,
+        associated_token::authority = player,
+        associated_token::token_program = token_program
+    )]
+    pub player_badge_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: PDA that holds mint authority over every badge
+    #[account(seeds = [b"badge_authority"], bump)]
+    pub badge_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeSkinCollection<'info> {
+    /// CHECK: Created and initialized in the handler so extensions can be added first
+    #[account(mut, seeds = [b"skin_collection"], bump)]
+    pub collection_mint: UncheckedAccount<'info>,
+
+    /// CHECK: PDA that holds mint, metadata and group authority over skins
+    #[account(seeds = [b"skin_authority"], bump)]
+    pub skin_authority: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct MintSkin<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    /// CHECK: The skin collection mint, validated by its seeds and by Token-2022
+    #[account(mut, seeds = [b"skin_collection"], bump)]
+    pub collection_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Fresh keypair, created and initialized in the handler
+    #[account(mut)]
+    pub skin_mint: Signer<'info>,
+
+    #[account(
+        init,
+        payer = minter,
+        space = 8 + Skin::INIT_SPACE,
+        seeds = [b"skin", skin_mint.key().as_ref()],
+        bump
+    )]
+    pub skin: Account<'info, Skin>,
+
+    /// CHECK: Any wallet can receive a skin
+    pub recipient: UncheckedAccount<'info>,
+
+    /// CHECK: Associated token account, created in the handler once the mint exists
+    #[account(mut)]
+    pub recipient_skin_account: UncheckedAccount<'info>,
+
+    /// CHECK: PDA that holds mint, metadata and group authority over skins
+    #[account(seeds = [b"skin_authority"], bump)]
+    pub skin_authority: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), minter.key().as_ref()],
+        bump = minter_roles.bump
+    )]
+    pub minter_roles: Option<Account<'info, Roles>>,
+
+    // Passed when the skin fulfils a chest roll
+    #[account(
+        mut,
+        seeds = [b"chest_opening", chest_opening.loot_table.as_ref(), recipient.key().as_ref()],
+        bump = chest_opening.bump
+    )]
+    pub chest_opening: Option<Account<'info, ChestOpening>>,
+
+    #[account(mut)]
+    pub minter: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct EquipSkin<'info> {
+    #[account(
+        mut,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump,
+        has_one = player
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+    pub player: Signer<'info>,
+
+    #[account(seeds = [b"skin", skin.mint.as_ref()], bump = skin.bump)]
+    pub skin: Account<'info, Skin>,
+
+    #[account(address = skin.mint, mint::token_program = token_program)]
+    pub skin_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        associated_token::mint = skin_mint,
+        associated_token::authority = player,
+        associated_token::token_program = token_program
+    )]
+    pub player_skin_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct UnequipSkin<'info> {
+    #[account(
+        mut,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump,
+        has_one = player
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshEquippedSkin<'info> {
+    #[account(
+        mut,
+        seeds = [b"player_profile", player_profile.player.as_ref()],
+        bump
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
+    #[account(seeds = [b"skin", skin.mint.as_ref()], bump = skin.bump)]
+    pub skin: Account<'info, Skin>,
+
+    /// CHECK: The player's associated token account for the skin; may be closed
+    #[account(
+        address = get_associated_token_address_with_program_id(
+            &player_profile.player,
+            &skin.mint,
+            &Token2022::id(),
+        ) @ PlayerRegistryError::SkinNotOwned
+    )]
+    pub player_skin_account: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(tournament_id: u64)]
+pub struct CreateTournament<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Tournament::INIT_SPACE,
+        seeds = [b"tournament", tournament_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"tournament_vault", tournament.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = tournament,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EnterTournament<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"tournament", tournament.tournament_id.to_le_bytes().as_ref()],
+        bump = tournament.bump,
+        has_one = mint,
+        constraint = tournament.mint == registry_config.mint_for(&tournament.currency)
+            @ PlayerRegistryError::InvalidTournament
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    #[account(
+        init,
+        payer = player,
+        space = 8 + TournamentEntry::INIT_SPACE,
+        seeds = [b"tournament_entry", tournament.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub entry: Account<'info, TournamentEntry>,
+
+    #[account(
+        mut,
+        seeds = [b"tournament_vault", tournament.key().as_ref()],
+        bump = tournament.vault_bump
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = player,
+        token::token_program = token_program
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitRankings<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"tournament", tournament.tournament_id.to_le_bytes().as_ref()],
+        bump = tournament.bump
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    #[account(
+        seeds = [b"tournament_vault", tournament.key().as_ref()],
+        bump = tournament.vault_bump
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), game_server.key().as_ref()],
+        bump = game_server_roles.bump
+    )]
+    pub game_server_roles: Option<Account<'info, Roles>>,
+    pub game_server: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimTournamentPayout<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"tournament", tournament.tournament_id.to_le_bytes().as_ref()],
+        bump = tournament.bump,
+        has_one = mint
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    #[account(
+        mut,
+        seeds = [b"tournament_vault", tournament.key().as_ref()],
+        bump = tournament.vault_bump
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub winner_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CancelTournament<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"tournament", tournament.tournament_id.to_le_bytes().as_ref()],
+        bump = tournament.bump
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefundTournamentEntry<'info> {
+    #[account(
+        seeds = [b"tournament", tournament.tournament_id.to_le_bytes().as_ref()],
+        bump = tournament.bump,
+        has_one = mint
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    #[account(
+        mut,
+        close = player,
+        seeds = [b"tournament_entry", tournament.key().as_ref(), entry.player.as_ref()],
+        bump = entry.bump
+    )]
+    pub entry: Account<'info, TournamentEntry>,
+
+    #[account(
+        mut,
+        seeds = [b"tournament_vault", tournament.key().as_ref()],
+        bump = tournament.vault_bump
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = entry.player,
+        token::token_program = token_program
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: The entrant, receives the entry account rent
+    #[account(mut, address = entry.player)]
+    pub player: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetChapterLayout<'info> {
+    #[account(
+        mut,
+        seeds = [b"registry_config"],
+        bump = registry_config.bump
+    )]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetWagerFee<'info> {
+    #[account(
+        mut,
+        seeds = [b"registry_config"],
+        bump = registry_config.bump
+    )]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(match_id: u64)]
+pub struct CreateMatch<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        init,
+        payer = challenger,
+        space = 8 + Match::INIT_SPACE,
+        seeds = [b"match", challenger.key().as_ref(), match_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub wager_match: Account<'info, Match>,
+
+    #[account(
+        init,
+        payer = challenger,
+        seeds = [b"match_vault", wager_match.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = wager_match,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    // Wagers are staked in $PIRATE only; settle pays out exact stakes, which a
+    // fee-bearing mint would leave the vault short of
+    #[account(
+        address = registry_config.pirate_mint @ PlayerRegistryError::InvalidWager,
+        mint::token_program = token_program
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = challenger,
+        token::token_program = token_program
+    )]
+    pub challenger_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelMatch<'info> {
+    #[account(
+        mut,
+        close = challenger,
+        seeds = [b"match", wager_match.challenger.as_ref(), wager_match.match_id.to_le_bytes().as_ref()],
+        bump = wager_match.bump,
+        has_one = challenger,
+        has_one = mint
+    )]
+    pub wager_match: Account<'info, Match>,
+
+    #[account(
+        mut,
+        seeds = [b"match_vault", wager_match.key().as_ref()],
+        bump = wager_match.vault_bump
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = challenger,
+        token::token_program = token_program
+    )]
+    pub challenger_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptMatch<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"match", wager_match.challenger.as_ref(), wager_match.match_id.to_le_bytes().as_ref()],
+        bump = wager_match.bump,
+        has_one = mint
+    )]
+    pub wager_match: Account<'info, Match>,
+
+    #[account(
+        mut,
+        seeds = [b"match_vault", wager_match.key().as_ref()],
+        bump = wager_match.vault_bump
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = opponent,
+        token::token_program = token_program
+    )]
+    pub opponent_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub opponent: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SettleMatch<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        mut,
+        close = challenger,
+        seeds = [b"match", wager_match.challenger.as_ref(), wager_match.match_id.to_le_bytes().as_ref()],
+        bump = wager_match.bump,
+        has_one = mint
+    )]
+    pub wager_match: Account<'info, Match>,
+
+    #[account(
+        mut,
+        seeds = [b"match_vault", wager_match.key().as_ref()],
+        bump = wager_match.vault_bump
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = wager_match.challenger,
+        token::token_program = token_program
+    )]
+    pub challenger_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = wager_match.opponent,
+        token::token_program = token_program
+    )]
+    pub opponent_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = registry_config.treasury_authority,
+        token::token_program = token_program
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: The challenger, receives the match and vault rent
+    #[account(mut, address = wager_match.challenger)]
+    pub challenger: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), game_server.key().as_ref()],
+        bump = game_server_roles.bump
+    )]
+    pub game_server_roles: Option<Account<'info, Roles>>,
+    pub game_server: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RefundTimedOutMatch<'info> {
+    #[account(
+        mut,
+        close = challenger,
+        seeds = [b"match", wager_match.challenger.as_ref(), wager_match.match_id.to_le_bytes().as_ref()],
+        bump = wager_match.bump,
+        has_one = mint
+    )]
+    pub wager_match: Account<'info, Match>,
+
+    #[account(
+        mut,
+        seeds = [b"match_vault", wager_match.key().as_ref()],
+        bump = wager_match.vault_bump
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = wager_match.challenger,
+        token::token_program = token_program
+    )]
+    pub challenger_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = wager_match.opponent,
+        token::token_program = token_program
+    )]
+    pub opponent_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: The challenger, receives the match and vault rent
+    #[account(mut, address = wager_match.challenger)]
+    pub challenger: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(quest_id: u16)]
+pub struct CreateQuest<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + QuestDefinition::INIT_SPACE,
+        seeds = [b"quest", quest_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub quest: Account<'info, QuestDefinition>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateQuest<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"quest", quest.quest_id.to_le_bytes().as_ref()],
+        bump = quest.bump
+    )]
+    pub quest: Account<'info, QuestDefinition>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RecordQuestProgress<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        seeds = [b"quest", quest.quest_id.to_le_bytes().as_ref()],
+        bump = quest.bump
+    )]
+    pub quest: Account<'info, QuestDefinition>,
+
+    #[account(
+        init_if_needed,
+        payer = game_server,
+        space = 8 + QuestProgress::INIT_SPACE,
+        seeds = [b"quest_progress", quest.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub quest_progress: Account<'info, QuestProgress>,
+
+    #[account(
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump,
+        has_one = player
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+    /// CHECK: Profile owner, only used to derive the profile and progress PDAs
+    pub player: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), game_server.key().as_ref()],
+        bump = game_server_roles.bump
+    )]
+    pub game_server_roles: Option<Account<'info, Roles>>,
+    #[account(mut)]
+    pub game_server: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimQuest<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        seeds = [b"quest", quest.quest_id.to_le_bytes().as_ref()],
+        bump = quest.bump
+    )]
+    pub quest: Account<'info, QuestDefinition>,
+
+    #[account(
+        mut,
+        seeds = [b"quest_progress", quest.key().as_ref(), player.key().as_ref()],
+        bump = quest_progress.bump
+    )]
+    pub quest_progress: Account<'info, QuestProgress>,
+
+    #[account(
+        mut,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump,
+        has_one = player
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+    pub player: Signer<'info>,
+
+    // Achievement rules evaluated after the instruction
+    #[account(seeds = [b"achievement_rules"], bump = achievement_rules.bump)]
+    pub achievement_rules: Account<'info, AchievementRules>,
+
+    // Only passed when the player has joined the live battle pass
+    #[account(
+        seeds = [b"battle_pass", battle_pass.season.to_le_bytes().as_ref()],
+        bump = battle_pass.bump
+    )]
+    pub battle_pass: Option<Account<'info, BattlePass>>,
+    #[account(
+        mut,
+        seeds = [b"pass_progress", pass_progress.battle_pass.as_ref(), player.key().as_ref()],
+        bump = pass_progress.bump
+    )]
+    pub pass_progress: Option<Account<'info, PassProgress>>,
+}
+
+#[derive(Accounts)]
+pub struct SetVipTiers<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + VipConfig::INIT_SPACE,
+        seeds = [b"vip_config"],
+        bump
+    )]
+    pub vip_config: Account<'info, VipConfig>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshVipTier<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(seeds = [b"vip_config"], bump = vip_config.bump)]
+    pub vip_config: Account<'info, VipConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump,
+        has_one = player
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+    pub player: Signer<'info>,
+
+    /// CHECK: Optional admiral_token StakeAccount, validated in read_staked_admiral
+    pub stake_account: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+#[instruction(season: u16)]
+pub struct CreateBattlePass<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + BattlePass::INIT_SPACE,
+        seeds = [b"battle_pass", season.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub battle_pass: Account<'info, BattlePass>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateBattlePass<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"battle_pass", battle_pass.season.to_le_bytes().as_ref()],
+        bump = battle_pass.bump
+    )]
+    pub battle_pass: Account<'info, BattlePass>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct JoinBattlePass<'info> {
+    #[account(
+        seeds = [b"battle_pass", battle_pass.season.to_le_bytes().as_ref()],
+        bump = battle_pass.bump
+    )]
+    pub battle_pass: Account<'info, BattlePass>,
+
+    #[account(
+        init,
+        payer = player,
+        space = 8 + PassProgress::INIT_SPACE,
+        seeds = [b"pass_progress", battle_pass.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub pass_progress: Account<'info, PassProgress>,
+
+    #[account(seeds = [b"player_profile", player.key().as_ref()], bump, has_one = player)]
+    pub player_profile: Account<'info, PlayerProfile>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnlockPremiumPass<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        seeds = [b"battle_pass", battle_pass.season.to_le_bytes().as_ref()],
+        bump = battle_pass.bump
+    )]
+    pub battle_pass: Account<'info, BattlePass>,
+
+    #[account(
+        mut,
+        seeds = [b"pass_progress", battle_pass.key().as_ref(), player.key().as_ref()],
+        bump = pass_progress.bump
+    )]
+    pub pass_progress: Account<'info, PassProgress>,
+
+    pub player: Signer<'info>,
+
+    #[account(
+        address = battle_pass.premium_mint @ PlayerRegistryError::InvalidBattlePass,
+        constraint = mint.key() == registry_config.admiral_mint @ PlayerRegistryError::InvalidBattlePass,
+        mint::token_program = token_program
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = player,
+        token::token_program = token_program
+    )]
+    pub player_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = registry_config.treasury_authority,
+        token::token_program = token_program
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPassTier<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        seeds = [b"battle_pass", battle_pass.season.to_le_bytes().as_ref()],
+        bump = battle_pass.bump
+    )]
+    pub battle_pass: Account<'info, BattlePass>,
+
+    #[account(
+        mut,
+        seeds = [b"pass_progress", battle_pass.key().as_ref(), player.key().as_ref()],
+        bump = pass_progress.bump
+    )]
+    pub pass_progress: Account<'info, PassProgress>,
+
+    #[account(
+        mut,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump,
+        has_one = player
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
+    // Receives item rewards
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = 8 + Inventory::INIT_SPACE,
+        seeds = [b"inventory", player.key().as_ref()],
+        bump
+    )]
+    pub inventory: Account<'info, Inventory>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    // Achievement rules evaluated after the instruction
+    #[account(seeds = [b"achievement_rules"], bump = achievement_rules.bump)]
+    pub achievement_rules: Account<'info, AchievementRules>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GrantItem<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        seeds = [b"shop_item", shop_item.item_id.to_le_bytes().as_ref()],
+        bump = shop_item.bump
+    )]
+    pub shop_item: Account<'info, ShopItem>,
+
+    #[account(
+        init_if_needed,
+        payer = game_server,
+        space = 8 + Inventory::INIT_SPACE,
+        seeds = [b"inventory", player.key().as_ref()],
+        bump
+    )]
+    pub inventory: Account<'info, Inventory>,
+    /// CHECK: Inventory owner, only used to derive the inventory PDA
+    pub player: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), game_server.key().as_ref()],
+        bump = game_server_roles.bump
+    )]
+    pub game_server_roles: Option<Account<'info, Roles>>,
+    #[account(mut)]
+    pub game_server: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(table_id: u16)]
+pub struct CreateLootTable<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + LootTable::INIT_SPACE,
+        seeds = [b"loot_table", table_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub loot_table: Account<'info, LootTable>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateLootTable<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"loot_table", loot_table.table_id.to_le_bytes().as_ref()],
+        bump = loot_table.bump
+    )]
+    pub loot_table: Account<'info, LootTable>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OpenChest<'info> {
+    #[account(
+        seeds = [b"loot_table", loot_table.table_id.to_le_bytes().as_ref()],
+        bump = loot_table.bump
+    )]
+    pub loot_table: Account<'info, LootTable>,
+
+    #[account(
+        mut,
+        seeds = [b"inventory", player.key().as_ref()],
+        bump = inventory.bump
+    )]
+    pub inventory: Account<'info, Inventory>,
+
+    #[account(
+        init,
+        payer = player,
+        space = 8 + ChestOpening::INIT_SPACE,
+        seeds = [b"chest_opening", loot_table.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub chest_opening: Account<'info, ChestOpening>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevealChest<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        seeds = [b"loot_table", loot_table.table_id.to_le_bytes().as_ref()],
+        bump = loot_table.bump
+    )]
+    pub loot_table: Account<'info, LootTable>,
+
+    #[account(
+        mut,
+        seeds = [b"chest_opening", loot_table.key().as_ref(), player.key().as_ref()],
+        bump = chest_opening.bump
+    )]
+    pub chest_opening: Account<'info, ChestOpening>,
+
+    #[account(
+        mut,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump,
+        has_one = player
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
+    #[account(
+        mut,
+        seeds = [b"inventory", player.key().as_ref()],
+        bump = inventory.bump
+    )]
+    pub inventory: Account<'info, Inventory>,
+
+    pub player: Signer<'info>,
+
+    // Achievement rules evaluated after the instruction
+    #[account(seeds = [b"achievement_rules"], bump = achievement_rules.bump)]
+    pub achievement_rules: Account<'info, AchievementRules>,
+
+    /// CHECK: SlotHashes sysvar, read in find_slot_hash
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseChestOpening<'info> {
+    #[account(
+        mut,
+        close = player,
+        seeds = [b"chest_opening", chest_opening.loot_table.as_ref(), player.key().as_ref()],
+        bump = chest_opening.bump
+    )]
+    pub chest_opening: Account<'info, ChestOpening>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetAchievementRules<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"achievement_rules"],
+        bump = achievement_rules.bump
+    )]
+    pub achievement_rules: Account<'info, AchievementRules>,
+
+    #[account(
+        seeds = [b"roles", registry_config.key().as_ref(), authority.key().as_ref()],
+        bump = authority_roles.bump
+    )]
+    pub authority_roles: Option<Account<'info, Roles>>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EvaluateAchievements<'info> {
+    #[account(seeds = [b"registry_config"], bump = registry_config.bump)]
+    pub registry_config: Account<'info, RegistryConfig>,
+
+    #[account(seeds = [b"achievement_rules"], bump = achievement_rules.bump)]
+    pub achievement_rules: Account<'info, AchievementRules>,
+
+    #[account(
+        mut,
+        seeds = [b"player_profile", player_profile.player.as_ref()],
+        bump
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+}
+
+#[derive(Accounts)]
+pub struct EscrowMarketItem<'info> {
+    #[account(
+        seeds = [b"shop_item", shop_item.item_id.to_le_bytes().as_ref()],
+        bump = shop_item.bump
+    )]
+    pub shop_item: Account<'info, ShopItem>,
+
+    #[account(
+        mut,
+        seeds = [b"inventory", player.key().as_ref()],
+        bump = inventory.bump
+    )]
+    pub inventory: Account<'info, Inventory>,
+    pub player: Signer<'info>,
+
+    #[account(seeds = [b"market_authority"], bump, seeds::program = MARKETPLACE_PROGRAM_ID)]
+    pub market_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseMarketItem<'info> {
+    #[account(
+        seeds = [b"shop_item", shop_item.item_id.to_le_bytes().as_ref()],
+        bump = shop_item.bump
+    )]
+    pub shop_item: Account<'info, ShopItem>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + Inventory::INIT_SPACE,
+        seeds = [b"inventory", recipient.key().as_ref()],
+        bump
+    )]
+    pub inventory: Account<'info, Inventory>,
+    /// CHECK: Inventory owner, only used to derive the inventory PDA
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"market_authority"], bump, seeds::program = MARKETPLACE_PROGRAM_ID)]
+    pub market_authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Data structures
+#[account]
+#[derive(InitSpace)]
+pub struct RegistryConfig {
+    pub authority: Pubkey,
+    pub pending_authority: Option<Pubkey>,
+    pub treasury_authority: Pubkey,
+    pub pending_treasury_authority: Option<Pubkey>,
+    pub paused_flags: u8,
+    pub stake_pool: Pubkey,
+    pub wager_fee_bps: u16,
+    pub chapter_count: u8,
+    pub levels_per_chapter: u8,
+    pub pirate_mint: Pubkey,
+    pub admiral_mint: Pubkey,
+    pub boss_rewards: [u64; MAX_CHAPTERS as usize],  // $PIRATE for each chapter boss's first defeat
+    pub bump: u8,
+}
+
+impl RegistryConfig {
+    pub fn is_paused(&self, category: u8) -> bool {
+        self.paused_flags & category != 0
+    }
+
+    pub fn mint_for(&self, currency: &TokenType) -> Pubkey {
+        match currency {
+            TokenType::Pirate => self.pirate_mint,
+            TokenType::Admiral => self.admiral_mint,
+        }
+    }
+
+    pub fn max_level(&self) -> u8 {
+        self.chapter_count * self.levels_per_chapter
+    }
+
+    // Zero-based chapter of a level (levels start at 1)
+    pub fn chapter_of(&self, level: u8) -> u8 {
+        level.saturating_sub(1) / self.levels_per_chapter
+    }
+
+    // Boss levels are the last level of each chapter
+    pub fn is_boss_level(&self, level: u8) -> bool {
+        level >= 1 && level <= self.max_level() && level % self.levels_per_chapter == 0
+    }
+
+    // Leaving a chapter needs that chapter's boss defeated
+    pub fn can_advance(&self, from_level: u8, to_level: u8, chapter_bosses_defeated: u8) -> bool {
+        let chapter = self.chapter_of(from_level);
+        self.chapter_of(to_level) == chapter || chapter_bosses_defeated & (1 << chapter) != 0
+    }
+
+    pub fn boss_reward(&self, chapter: u8) -> u64 {
+        self.boss_rewards[chapter as usize]
+    }
+
+    pub fn all_chapters_mask(&self) -> u8 {
+        ((1u16 << self.chapter_count) - 1) as u8
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Roles {
+    pub config: Pubkey,
+    pub member: Pubkey,
+    pub roles: u8,
+    pub bump: u8,
+}
+
+impl Roles {
+    // Admin is reserved to the config authority (the timelock), so role grants
+    // can't be used to skip the delay; Admin-gated checks only pass the authority
+    pub fn has(&self, role: Role) -> bool {
+        role != Role::Admin && self.roles & role.bit() != 0
+    }
+
+    pub fn grant(&mut self, role: Role) {
+        self.roles |= role.bit();
+    }
+
+    pub fn revoke(&mut self, role: Role) {
+        self.roles &= !role.bit();
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PlayerProfile {
+    pub player: Pubkey,
+    pub username: String,
+    pub level: u8,
+    pub score: u64,
+    pub total_score: u64,
+    pub pirate_tokens: u64,
+    pub admiral_tokens: u64,
+    pub total_levels_completed: u32,
+    pub total_bosses_defeated: u32,
+    pub achievements: Vec<String>,
+    pub achievement_count: u32,
+    pub last_daily_claim: i64,
+    pub last_weekly_claim: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub is_active: bool,
+    pub streak_days: u32,
+    pub last_login: i64,
+    pub equipped_skin: Option<Pubkey>,
+    pub equipped_character: SkinCharacter,
+    pub vip_tier: u8,
+    pub chapter_bosses_defeated: u8,  // bit per chapter
+    pub badges_issued: u64,  // bit per achievement position
+}
+
+impl PlayerProfile {
+    // Bit of badges_issued for a recorded achievement
+    pub fn badge_bit(&self, achievement_id: &str) -> Option<u64> {
+        self.achievements
+            .iter()
+            .position(|id| id == achievement_id)
+            .filter(|position| *position < MAX_ACHIEVEMENTS)
+            .map(|position| 1u64 << position)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct VipConfig {
+    #[max_len(MAX_VIP_TIERS)]
+    pub thresholds: Vec<u64>,
+    #[max_len(MAX_VIP_TIERS)]
+    pub multipliers_bps: Vec<u16>,
+    pub bump: u8,
+}
+
+impl VipConfig {
+    // Highest tier whose threshold the stake reaches, 0 below the first threshold
+    pub fn tier_for(&self, staked_admiral: u64) -> u8 {
+        self.thresholds
+            .iter()
+            .take_while(|threshold| staked_admiral >= **threshold)
+            .count() as u8
+    }
+
+    pub fn multiplier_bps(&self, tier: u8) -> u16 {
+        match tier {
+            0 => 10_000,
+            _ => self
+                .multipliers_bps
+                .get(tier as usize - 1)
+                .copied()
+                .unwrap_or(10_000),
+        }
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct AchievementRules {
+    #[max_len(MAX_ACHIEVEMENT_RULES)]
+    pub rules: Vec<AchievementRule>,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct AchievementRule {
+    #[max_len(50)]
+    pub achievement_id: String,
+    pub field: ProfileField,
+    pub comparator: Comparator,
+    pub threshold: u64,
+    pub reward: u64,  // $PIRATE credited to the profile
+}
+
+impl AchievementRules {
+    // Rules the registry starts with: a first cleared chapter, every chapter in the
+    // current layout, every boss of the original five chapters and a $PIRATE balance
+    pub fn defaults() -> Vec<AchievementRule> {
+        vec![
+            AchievementRule {
+                achievement_id: CHAPTER_MASTER_ACHIEVEMENT.to_string(),
+                field: ProfileField::ChaptersCompleted,
+                comparator: Comparator::GreaterOrEqual,
+                threshold: 1,
+                reward: CHAPTER_MASTER_REWARD,
+            },
+            AchievementRule {
+                achievement_id: GAME_MASTER_ACHIEVEMENT.to_string(),
+                field: ProfileField::ChaptersRemaining,
+                comparator: Comparator::Equal,
+                threshold: 0,
+                reward: GAME_MASTER_REWARD,
+            },
+            AchievementRule {
+                achievement_id: BOSS_SLAYER_ACHIEVEMENT.to_string(),
+                field: ProfileField::TotalBossesDefeated,
+                comparator: Comparator::GreaterOrEqual,
+                threshold: BOSS_SLAYER_BOSSES,
+                reward: BOSS_SLAYER_REWARD,
+            },
+            AchievementRule {
+                achievement_id: TOKEN_COLLECTOR_ACHIEVEMENT.to_string(),
+                field: ProfileField::PirateTokens,
+                comparator: Comparator::GreaterOrEqual,
+                threshold: TOKEN_COLLECTOR_BALANCE,
+                reward: TOKEN_COLLECTOR_REWARD,
+            },
+        ]
+    }
+}
+
+impl AchievementRule {
+    pub fn is_met(&self, player_profile: &PlayerProfile, registry_config: &RegistryConfig) -> bool {
+        let value = self.field.read(player_profile, registry_config);
+        match self.comparator {
+            Comparator::GreaterOrEqual => value >= self.threshold,
+            Comparator::GreaterThan => value > self.threshold,
+            Comparator::Equal => value == self.threshold,
+        }
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Skin {
+    pub mint: Pubkey,
+    pub character: SkinCharacter,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Tournament {
+    pub tournament_id: u64,
+    pub mint: Pubkey,
+    pub currency: TokenType,
+    pub entry_fee: u64,
+    pub min_entrants: u16,
+    pub max_entrants: u16,
+    pub entrant_count: u16,
+    pub starts_at: i64,
+    pub ends_at: i64,
+    #[max_len(MAX_PAYOUT_PLACES)]
+    pub payout_bps: Vec<u16>,
+    #[max_len(MAX_PAYOUT_PLACES)]
+    pub winners: Vec<Pubkey>,
+    #[max_len(MAX_PAYOUT_PLACES)]
+    pub claimed: Vec<bool>,
+    pub prize_pool: u64,
+    pub status: TournamentStatus,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+impl Tournament {
+    // First place also takes the rounding remainder so the vault is fully paid out
+    pub fn payout_for(&self, rank: usize) -> u64 {
+        let share = |bps: u16| (self.prize_pool as u128 * bps as u128 / 10_000) as u64;
+        if rank == 0 {
+            let others: u64 = self.payout_bps[1..].iter().map(|bps| share(*bps)).sum();
+            self.prize_pool - others
+        } else {
+            share(self.payout_bps[rank])
+        }
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct TournamentEntry {
+    pub tournament: Pubkey,
+    pub player: Pubkey,
+    pub amount_paid: u64,  // net of any transfer fee
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Match {
+    pub match_id: u64,
+    pub challenger: Pubkey,
+    pub invited: Option<Pubkey>,
+    pub opponent: Pubkey,
+    pub mint: Pubkey,
+    pub stake: u64,
+    pub fee_bps: u16,
+    pub status: MatchStatus,
+    pub created_at: i64,
+    pub accepted_at: i64,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct QuestDefinition {
+    pub quest_id: u16,
+    #[max_len(32)]
+    pub name: String,
+    pub cadence: QuestCadence,
+    pub objective: QuestObjective,
+    pub target: u32,
+    pub reward_token: TokenType,
+    pub reward_amount: u64,
+    pub rotation_length: u16,  // quests in this rotation, 1 = every period
+    pub rotation_slot: u16,
+    pub active: bool,
+    pub bump: u8,
+}
+
+impl QuestDefinition {
+    pub fn apply(&mut self, args: QuestArgs) {
+        self.name = args.name;
+        self.cadence = args.cadence;
+        self.objective = args.objective;
+        self.target = args.target;
+        self.reward_token = args.reward_token;
+        self.reward_amount = args.reward_amount;
+        self.rotation_length = args.rotation_length;
+        self.rotation_slot = args.rotation_slot;
+        self.active = args.active;
+    }
+
+    pub fn is_live(&self, now: i64) -> bool {
+        let period = self.cadence.period_index(now);
+        self.active && period % self.rotation_length as u64 == self.rotation_slot as u64
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct QuestProgress {
+    pub quest: Pubkey,
+    pub player: Pubkey,
+    pub period: u64,
+    pub progress: u32,
+    pub claimed: bool,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct BattlePass {
+    pub season: u16,
+    #[max_len(32)]
+    pub name: String,
+    pub starts_at: i64,
+    pub ends_at: i64,
+    pub xp_per_tier: u32,
+    pub xp_per_level: u32,
+    pub xp_per_quest: u32,
+    pub premium_mint: Pubkey,
+    pub premium_price: u64,
+    #[max_len(MAX_PASS_TIERS)]
+    pub tiers: Vec<PassTier>,
+    pub bump: u8,
+}
+
+impl BattlePass {
+    pub fn apply(&mut self, args: BattlePassArgs) {
+        self.name = args.name;
+        self.starts_at = args.starts_at;
+        self.ends_at = args.ends_at;
+        self.xp_per_tier = args.xp_per_tier;
+        self.xp_per_level = args.xp_per_level;
+        self.xp_per_quest = args.xp_per_quest;
+        self.premium_mint = args.premium_mint;
+        self.premium_price = args.premium_price;
+        self.tiers = args.tiers;
+    }
+
+    pub fn is_live(&self, now: i64) -> bool {
+        now >= self.starts_at && now < self.ends_at
+    }
+
+    // Tier 0 is reached after the first xp_per_tier
+    pub fn xp_required(&self, tier: u16) -> u64 {
+        (tier as u64 + 1).saturating_mul(self.xp_per_tier as u64)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct PassTier {
+    pub free: PassReward,
+    pub premium: PassReward,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PassProgress {
+    pub battle_pass: Pubkey,
+    pub player: Pubkey,
+    pub xp: u64,
+    pub premium: bool,
+    pub free_claimed: u64,     // bit per tier
+    pub premium_claimed: u64,  // bit per tier
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct LootTable {
+    pub table_id: u16,
+    #[max_len(32)]
+    pub name: String,
+    pub chest_item_id: u16,  // inventory item spent to open a chest
+    #[max_len(MAX_LOOT_ENTRIES)]
+    pub entries: Vec<LootEntry>,
+    pub total_weight: u32,
+    pub version: u32,  // bumped on every update; openings roll against the version they committed to
+    pub active: bool,
+    pub bump: u8,
+}
+
+impl LootTable {
+    pub fn apply(&mut self, args: LootTableArgs) {
+        self.name = args.name;
+        self.chest_item_id = args.chest_item_id;
+        self.total_weight = args.entries.iter().map(|entry| entry.weight).sum();
+        self.entries = args.entries;
+        self.active = args.active;
+        self.version = self.version.wrapping_add(1);
+    }
+
+    // Index of the entry whose weight range contains roll (0 <= roll < total_weight)
+    pub fn entry_for(&self, roll: u64) -> usize {
+        let mut cumulative = 0u64;
+        for (index, entry) in self.entries.iter().enumerate() {
+            cumulative += entry.weight as u64;
+            if roll < cumulative {
+                return index;
+            }
+        }
+        self.entries.len() - 1
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct LootEntry {
+    pub weight: u32,
+    pub reward: LootReward,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ChestOpening {
+    pub player: Pubkey,
+    pub loot_table: Pubkey,
+    pub loot_table_version: u32,
+    pub commitment: [u8; 32],
+    pub reveal_slot: u64,
+    pub status: ChestStatus,
+    pub result: Option<LootReward>,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ShopItem {
+    pub item_id: u16,
+    #[max_len(32)]
+    pub name: String,
+    pub kind: ItemKind,
+    pub currency: TokenType,
+    pub mint: Pubkey,
+    pub price: u64,
+    pub payment: PaymentMode,
+    pub max_supply: u32,        // 0 = unlimited
+    pub sold: u32,
+    pub per_player_limit: u32,  // 0 = unlimited
+    pub starts_at: i64,
+    pub ends_at: i64,           // 0 = no end
+    pub active: bool,
+    pub bump: u8,
+}
+
+impl ShopItem {
+    pub fn apply(&mut self, args: ShopItemArgs) {
+        self.name = args.name;
+        self.kind = args.kind;
+        self.currency = args.currency;
+        self.mint = args.mint;
+        self.price = args.price;
+        self.payment = args.payment;
+        self.max_supply = args.max_supply;
+        self.per_player_limit = args.per_player_limit;
+        self.starts_at = args.starts_at;
+        self.ends_at = args.ends_at;
+        self.active = args.active;
+    }
+
+    pub fn is_on_sale(&self, now: i64) -> bool {
+        self.active && now >= self.starts_at && (self.ends_at == 0 || now < self.ends_at)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Inventory {
+    pub player: Pubkey,
+    #[max_len(MAX_INVENTORY_ITEMS)]
+    pub items: Vec<InventoryEntry>,
+    pub bump: u8,
+}
+
+impl Inventory {
+    pub fn entry_mut(&mut self, item_id: u16) -> Result<&mut InventoryEntry> {
+        if let Some(index) = self.items.iter().position(|entry| entry.item_id == item_id) {
+            return Ok(&mut self.items[index]);
+        }
+
+        require!(
+            self.items.len() < MAX_INVENTORY_ITEMS,
+            PlayerRegistryError::InventoryFull
+        );
+        self.items.push(InventoryEntry {
+            item_id,
+            quantity: 0,
+            purchased: 0,
+        });
+        Ok(self.items.last_mut().unwrap())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct InventoryEntry {
+    pub item_id: u16,
+    pub quantity: u32,
+    pub purchased: u32,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ShopItemArgs {
+    pub name: String,
+    pub kind: ItemKind,
+    pub currency: TokenType,
+    pub mint: Pubkey,
+    pub price: u64,
+    pub payment: PaymentMode,
+    pub max_supply: u32,
+    pub per_player_limit: u32,
+    pub starts_at: i64,
+    pub ends_at: i64,
+    pub active: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct QuestArgs {
+    pub name: String,
+    pub cadence: QuestCadence,
+    pub objective: QuestObjective,
+    pub target: u32,
+    pub reward_token: TokenType,
+    pub reward_amount: u64,
+    pub rotation_length: u16,
+    pub rotation_slot: u16,
+    pub active: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BattlePassArgs {
+    pub name: String,
+    pub starts_at: i64,
+    pub ends_at: i64,
+    pub xp_per_tier: u32,
+    pub xp_per_level: u32,
+    pub xp_per_quest: u32,
+    pub premium_mint: Pubkey,
+    pub premium_price: u64,
+    pub tiers: Vec<PassTier>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LootTableArgs {
+    pub name: String,
+    pub chest_item_id: u16,
+    pub entries: Vec<LootEntry>,
+    pub active: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TournamentArgs {
+    pub currency: TokenType,
+    pub entry_fee: u64,
+    pub min_entrants: u16,
+    pub max_entrants: u16,
+    pub starts_at: i64,
+    pub ends_at: i64,
+    pub payout_bps: Vec<u16>,
+}
+
+// Return data of the quote_* view instructions
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LevelRewardQuote {
+    pub level: u8,
+    pub base_reward: u64,
+    pub boost_bps: u16,
+    pub vip_multiplier_bps: u16,
+    pub reward: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DailyRewardQuote {
+    pub streak_days: u32,
+    pub base_reward: u64,
+    pub boost_bps: u16,
+    pub vip_multiplier_bps: u16,
+    pub reward: u64,
+    pub next_claim_at: i64,
+    pub claimable: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct WeeklyRewardQuote {
+    pub reward: u64,
+    pub next_claim_at: i64,
+    pub claimable: bool,
+}
+
+// Leading fields of admiral_token's StakeAccount
+#[derive(AnchorDeserialize)]
+pub struct StakeAccountPrefix {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+// Enums
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum TokenType {
+    Pirate,
+    Admiral,
+}
+
+impl TokenType {
+    pub fn to_string(&self) -> String {
+        match self {
+            TokenType::Pirate => "PIRATE".to_string(),
+            TokenType::Admiral => "ADMIRAL".to_string(),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AuthorityKind {
+    Admin,
+    Treasury,
+}
+
+impl AuthorityKind {
+    pub fn to_string(&self) -> String {
+        match self {
+            AuthorityKind::Admin => "Admin".to_string(),
+            AuthorityKind::Treasury => "Treasury".to_string(),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Admin,
+    Minter,
+    Pauser,
+    GameServer,
+    Moderator,
+    Treasurer,
+}
+
+impl Role {
+    pub fn bit(&self) -> u8 {
+        1 << (*self as u8)
+    }
+
+    pub fn to_string(&self) -> String {
+        match self {
+            Role::Admin => "Admin".to_string(),
+            Role::Minter => "Minter".to_string(),
+            Role::Pauser => "Pauser".to_string(),
+            Role::GameServer => "GameServer".to_string(),
+            Role::Moderator => "Moderator".to_string(),
+            Role::Treasurer => "Treasurer".to_string(),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum ItemKind {
+    Consumable,
+    Permanent,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum PaymentMode {
+    Burn,
+    Treasury,
+}
+
+// Character sprite sets under Sprites/
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum SkinCharacter {
+    BombGuy,
+    BaldPirate,
+    Cucumber,
+    BigGuy,
+    Captain,
+    Whale,
+}
+
+impl SkinCharacter {
+    pub fn to_string(&self) -> String {
+        match self {
+            SkinCharacter::BombGuy => "Bomb Guy".to_string(),
+            SkinCharacter::BaldPirate => "Bald Pirate".to_string(),
+            SkinCharacter::Cucumber => "Cucumber".to_string(),
+            SkinCharacter::BigGuy => "Big Guy".to_string(),
+            SkinCharacter::Captain => "Captain".to_string(),
+            SkinCharacter::Whale => "Whale".to_string(),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum TournamentStatus {
+    Open,
+    Settled,
+    Cancelled,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum MatchStatus {
+    Open,
+    Active,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum MatchResult {
+    ChallengerWins,
+    OpponentWins,
+    Draw,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum QuestCadence {
+    Daily,
+    Weekly,
+}
+
+impl QuestCadence {
+    // Days or weeks since the unix epoch; progress resets when it changes
+    pub fn period_index(&self, now: i64) -> u64 {
+        let interval = match self {
+            QuestCadence::Daily => DAILY_CLAIM_INTERVAL,
+            QuestCadence::Weekly => WEEKLY_CLAIM_INTERVAL,
+        };
+        (now.max(0) / interval) as u64
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum QuestObjective {
+    CompleteLevels,
+    DefeatBosses,
+    KillEnemies { enemy: EnemyKind },
+}
+
+// Enemy sprite sets under Sprites/
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum EnemyKind {
+    BaldPirate,
+    Cucumber,
+    BigGuy,
+    Captain,
+    Whale,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum PassReward {
+    None,
+    Tokens { token_type: TokenType, amount: u64 },
+    Item { item_id: u16, quantity: u32 },
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum LootReward {
+    Tokens { token_type: TokenType, amount: u64 },
+    Item { item_id: u16, quantity: u32 },
+    Skin { character: SkinCharacter },
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum ChestStatus {
+    Pending,
+    AwaitingSkin,
+    Fulfilled,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum ProfileField {
+    Level,
+    TotalScore,
+    PirateTokens,
+    AdmiralTokens,
+    TotalLevelsCompleted,
+    TotalBossesDefeated,
+    ChaptersCompleted,
+    AchievementCount,
+    StreakDays,
+    ChaptersRemaining,  // chapters of the current layout whose boss is still alive
+}
+
+impl ProfileField {
+    pub fn read(&self, player_profile: &PlayerProfile, registry_config: &RegistryConfig) -> u64 {
+        match self {
+            ProfileField::Level => player_profile.level as u64,
+            ProfileField::TotalScore => player_profile.total_score,
+            ProfileField::PirateTokens => player_profile.pirate_tokens,
+            ProfileField::AdmiralTokens => player_profile.admiral_tokens,
+            ProfileField::TotalLevelsCompleted => player_profile.total_levels_completed as u64,
+            ProfileField::TotalBossesDefeated => player_profile.total_bosses_defeated as u64,
+            ProfileField::ChaptersCompleted => player_profile.chapter_bosses_defeated.count_ones() as u64,
+            ProfileField::AchievementCount => player_profile.achievement_count as u64,
+            ProfileField::StreakDays => player_profile.streak_days as u64,
+            ProfileField::ChaptersRemaining => {
+                let completed =
+                    player_profile.chapter_bosses_defeated & registry_config.all_chapters_mask();
+                (registry_config.chapter_count as u32 - completed.count_ones()) as u64
+            },
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum Comparator {
+    GreaterOrEqual,
+    GreaterThan,
+    Equal,
+}
+
+// Events
+#[event]
+pub struct AuthorityTransferProposed {
+    pub kind: AuthorityKind,
+    pub current_authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub kind: AuthorityKind,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct PauseStateChanged {
+    pub pauser: Pubkey,
+    pub paused_flags: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoleGranted {
+    pub config: Pubkey,
+    pub member: Pubkey,
+    pub role: Role,
+    pub granted_by: Pubkey,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub config: Pubkey,
+    pub member: Pubkey,
+    pub role: Role,
+    pub revoked_by: Pubkey,
+}
+
+#[event]
+pub struct ShopItemUpdated {
+    pub item_id: u16,
+    pub price: u64,
+    pub currency: TokenType,
+    pub max_supply: u32,
+    pub active: bool,
+}
+
+#[event]
+pub struct ItemPurchased {
+    pub player: Pubkey,
+    pub item_id: u16,
+    pub quantity: u32,
+    pub total_price: u64,
+    pub currency: TokenType,
+    pub payment: PaymentMode,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ItemConsumed {
+    pub player: Pubkey,
+    pub item_id: u16,
+    pub quantity: u32,
+    pub remaining: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BadgeIssued {
+    pub player: Pubkey,
+    pub achievement_id: String,
+    pub badge_mint: Pubkey,
+    pub backfilled: bool,
+}
+
+#[event]
+pub struct SkinMinted {
+    pub mint: Pubkey,
+    pub character: SkinCharacter,
+    pub recipient: Pubkey,
+}
+
+#[event]
+pub struct SkinEquipped {
+    pub player: Pubkey,
+    pub mint: Option<Pubkey>,
+    pub character: SkinCharacter,
+}
+
+#[event]
+pub struct TournamentCreated {
+    pub tournament: Pubkey,
+    pub tournament_id: u64,
+    pub currency: TokenType,
+    pub entry_fee: u64,
+    pub max_entrants: u16,
+    pub starts_at: i64,
+    pub ends_at: i64,
+}
+
+#[event]
+pub struct TournamentEntered {
+    pub tournament: Pubkey,
+    pub player: Pubkey,
+    pub entrant_count: u16,
+}
+
+#[event]
+pub struct TournamentSettled {
+    pub tournament: Pubkey,
+    pub winners: Vec<Pubkey>,
+    pub prize_pool: u64,
+}
+
+#[event]
+pub struct TournamentPayout {
+    pub tournament: Pubkey,
+    pub winner: Pubkey,
+    pub rank: u8,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TournamentCancelled {
+    pub tournament: Pubkey,
+    pub entrant_count: u16,
+}
+
+#[event]
+pub struct TournamentRefunded {
+    pub tournament: Pubkey,
+    pub player: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct MatchCreated {
+    pub wager_match: Pubkey,
+    pub challenger: Pubkey,
+    pub invited: Option<Pubkey>,
+    pub stake: u64,
+    pub fee_bps: u16,
+}
+
+#[event]
+pub struct MatchAccepted {
+    pub wager_match: Pubkey,
+    pub opponent: Pubkey,
+    pub settle_deadline: i64,
+}
+
+#[event]
+pub struct MatchClosed {
+    pub wager_match: Pubkey,
+    pub result: Option<MatchResult>,
+    pub winner: Option<Pubkey>,
+    pub house_fee: u64,
+    pub timed_out: bool,
+}
+
+#[event]
+pub struct QuestUpdated {
+    pub quest_id: u16,
+    pub cadence: QuestCadence,
+    pub objective: QuestObjective,
+    pub target: u32,
+    pub reward_token: TokenType,
+    pub reward_amount: u64,
+    pub active: bool,
+}
+
+#[event]
+pub struct QuestProgressed {
+    pub player: Pubkey,
+    pub quest_id: u16,
+    pub period: u64,
+    pub progress: u32,
+    pub target: u32,
+}
+
+#[event]
+pub struct QuestClaimed {
+    pub player: Pubkey,
+    pub quest_id: u16,
+    pub period: u64,
+    pub reward_token: TokenType,
+    pub reward_amount: u64,
+}
+
+#[event]
+pub struct VipTiersUpdated {
+    pub thresholds: Vec<u64>,
+    pub multipliers_bps: Vec<u16>,
+}
+
+#[event]
+pub struct VipTierChanged {
+    pub player: Pubkey,
+    pub previous_tier: u8,
+    pub new_tier: u8,
+    pub staked_admiral: u64,
+}
+
+#[event]
+pub struct BattlePassUpdated {
+    pub season: u16,
+    pub tier_count: u16,
+    pub xp_per_tier: u32,
+    pub premium_price: u64,
+    pub starts_at: i64,
+    pub ends_at: i64,
+}
+
+#[event]
+pub struct PassXpEarned {
+    pub player: Pubkey,
+    pub season: u16,
+    pub xp: u32,
+    pub total_xp: u64,
+}
+
+#[event]
+pub struct PremiumPassUnlocked {
+    pub player: Pubkey,
+    pub season: u16,
+    pub price: u64,
+}
+
+#[event]
+pub struct PassTierClaimed {
+    pub player: Pubkey,
+    pub season: u16,
+    pub tier: u16,
+    pub premium: bool,
+    pub reward: PassReward,
+}
+
+#[event]
+pub struct ItemGranted {
+    pub player: Pubkey,
+    pub item_id: u16,
+    pub quantity: u32,
+    pub granted_by: Pubkey,
+}
+
+#[event]
+pub struct LootTableUpdated {
+    pub table_id: u16,
+    pub chest_item_id: u16,
+    pub weights: Vec<u32>,
+    pub total_weight: u32,
+    pub version: u32,
+    pub active: bool,
+}
+
+#[event]
+pub struct ChestOpened {
+    pub player: Pubkey,
+    pub table_id: u16,
+    pub roll: u64,
+    pub total_weight: u32,
+    pub entry_index: u8,
+    pub reward: LootReward,
+}
+
+#[event]
+pub struct AchievementUnlocked {
+    pub player: Pubkey,
+    pub achievement_id: String,
+    pub reward: u64,
+}
+
+#[event]
+pub struct AchievementRulesUpdated {
+    pub achievement_ids: Vec<String>,
+}
+
+#[event]
+pub struct MarketItemMoved {
+    pub player: Pubkey,
+    pub item_id: u16,
+    pub quantity: u32,
+    pub remaining: u32,
+    pub escrowed: bool,
+}
+
+// Error codes
+#[error_code]
+pub enum PlayerRegistryError {
     #[msg("Invalid username length")]
     InvalidUsername,
     #[msg("Invalid level progression")]
@@ -499,4 +6482,473 @@ pub enum PlayerRegistryError {
     WeeklyRewardNotReady,
     #[msg("Insufficient tokens")]
     InsufficientTokens,
+    #[msg("This operation is paused")]
+    Paused,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Signer is not the pending authority")]
+    NotPendingAuthority,
+    #[msg("Invalid stake account")]
+    InvalidStakeAccount,
+    #[msg("Signer is missing the required role")]
+    MissingRole,
+    #[msg("Invalid shop item")]
+    InvalidShopItem,
+    #[msg("Quantity must be greater than zero")]
+    InvalidQuantity,
+    #[msg("Item is not on sale")]
+    ItemNotAvailable,
+    #[msg("Item is out of stock")]
+    OutOfStock,
+    #[msg("Per-player purchase limit reached")]
+    PurchaseLimitExceeded,
+    #[msg("Inventory is full")]
+    InventoryFull,
+    #[msg("Not enough of this item in the inventory")]
+    InsufficientItems,
+    #[msg("Item is not a consumable")]
+    ItemNotConsumable,
+    #[msg("Payment accounts for this item were not provided")]
+    MissingPaymentAccounts,
+    #[msg("Achievement is not recorded on this profile")]
+    AchievementNotRecorded,
+    #[msg("Badge already issued")]
+    BadgeAlreadyIssued,
+    #[msg("Invalid skin data")]
+    InvalidSkinData,
+    #[msg("Player does not hold this skin")]
+    SkinNotOwned,
+    #[msg("This skin is not equipped")]
+    SkinNotEquipped,
+    #[msg("Invalid tournament parameters")]
+    InvalidTournament,
+    #[msg("Payout table must have 1-10 places, no more than the minimum entrants, summing to 100%")]
+    InvalidPayoutTable,
+    #[msg("Tournament is not open for entries")]
+    TournamentClosed,
+    #[msg("Tournament is full")]
+    TournamentFull,
+    #[msg("Tournament has not finished")]
+    TournamentNotFinished,
+    #[msg("Tournament did not reach its minimum entrants")]
+    TournamentNotFilled,
+    #[msg("Rankings don't match the payout table or the entrants")]
+    InvalidRankings,
+    #[msg("Payout already claimed")]
+    PayoutAlreadyClaimed,
+    #[msg("Refund not available")]
+    RefundNotAvailable,
+    #[msg("Wager fee cannot exceed 10%")]
+    InvalidWagerFee,
+    #[msg("Invalid wager")]
+    InvalidWager,
+    #[msg("Match is not open")]
+    MatchNotOpen,
+    #[msg("Match is not active")]
+    MatchNotActive,
+    #[msg("Signer was not invited to this match")]
+    NotInvited,
+    #[msg("Match settlement window has passed")]
+    MatchTimedOut,
+    #[msg("Match settlement window has not passed yet")]
+    MatchNotTimedOut,
+    #[msg("Invalid quest definition")]
+    InvalidQuest,
+    #[msg("Quest is not live this period")]
+    QuestNotActive,
+    #[msg("Quest objective not complete")]
+    QuestNotComplete,
+    #[msg("Quest reward already claimed")]
+    QuestAlreadyClaimed,
+    #[msg("Quest progress is from an earlier period")]
+    QuestExpired,
+    #[msg("Invalid VIP tier configuration")]
+    InvalidVipTiers,
+    #[msg("Invalid battle pass")]
+    InvalidBattlePass,
+    #[msg("Battle pass season has already started")]
+    BattlePassStarted,
+    #[msg("Battle pass season has ended")]
+    BattlePassNotLive,
+    #[msg("Invalid battle pass tier")]
+    InvalidPassTier,
+    #[msg("Not enough pass XP for this tier")]
+    PassTierLocked,
+    #[msg("Pass tier already claimed")]
+    PassTierAlreadyClaimed,
+    #[msg("Premium pass required")]
+    PremiumPassRequired,
+    #[msg("Premium pass already unlocked")]
+    PremiumPassAlreadyUnlocked,
+    #[msg("Invalid loot table")]
+    InvalidLootTable,
+    #[msg("Loot table is not active")]
+    LootTableInactive,
+    #[msg("Chest opening is not in the expected state")]
+    InvalidChestOpening,
+    #[msg("Secret does not match the chest commitment")]
+    InvalidChestSecret,
+    #[msg("Chest reveal slot has not passed yet")]
+    ChestNotReady,
+    #[msg("Chest reveal slot is no longer in SlotHashes")]
+    ChestExpired,
+    #[msg("Invalid chapter layout")]
+    InvalidChapterLayout,
+    #[msg("Defeat the chapter boss to unlock the next chapter")]
+    ChapterBossNotDefeated,
+    #[msg("Boss level is not a reached chapter boss")]
+    InvalidBossLevel,
+    #[msg("Invalid achievement rule")]
+    InvalidAchievementRule,
+    #[msg("Profile holds the maximum number of achievements")]
+    TooManyAchievements,
+    #[msg("Loot table changed after the chest was opened")]
+    LootTableChanged,
+    #[msg("The Admin role belongs to the config authority and can't be granted")]
+    AdminRoleReserved,
+    #[msg("Mint is not the configured mint for this token type")]
+    InvalidTokenMint,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // SlotHashes data for the given slots, newest first, each hashed to its slot
+    fn slot_hashes_data(slots: &[u64]) -> Vec<u8> {
+        let mut data = (slots.len() as u64).to_le_bytes().to_vec();
+        for slot in slots {
+            data.extend_from_slice(&slot.to_le_bytes());
+            data.extend_from_slice(&slot_hash_for(*slot));
+        }
+        data
+    }
+
+    fn slot_hash_for(slot: u64) -> [u8; 32] {
+        hash(&slot.to_le_bytes()).to_bytes()
+    }
+
+    fn registry_with_chapters(chapter_count: u8) -> RegistryConfig {
+        RegistryConfig {
+            authority: Pubkey::new_unique(),
+            pending_authority: None,
+            treasury_authority: Pubkey::new_unique(),
+            pending_treasury_authority: None,
+            paused_flags: 0,
+            stake_pool: Pubkey::new_unique(),
+            wager_fee_bps: 0,
+            chapter_count,
+            levels_per_chapter: DEFAULT_LEVELS_PER_CHAPTER,
+            pirate_mint: Pubkey::new_unique(),
+            admiral_mint: Pubkey::new_unique(),
+            boss_rewards: DEFAULT_BOSS_REWARDS,
+            bump: 255,
+        }
+    }
+
+    fn player_profile() -> PlayerProfile {
+        PlayerProfile {
+            player: Pubkey::new_unique(),
+            username: "player".to_string(),
+            level: 1,
+            score: 0,
+            total_score: 0,
+            pirate_tokens: 0,
+            admiral_tokens: 0,
+            total_levels_completed: 0,
+            total_bosses_defeated: 0,
+            achievements: Vec::new(),
+            achievement_count: 0,
+            last_daily_claim: 0,
+            last_weekly_claim: 0,
+            created_at: 0,
+            updated_at: 0,
+            is_active: true,
+            streak_days: 0,
+            last_login: 0,
+            equipped_skin: None,
+            equipped_character: SkinCharacter::BombGuy,
+            vip_tier: 0,
+            chapter_bosses_defeated: 0,
+            badges_issued: 0,
+        }
+    }
+
+    fn tournament(prize_pool: u64, payout_bps: Vec<u16>) -> Tournament {
+        Tournament {
+            tournament_id: 1,
+            mint: Pubkey::new_unique(),
+            currency: TokenType::Pirate,
+            entry_fee: 100,
+            min_entrants: 2,
+            max_entrants: 100,
+            entrant_count: 10,
+            starts_at: 0,
+            ends_at: 1,
+            winners: vec![Pubkey::new_unique(); payout_bps.len()],
+            claimed: vec![false; payout_bps.len()],
+            payout_bps,
+            prize_pool,
+            status: TournamentStatus::Settled,
+            bump: 255,
+            vault_bump: 254,
+        }
+    }
+
+    fn rotating_quest(cadence: QuestCadence, rotation_length: u16, rotation_slot: u16) -> QuestDefinition {
+        QuestDefinition {
+            quest_id: 1,
+            name: "Test".to_string(),
+            cadence,
+            objective: QuestObjective::CompleteLevels,
+            target: 3,
+            reward_token: TokenType::Pirate,
+            reward_amount: 100,
+            rotation_length,
+            rotation_slot,
+            active: true,
+            bump: 255,
+        }
+    }
+
+    fn loot_table(weights: &[u32]) -> LootTable {
+        LootTable {
+            table_id: 1,
+            name: "Test".to_string(),
+            chest_item_id: 1,
+            entries: weights
+                .iter()
+                .map(|weight| LootEntry {
+                    weight: *weight,
+                    reward: LootReward::Item { item_id: 2, quantity: 1 },
+                })
+                .collect(),
+            total_weight: weights.iter().sum(),
+            version: 1,
+            active: true,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn slot_hash_exact_match() {
+        let data = slot_hashes_data(&[110, 109, 108, 107, 106]);
+        for slot in 106..=110 {
+            assert_eq!(find_slot_hash(&data, slot), Some(slot_hash_for(slot)));
+        }
+    }
+
+    #[test]
+    fn slot_hash_skipped_slot_uses_next_slot() {
+        // 108 and 105 were skipped
+        let data = slot_hashes_data(&[110, 109, 107, 106, 104]);
+        assert_eq!(find_slot_hash(&data, 108), Some(slot_hash_for(109)));
+        assert_eq!(find_slot_hash(&data, 105), Some(slot_hash_for(106)));
+    }
+
+    #[test]
+    fn slot_hash_missing_when_not_reached() {
+        let data = slot_hashes_data(&[110, 109, 108]);
+        assert_eq!(find_slot_hash(&data, 111), None);
+    }
+
+    #[test]
+    fn slot_hash_missing_when_evicted() {
+        let data = slot_hashes_data(&[110, 109, 108]);
+        assert_eq!(find_slot_hash(&data, 107), None);
+        assert_eq!(find_slot_hash(&data, 100), None);
+    }
+
+    #[test]
+    fn slot_hash_rejects_malformed_data() {
+        assert_eq!(find_slot_hash(&[], 100), None);
+        let mut data = slot_hashes_data(&[110, 109]);
+        data.truncate(data.len() - 1);
+        assert_eq!(find_slot_hash(&data, 109), None);
+        assert_eq!(find_slot_hash(&slot_hashes_data(&[]), 100), None);
+    }
+
+    #[test]
+    fn loot_entry_covers_weight_ranges() {
+        let table = loot_table(&[5, 1, 4]);
+        let picks: Vec<usize> =
+            (0..table.total_weight as u64).map(|roll| table.entry_for(roll)).collect();
+        assert_eq!(picks, vec![0, 0, 0, 0, 0, 1, 2, 2, 2, 2]);
+    }
+
+    #[test]
+    fn loot_entry_single_entry() {
+        let table = loot_table(&[7]);
+        assert!((0..7).all(|roll| table.entry_for(roll) == 0));
+    }
+
+    #[test]
+    fn loot_table_update_bumps_version() {
+        let mut table = loot_table(&[1, 2]);
+        table.apply(LootTableArgs {
+            name: "Test".to_string(),
+            chest_item_id: 1,
+            entries: table.entries.clone(),
+            active: true,
+        });
+        assert_eq!(table.version, 2);
+        assert_eq!(table.total_weight, 3);
+    }
+
+    #[test]
+    fn tournament_first_place_takes_remainder() {
+        let tournament = tournament(1_001, vec![5_000, 3_000, 2_000]);
+        assert_eq!(tournament.payout_for(1), 300);
+        assert_eq!(tournament.payout_for(2), 200);
+        assert_eq!(tournament.payout_for(0), 501);
+        let total: u64 = (0..3).map(|rank| tournament.payout_for(rank)).sum();
+        assert_eq!(total, tournament.prize_pool);
+    }
+
+    #[test]
+    fn tournament_single_winner_takes_pool() {
+        assert_eq!(tournament(999, vec![10_000]).payout_for(0), 999);
+    }
+
+    #[test]
+    fn quest_period_boundaries() {
+        assert_eq!(QuestCadence::Daily.period_index(0), 0);
+        assert_eq!(QuestCadence::Daily.period_index(DAILY_CLAIM_INTERVAL - 1), 0);
+        assert_eq!(QuestCadence::Daily.period_index(DAILY_CLAIM_INTERVAL), 1);
+        assert_eq!(QuestCadence::Weekly.period_index(WEEKLY_CLAIM_INTERVAL - 1), 0);
+        assert_eq!(QuestCadence::Weekly.period_index(WEEKLY_CLAIM_INTERVAL * 3), 3);
+        assert_eq!(QuestCadence::Daily.period_index(-1), 0);
+    }
+
+    #[test]
+    fn quest_live_in_its_rotation_slot() {
+        let day = |index: i64| index * DAILY_CLAIM_INTERVAL;
+        let mut quest = rotating_quest(QuestCadence::Daily, 3, 1);
+        let live: Vec<bool> = (0..6).map(|index| quest.is_live(day(index))).collect();
+        assert_eq!(live, vec![false, true, false, false, true, false]);
+
+        quest.active = false;
+        assert!(!quest.is_live(day(1)));
+
+        let every_period = rotating_quest(QuestCadence::Weekly, 1, 0);
+        assert!((0..4).all(|index| every_period.is_live(index * WEEKLY_CLAIM_INTERVAL)));
+    }
+
+    #[test]
+    fn vip_tier_follows_thresholds() {
+        let vip_config = VipConfig {
+            thresholds: vec![1_000, 5_000, 20_000],
+            multipliers_bps: vec![11_000, 12_500, 15_000],
+            bump: 255,
+        };
+        assert_eq!(vip_config.tier_for(0), 0);
+        assert_eq!(vip_config.tier_for(999), 0);
+        assert_eq!(vip_config.tier_for(1_000), 1);
+        assert_eq!(vip_config.tier_for(19_999), 2);
+        assert_eq!(vip_config.tier_for(u64::MAX), 3);
+
+        assert_eq!(vip_config.multiplier_bps(0), 10_000);
+        assert_eq!(vip_config.multiplier_bps(2), 12_500);
+        assert_eq!(vip_config.multiplier_bps(4), 10_000);
+    }
+
+    #[test]
+    fn chapter_layout() {
+        let registry_config = registry_with_chapters(DEFAULT_CHAPTER_COUNT);
+        assert_eq!(registry_config.max_level(), 40);
+        assert_eq!(registry_config.chapter_of(1), 0);
+        assert_eq!(registry_config.chapter_of(8), 0);
+        assert_eq!(registry_config.chapter_of(9), 1);
+        assert_eq!(registry_config.chapter_of(40), 4);
+
+        assert!(registry_config.is_boss_level(8));
+        assert!(registry_config.is_boss_level(40));
+        assert!(!registry_config.is_boss_level(0));
+        assert!(!registry_config.is_boss_level(9));
+        assert!(!registry_config.is_boss_level(48));
+
+        assert_eq!(registry_config.all_chapters_mask(), 0b1_1111);
+        assert_eq!(registry_with_chapters(MAX_CHAPTERS).all_chapters_mask(), u8::MAX);
+        assert_eq!(registry_config.boss_reward(0), DEFAULT_BOSS_REWARDS[0]);
+    }
+
+    #[test]
+    fn next_chapter_needs_its_boss() {
+        let registry_config = registry_with_chapters(DEFAULT_CHAPTER_COUNT);
+        assert!(registry_config.can_advance(7, 8, 0));
+        assert!(!registry_config.can_advance(8, 9, 0));
+        assert!(!registry_config.can_advance(8, 9, 0b10));
+        assert!(registry_config.can_advance(8, 9, 0b1));
+        assert!(!registry_config.can_advance(16, 17, 0b1));
+    }
+
+    #[test]
+    fn chapters_remaining_tracks_layout() {
+        let mut player_profile = player_profile();
+        player_profile.chapter_bosses_defeated = 0b1_1111;
+
+        let registry_config = registry_with_chapters(DEFAULT_CHAPTER_COUNT);
+        assert_eq!(ProfileField::ChaptersCompleted.read(&player_profile, &registry_config), 5);
+        assert_eq!(ProfileField::ChaptersRemaining.read(&player_profile, &registry_config), 0);
+
+        // A chapter added to the layout reopens game_master
+        let registry_config = registry_with_chapters(DEFAULT_CHAPTER_COUNT + 1);
+        assert_eq!(ProfileField::ChaptersRemaining.read(&player_profile, &registry_config), 1);
+    }
+
+    #[test]
+    fn rule_comparators() {
+        let registry_config = registry_with_chapters(DEFAULT_CHAPTER_COUNT);
+        let mut player_profile = player_profile();
+        player_profile.streak_days = 7;
+        let rule = |comparator: Comparator| AchievementRule {
+            achievement_id: "streak".to_string(),
+            field: ProfileField::StreakDays,
+            comparator,
+            threshold: 7,
+            reward: 0,
+        };
+        assert!(rule(Comparator::GreaterOrEqual).is_met(&player_profile, &registry_config));
+        assert!(!rule(Comparator::GreaterThan).is_met(&player_profile, &registry_config));
+        assert!(rule(Comparator::Equal).is_met(&player_profile, &registry_config));
+    }
+
+    #[test]
+    fn achievement_rules_repeat_until_stable() {
+        let registry_config = registry_with_chapters(DEFAULT_CHAPTER_COUNT);
+        // token_collector is checked first, so it can only unlock on a later pass
+        // once the other rewards have been credited
+        let mut rules = AchievementRules::defaults();
+        rules.rotate_right(1);
+        let achievement_rules = AchievementRules { rules, bump: 255 };
+
+        let mut player_profile = player_profile();
+        player_profile.chapter_bosses_defeated = 0b1_1111;
+        player_profile.total_bosses_defeated = 5;
+        player_profile.pirate_tokens = TOKEN_COLLECTOR_BALANCE
+            - CHAPTER_MASTER_REWARD
+            - GAME_MASTER_REWARD
+            - BOSS_SLAYER_REWARD;
+
+        assert_eq!(apply_achievement_rules(&mut player_profile, &achievement_rules, &registry_config), 4);
+        assert_eq!(player_profile.achievement_count, 4);
+        assert_eq!(player_profile.pirate_tokens, TOKEN_COLLECTOR_BALANCE + TOKEN_COLLECTOR_REWARD);
+        assert_eq!(player_profile.achievements.last().unwrap(), TOKEN_COLLECTOR_ACHIEVEMENT);
+
+        // Already unlocked achievements aren't paid again
+        assert_eq!(apply_achievement_rules(&mut player_profile, &achievement_rules, &registry_config), 0);
+        assert_eq!(player_profile.pirate_tokens, TOKEN_COLLECTOR_BALANCE + TOKEN_COLLECTOR_REWARD);
+    }
+
+    #[test]
+    fn achievement_rules_stop_at_a_full_profile() {
+        let registry_config = registry_with_chapters(DEFAULT_CHAPTER_COUNT);
+        let achievement_rules = AchievementRules { rules: AchievementRules::defaults(), bump: 255 };
+        let mut player_profile = player_profile();
+        player_profile.chapter_bosses_defeated = 0b1;
+        player_profile.achievements = (0..MAX_ACHIEVEMENTS).map(|index| index.to_string()).collect();
+
+        assert_eq!(apply_achievement_rules(&mut player_profile, &achievement_rules, &registry_config), 0);
+        assert_eq!(player_profile.pirate_tokens, 0);
+    }
 }
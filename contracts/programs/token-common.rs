@@ -0,0 +1,482 @@
+// Shared by pirate_token and admiral_token, each of which includes this file with
+// #[path] and aliases its error enum as TokenError. Account layouts defined here are
+// identical in both programs.
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{self, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{BatchMint, BatchMinted, BatchTransfer, BatchTransferred, TokenConfig, TokenError};
+
+// Pause categories for the emergency switch
+pub const PAUSE_MINTING: u8 = 1 << 0;
+pub const PAUSE_TRANSFERS: u8 = 1 << 1;
+pub const PAUSE_CLAIMS: u8 = 1 << 2;
+
+// Recipients per batch_mint / batch_transfer. Bounded by the 1.4M CU transaction budget
+// rather than account count (payouts are expected to use an address lookup table):
+// a batched mint is budgeted at ~60k CU per recipient including mint limiter creation,
+// a batched transfer at ~35k CU
+pub const MAX_BATCH_MINT_RECIPIENTS: usize = 20;
+pub const MAX_BATCH_TRANSFER_RECIPIENTS: usize = 32;
+
+// Batch instruction bodies
+pub fn process_batch_mint<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchMint<'info>>,
+    amounts: Vec<u64>,
+) -> Result<()> {
+    require_role(
+        &ctx.accounts.token_config,
+        &ctx.accounts.authority,
+        &ctx.accounts.authority_roles,
+        Role::Minter,
+    )?;
+
+    require!(
+        !ctx.accounts.token_config.is_paused(PAUSE_MINTING),
+        TokenError::Paused
+    );
+    require!(
+        !amounts.is_empty()
+            && amounts.len() <= MAX_BATCH_MINT_RECIPIENTS
+            && ctx.remaining_accounts.len() == amounts.len() * 2,
+        TokenError::InvalidBatch
+    );
+
+    // Validate every recipient before minting anything
+    let mint_key = ctx.accounts.mint.key();
+    let mut recipients = Vec::with_capacity(amounts.len());
+    for (amount, pair) in amounts.iter().zip(ctx.remaining_accounts.chunks(2)) {
+        require!(*amount > 0, TokenError::InvalidAmount);
+        let recipient = load_batch_recipient(&pair[0], &mint_key, &ctx.accounts.token_program)?;
+        recipients.push(recipient.owner);
+    }
+
+    let clock = Clock::get()?;
+    let token_config = &ctx.accounts.token_config;
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"mint_authority",
+        mint_key.as_ref(),
+        &[ctx.bumps.mint_authority],
+    ]];
+
+    let mut total_amount: u64 = 0;
+    for ((amount, pair), recipient) in amounts
+        .iter()
+        .zip(ctx.remaining_accounts.chunks(2))
+        .zip(recipients.iter())
+    {
+        // Limiters are loaded and saved one at a time so a recipient listed twice
+        // is checked against its running total
+        let mut mint_limiter = load_or_create_mint_limiter(
+            &pair[1],
+            &mint_key,
+            recipient,
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
+            clock.unix_timestamp,
+        )?;
+        mint_limiter.record_mint(
+            *amount,
+            clock.unix_timestamp,
+            token_config.mint_limit_per_window,
+            token_config.mint_limit_window_seconds,
+        )?;
+        mint_limiter.exit(&crate::ID)?;
+
+        let cpi_accounts = token_interface::MintTo {
+            mint: ctx.accounts.mint.to_account_info(),
+            to: pair[0].clone(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        token_interface::mint_to(cpi_ctx, *amount)?;
+
+        total_amount = total_amount
+            .checked_add(*amount)
+            .ok_or(TokenError::MathOverflow)?;
+    }
+
+    emit!(BatchMinted {
+        mint: mint_key,
+        authority: ctx.accounts.authority.key(),
+        recipients: amounts.len() as u16,
+        total_amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Batch minted {} tokens to {} recipients", total_amount, amounts.len());
+    Ok(())
+}
+
+pub fn process_batch_transfer<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchTransfer<'info>>,
+    amounts: Vec<u64>,
+) -> Result<()> {
+    require!(
+        !ctx.accounts.token_config.is_paused(PAUSE_TRANSFERS),
+        TokenError::Paused
+    );
+    require!(
+        !amounts.is_empty()
+            && amounts.len() <= MAX_BATCH_TRANSFER_RECIPIENTS
+            && ctx.remaining_accounts.len() == amounts.len(),
+        TokenError::InvalidBatch
+    );
+
+    // Validate every recipient and the total before transferring anything
+    let mint_key = ctx.accounts.mint.key();
+    let mut total_amount: u64 = 0;
+    for (amount, recipient) in amounts.iter().zip(ctx.remaining_accounts.iter()) {
+        require!(*amount > 0, TokenError::InvalidAmount);
+        load_batch_recipient(recipient, &mint_key, &ctx.accounts.token_program)?;
+        total_amount = total_amount
+            .checked_add(*amount)
+            .ok_or(TokenError::MathOverflow)?;
+    }
+    require!(
+        ctx.accounts.from.amount >= total_amount,
+        TokenError::InsufficientBalance
+    );
+
+    for (amount, recipient) in amounts.iter().zip(ctx.remaining_accounts.iter()) {
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.from.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: recipient.clone(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, *amount, ctx.accounts.mint.decimals)?;
+    }
+
+    emit!(BatchTransferred {
+        mint: mint_key,
+        from: ctx.accounts.from.key(),
+        recipients: amounts.len() as u16,
+        total_amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Batch transferred {} tokens to {} recipients", total_amount, amounts.len());
+    Ok(())
+}
+
+// Helper functions
+// Bytes needed for the embedded token metadata TLV entry
+pub fn token_metadata_space(name: &str, symbol: &str, uri: &str) -> usize {
+    // type + length header, update authority, mint, three strings and an empty field list
+    4 + 32 + 32 + (4 + name.len()) + (4 + symbol.len()) + (4 + uri.len()) + 4
+}
+
+// The config authority holds every role; anyone else needs a matching Roles grant
+pub fn require_role(
+    token_config: &TokenConfig,
+    signer: &Signer,
+    roles: &Option<Account<Roles>>,
+    role: Role,
+) -> Result<()> {
+    if signer.key() == token_config.authority {
+        return Ok(());
+    }
+
+    match roles {
+        Some(roles) if roles.has(role) => Ok(()),
+        _ => err!(TokenError::MissingRole),
+    }
+}
+
+// Deserializes a batch recipient and checks it holds this mint under the expected token program
+pub fn load_batch_recipient<'info>(
+    account: &'info AccountInfo<'info>,
+    mint: &Pubkey,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<InterfaceAccount<'info, TokenAccount>> {
+    require_keys_eq!(
+        *account.owner,
+        token_program.key(),
+        TokenError::InvalidBatchRecipient
+    );
+    let recipient = InterfaceAccount::<TokenAccount>::try_from(account)?;
+    require_keys_eq!(recipient.mint, *mint, TokenError::InvalidBatchRecipient);
+    Ok(recipient)
+}
+
+// Loads a recipient's mint limiter from remaining_accounts, creating it on first use
+// the same way mint_tokens' init_if_needed does
+pub fn load_or_create_mint_limiter<'info>(
+    account: &'info AccountInfo<'info>,
+    mint: &Pubkey,
+    recipient: &Pubkey,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    now: i64,
+) -> Result<Account<'info, MintLimiter>> {
+    let (expected, bump) = Pubkey::find_program_address(
+        &[b"mint_limiter", mint.as_ref(), recipient.as_ref()],
+        &crate::ID,
+    );
+    require_keys_eq!(account.key(), expected, TokenError::InvalidMintLimiter);
+
+    if account.data_is_empty() {
+        let space = 8 + MintLimiter::INIT_SPACE;
+        let signer_seeds: &[&[u8]] = &[b"mint_limiter", mint.as_ref(), recipient.as_ref(), &[bump]];
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                system_program::CreateAccount {
+                    from: payer.to_account_info(),
+                    to: account.clone(),
+                },
+                &[signer_seeds],
+            ),
+            Rent::get()?.minimum_balance(space),
+            space as u64,
+            &crate::ID,
+        )?;
+
+        let mint_limiter = MintLimiter {
+            mint: *mint,
+            recipient: *recipient,
+            window_start: now,
+            current_window_minted: 0,
+            previous_window_minted: 0,
+            override_allowance: 0,
+            bump,
+        };
+        mint_limiter.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+    }
+
+    Account::<MintLimiter>::try_from(account)
+}
+
+// CPI burns are attributed to the program whose burn authority PDA signed; a burn
+// with neither account is a plain holder burn
+pub fn resolve_burn_caller(
+    burn_caller: &Option<Account<BurnCaller>>,
+    caller_authority: &Option<Signer>,
+) -> Result<Option<Pubkey>> {
+    match (burn_caller, caller_authority) {
+        (Some(burn_caller), Some(caller_authority)) => {
+            require_keys_eq!(
+                caller_authority.key(),
+                burn_caller.authority,
+                TokenError::InvalidBurnCaller
+            );
+            Ok(Some(burn_caller.program))
+        }
+        (None, None) => Ok(None),
+        _ => err!(TokenError::InvalidBurnCaller),
+    }
+}
+
+// Data structures
+// Mints to one recipient, tracked over a sliding window so a burst can't
+// straddle two fixed windows to mint double the limit
+#[account]
+#[derive(InitSpace)]
+pub struct MintLimiter {
+    pub mint: Pubkey,
+    pub recipient: Pubkey,
+    pub window_start: i64,
+    pub current_window_minted: u64,
+    pub previous_window_minted: u64,
+    pub override_allowance: u64,
+    pub bump: u8,
+}
+
+impl MintLimiter {
+    pub fn record_mint(&mut self, amount: u64, now: i64, limit: u64, window_seconds: i64) -> Result<()> {
+        // Roll the windows forward
+        let elapsed_windows = now.saturating_sub(self.window_start) / window_seconds;
+        if elapsed_windows == 1 {
+            self.previous_window_minted = self.current_window_minted;
+            self.current_window_minted = 0;
+            self.window_start = self.window_start.saturating_add(window_seconds);
+        } else if elapsed_windows > 1 {
+            self.previous_window_minted = 0;
+            self.current_window_minted = 0;
+            self.window_start = now;
+        }
+
+        // Admin overrides are consumed first and don't count against the limit
+        let from_override = amount.min(self.override_allowance);
+        self.override_allowance -= from_override;
+        let limited_amount = amount - from_override;
+
+        if limit > 0 && limited_amount > 0 {
+            // Weight the previous window by how much of it still overlaps the rolling window
+            let into_window = now.saturating_sub(self.window_start) as u128;
+            let previous_weight = (window_seconds as u128).saturating_sub(into_window);
+            let previous_share = (self.previous_window_minted as u128)
+                .saturating_mul(previous_weight)
+                / window_seconds as u128;
+            let rolling_total = previous_share
+                .saturating_add(self.current_window_minted as u128)
+                .saturating_add(limited_amount as u128);
+            require!(
+                rolling_total <= limit as u128,
+                TokenError::MintLimitExceeded
+            );
+        }
+
+        self.current_window_minted = self.current_window_minted.saturating_add(limited_amount);
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Roles {
+    pub config: Pubkey,
+    pub member: Pubkey,
+    pub roles: u8,
+    pub bump: u8,
+}
+
+impl Roles {
+    // Admin is reserved to the config authority (the timelock), so role grants
+    // can't be used to skip the delay; Admin-gated checks only pass the authority
+    pub fn has(&self, role: Role) -> bool {
+        role != Role::Admin && self.roles & role.bit() != 0
+    }
+
+    pub fn grant(&mut self, role: Role) {
+        self.roles |= role.bit();
+    }
+
+    pub fn revoke(&mut self, role: Role) {
+        self.roles &= !role.bit();
+    }
+}
+
+// Burn totals of one program burning through CPI. authority is the program's
+// [b"burn_authority"] PDA, which must sign its burns
+#[account]
+#[derive(InitSpace)]
+pub struct BurnCaller {
+    pub mint: Pubkey,
+    pub program: Pubkey,
+    pub authority: Pubkey,
+    pub total_burned: u64,
+    pub burn_count: u64,
+    pub bump: u8,
+}
+
+impl BurnCaller {
+    pub fn record_burn(&mut self, amount: u64) -> Result<()> {
+        self.total_burned = self.total_burned.checked_add(amount).ok_or(TokenError::MathOverflow)?;
+        self.burn_count = self.burn_count.checked_add(1).ok_or(TokenError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+// Enums
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Admin,
+    Minter,
+    Pauser,
+    GameServer,
+    Moderator,
+    Treasurer,
+}
+
+impl Role {
+    pub fn bit(&self) -> u8 {
+        1 << (*self as u8)
+    }
+
+    pub fn to_string(&self) -> String {
+        match self {
+            Role::Admin => "Admin".to_string(),
+            Role::Minter => "Minter".to_string(),
+            Role::Pauser => "Pauser".to_string(),
+            Role::GameServer => "GameServer".to_string(),
+            Role::Moderator => "Moderator".to_string(),
+            Role::Treasurer => "Treasurer".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: i64 = 3_600;
+
+    fn mint_limiter(window_start: i64) -> MintLimiter {
+        MintLimiter {
+            mint: Pubkey::new_unique(),
+            recipient: Pubkey::new_unique(),
+            window_start,
+            current_window_minted: 0,
+            previous_window_minted: 0,
+            override_allowance: 0,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn roles_never_satisfy_admin() {
+        let mut roles = Roles {
+            config: Pubkey::new_unique(),
+            member: Pubkey::new_unique(),
+            roles: u8::MAX,
+            bump: 255,
+        };
+        assert!(!roles.has(Role::Admin));
+        assert!(roles.has(Role::Minter));
+
+        roles.revoke(Role::Minter);
+        assert!(!roles.has(Role::Minter));
+        assert!(roles.has(Role::Pauser));
+    }
+
+    #[test]
+    fn mint_limit_applies_within_a_window() {
+        let mut limiter = mint_limiter(0);
+        limiter.record_mint(600, 10, 1_000, WINDOW).unwrap();
+        limiter.record_mint(400, 20, 1_000, WINDOW).unwrap();
+        assert_eq!(
+            limiter.record_mint(1, 30, 1_000, WINDOW).unwrap_err(),
+            TokenError::MintLimitExceeded.into()
+        );
+    }
+
+    #[test]
+    fn previous_window_counts_by_overlap() {
+        let mut limiter = mint_limiter(0);
+        limiter.record_mint(1_000, 10, 1_000, WINDOW).unwrap();
+
+        // A quarter into the next window, three quarters of the last one still counts
+        let now = WINDOW + WINDOW / 4;
+        assert_eq!(
+            limiter.record_mint(251, now, 1_000, WINDOW).unwrap_err(),
+            TokenError::MintLimitExceeded.into()
+        );
+        limiter.record_mint(250, now, 1_000, WINDOW).unwrap();
+        assert_eq!(limiter.window_start, WINDOW);
+        assert_eq!(limiter.previous_window_minted, 1_000);
+
+        // After two idle windows nothing carries over
+        limiter.record_mint(1_000, WINDOW * 4, 1_000, WINDOW).unwrap();
+        assert_eq!(limiter.previous_window_minted, 0);
+    }
+
+    #[test]
+    fn overrides_are_spent_before_the_limit() {
+        let mut limiter = mint_limiter(0);
+        limiter.override_allowance = 5_000;
+        limiter.record_mint(5_500, 10, 1_000, WINDOW).unwrap();
+        assert_eq!(limiter.override_allowance, 0);
+        assert_eq!(limiter.current_window_minted, 500);
+    }
+
+    #[test]
+    fn zero_limit_is_unlimited() {
+        let mut limiter = mint_limiter(0);
+        limiter.record_mint(u64::MAX / 2, 10, 0, WINDOW).unwrap();
+    }
+}
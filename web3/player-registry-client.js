@@ -78,7 +78,7 @@ class PlayerRegistryClient {
                     'player_profile',
                     'player'
                 ],
-                args: ['boss_id', 'boss_level', 'reward_amount']
+                args: ['boss_id', 'boss_level']
             },
             claimDailyReward: {
                 name: 'claim_daily_reward',
@@ -266,7 +266,7 @@ class PlayerRegistryClient {
     }
 
     // Record boss defeat
-    async recordBossDefeat(bossId, bossLevel, rewardAmount = 0) {
+    async recordBossDefeat(bossId, bossLevel) {
         try {
             console.log('💀 Recording boss defeat...');
             
@@ -277,8 +277,7 @@ class PlayerRegistryClient {
             const data = Buffer.concat([
                 Buffer.from([3]), // Instruction index for record_boss_defeat
                 Buffer.from(bossId, 'utf8'),
-                Buffer.from([bossLevel]),
-                Buffer.from(createBN(rewardAmount).toArray('le', 8))
+                Buffer.from([bossLevel])
             ]);
 
             const instruction = new solanaWeb3.TransactionInstruction({
@@ -295,7 +294,6 @@ class PlayerRegistryClient {
             
             console.log('✅ Boss defeat recorded!');
             console.log('💀 Boss:', bossId, '(Level', bossLevel + ')');
-            console.log('💰 Reward:', rewardAmount, '$BOOM');
             console.log('🔗 Transaction:', signature);
             
             return { success: true, signature };
@@ -463,9 +461,8 @@ class PlayerRegistryClient {
     async onBossDefeat(bossId, bossLevel) {
         console.log('💀 Boss defeated - updating blockchain...');
         
-        const rewardAmount = 200 + (bossLevel * 50); // Scale reward with boss level
-        
-        return await this.recordBossDefeat(bossId, bossLevel, rewardAmount);
+        // The boss reward comes from the registry's chapter config
+        return await this.recordBossDefeat(bossId, bossLevel);
     }
 
     async onAchievementUnlock(achievementId, achievementName, rewardAmount) {
@@ -35,16 +35,19 @@ class RewardSystem {
         this.weeklyRewards.set('collection', { name: 'Weekly Collection', reward: 2000, claimed: false, lastClaimed: null });
     }
 
-    // Level completion rewards
+    // Level completion rewards, matching player_registry's calculate_level_reward
+    // (before staking boost). Simulate quote_level_reward for the authoritative amount.
     getLevelReward(level) {
         if (level >= 1 && level <= 10) {
-            return 10 + (level - 1) * 4; // 10, 14, 18, 22, 26, 30, 34, 38, 42, 46
+            return 10 + level * 5; // 15, 20, 25, 30, 35, 40, 45, 50, 55, 60
         } else if (level >= 11 && level <= 20) {
-            return 60 + (level - 11) * 10; // 60, 70, 80, 90, 100, 110, 120, 130, 140, 150
+            return 60 + (level - 10) * 10; // 70, 80, 90, 100, 110, 120, 130, 140, 150, 160
         } else if (level >= 21 && level <= 30) {
-            return 200 + (level - 21) * 33; // 200, 233, 266, 299, 332, 365, 398, 431, 464, 497
+            return 160 + (level - 20) * 20; // 180, 200, 220, 240, 260, 280, 300, 320, 340, 360
         } else if (level >= 31 && level <= 40) {
-            return 600 + (level - 31) * 44; // 600, 644, 688, 732, 776, 820, 864, 908, 952, 996
+            return 360 + (level - 30) * 40; // 400, 440, 480, 520, 560, 600, 640, 680, 720, 760
+        } else if (level > 40) {
+            return 1000; // Levels added past the original 40
         }
         return 0;
     }